@@ -0,0 +1,435 @@
+//! This module reads problems from CSV files and prints the results of the command line interface.
+//!
+//! The first row of the file is the header, with one column for each variable as `name:min` (the lower, the better)
+//! or `name:max` (the higher, the better). An optional first column named `alternative` labels the alternatives.
+//! Each other row is an alternative.
+use ndarray::Array1;
+use rustimizer::solver::{
+    Comparison, Diagnosis, Issue, Method, Normalization, Problem, ProblemError, Weights,
+    WeightsError,
+};
+use rustimizer::variables::{
+    Name, NameError, Values, ValuesError, VariableAutoscale, VariableInvertedAutoscale,
+    VariableProperties,
+};
+
+const LABEL_COLUMN: &str = "alternative";
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CliError {
+    #[error("The file has no header")]
+    NoHeader,
+    #[error("Column '{0}' must be 'name:min' or 'name:max'")]
+    InvalidColumn(String),
+    #[error("Row {0} must have one value for each column")]
+    RowSizeMismatch(usize),
+    #[error("Row {0} has an invalid number: '{1}'")]
+    InvalidNumber(usize, String),
+    #[error("Weight '{0}' must be 'name=value'")]
+    InvalidWeight(String),
+    #[error("Unknown method '{0}'")]
+    UnknownMethod(String),
+    #[error("Unknown normalization '{0}'")]
+    UnknownNormalization(String),
+    #[error(transparent)]
+    Name(#[from] NameError),
+    #[error(transparent)]
+    Values(#[from] ValuesError),
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+    #[error(transparent)]
+    Weights(#[from] WeightsError),
+}
+
+/// A problem with the labels of its alternatives.
+#[derive(Debug)]
+pub struct LabelledProblem {
+    pub labels: Vec<String>,
+    pub problem: Problem,
+}
+
+fn parse_column(column: &str) -> Result<(Name, bool), CliError> {
+    match column.rsplit_once(':') {
+        Some((name, "min")) => Ok((Name::try_from(name.trim())?, false)),
+        Some((name, "max")) => Ok((Name::try_from(name.trim())?, true)),
+        _ => Err(CliError::InvalidColumn(column.to_string())),
+    }
+}
+
+pub fn parse_problem(csv: &str) -> Result<LabelledProblem, CliError> {
+    // the rows are numbered as the lines of the file, from 1, blank lines included
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or(CliError::NoHeader)?
+        .1
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let has_labels = header[0] == LABEL_COLUMN;
+    let columns = header[usize::from(has_labels)..]
+        .iter()
+        .map(|column| parse_column(column))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut labels = Vec::new();
+    let mut values: Vec<Vec<f64>> = vec![Vec::new(); columns.len()];
+    for (row, line) in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        if cells.len() != header.len() {
+            return Err(CliError::RowSizeMismatch(row));
+        }
+        let cells = if has_labels {
+            labels.push(cells[0].to_string());
+            &cells[1..]
+        } else {
+            labels.push(format!("#{}", labels.len()));
+            &cells[..]
+        };
+        for (column, cell) in values.iter_mut().zip(cells) {
+            let value = cell
+                .parse()
+                .map_err(|_| CliError::InvalidNumber(row, cell.to_string()))?;
+            column.push(value);
+        }
+    }
+
+    let variables = columns
+        .into_iter()
+        .zip(values)
+        .map(|((name, inverted), values)| {
+            let values = Values::try_from(Array1::from(values))?;
+            let variable: Box<dyn VariableProperties> = if inverted {
+                Box::new(VariableInvertedAutoscale::new(name, values))
+            } else {
+                Box::new(VariableAutoscale::new(name, values))
+            };
+            Ok(variable)
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+    Ok(LabelledProblem {
+        labels,
+        problem: Problem::define(variables)?,
+    })
+}
+
+/// Parses the weights as `name=value`.
+pub fn parse_weights(weights: &[String]) -> Result<Weights, CliError> {
+    let weights = weights
+        .iter()
+        .map(|weight| {
+            let (name, value) = weight
+                .split_once('=')
+                .ok_or_else(|| CliError::InvalidWeight(weight.clone()))?;
+            let value = value
+                .trim()
+                .parse()
+                .map_err(|_| CliError::InvalidWeight(weight.clone()))?;
+            Ok((Name::try_from(name.trim())?, value))
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+    Ok(Weights::try_from(weights)?)
+}
+
+/// Parses the methods by name, all of them when there are none, with the lambda of WASPAS and the distinguishing
+/// coefficient of GRA.
+pub fn parse_methods(methods: &[String], lambda: f64, zeta: f64) -> Result<Vec<Method>, CliError> {
+    let with_parameters = |method| match method {
+        Method::Waspas(_) => Method::Waspas(lambda),
+        Method::GreyRelational(_) => Method::GreyRelational(zeta),
+        method => method,
+    };
+    if methods.is_empty() {
+        return Ok(Method::ALL.into_iter().map(with_parameters).collect());
+    }
+    methods
+        .iter()
+        .map(|method| {
+            Method::ALL
+                .into_iter()
+                .find(|m| m.name() == method.trim())
+                .map(with_parameters)
+                .ok_or_else(|| CliError::UnknownMethod(method.clone()))
+        })
+        .collect()
+}
+
+/// Parses the normalizations by name.
+pub fn parse_normalizations(normalizations: &[String]) -> Result<Vec<Normalization>, CliError> {
+    normalizations
+        .iter()
+        .map(|normalization| {
+            Normalization::ALL
+                .into_iter()
+                .find(|n| n.name() == normalization.trim())
+                .ok_or_else(|| CliError::UnknownNormalization(normalization.clone()))
+        })
+        .collect()
+}
+
+pub fn format_comparison(labels: &[String], comparison: &Comparison, spread: usize) -> String {
+    let methods: Vec<String> = comparison.variants().iter().map(|v| v.name()).collect();
+    let width = labels
+        .iter()
+        .chain(&methods)
+        .map(String::len)
+        .chain([LABEL_COLUMN.len()])
+        .max()
+        .unwrap();
+    // the names of the variants with a normalization can be longer than the positions
+    let column = methods.iter().map(String::len).chain([10]).max().unwrap();
+    let unstable = comparison.unstable_alternatives(spread);
+
+    let mut table = format!("{:width$}", LABEL_COLUMN);
+    for method in &methods {
+        table.push_str(&format!(" {method:>column$}"));
+    }
+    table.push('\n');
+    for (alternative, label) in labels.iter().enumerate() {
+        table.push_str(&format!("{label:width$}"));
+        for position in comparison.positions().row(alternative) {
+            // positions are printed from 1, the best
+            table.push_str(&format!(" {:>column$}", position + 1.));
+        }
+        if unstable.contains(&alternative) {
+            table.push_str("  (unstable)");
+        }
+        table.push('\n');
+    }
+
+    for (title, correlations) in [
+        ("spearman", comparison.spearman()),
+        ("kendall", comparison.kendall()),
+    ] {
+        table.push_str(&format!("\n{title:width$}"));
+        for method in &methods {
+            table.push_str(&format!(" {method:>column$}"));
+        }
+        table.push('\n');
+        for (method, row) in methods.iter().zip(correlations.rows()) {
+            table.push_str(&format!("{method:width$}"));
+            for correlation in row {
+                table.push_str(&format!(" {correlation:>column$.3}"));
+            }
+            table.push('\n');
+        }
+    }
+    if !comparison.skipped().is_empty() {
+        table.push('\n');
+    }
+    for (variant, reason) in comparison.skipped() {
+        table.push_str(&format!("skipped {}: {reason}\n", variant.name()));
+    }
+    table
+}
+
+/// One line for each issue, from the most severe, with the labels of the alternatives.
+pub fn format_diagnosis(labels: &[String], diagnosis: &Diagnosis) -> String {
+    if diagnosis.is_empty() {
+        return "no issues found\n".to_string();
+    }
+    let mut report = String::new();
+    for issue in diagnosis.issues() {
+        let message = match issue {
+            Issue::ConstantVariable(name) => format!(
+                "'{}' has the same value for all the alternatives: it does not count",
+                name.as_str()
+            ),
+            Issue::CorrelatedVariables(a, b, correlation) => format!(
+                "'{}' and '{}' are correlated ({correlation:.3}): they may count the same concern twice",
+                a.as_str(),
+                b.as_str()
+            ),
+            Issue::Outlier {
+                variable,
+                alternative,
+                value,
+                score,
+            } => format!(
+                "'{}' of {} is an outlier ({value}, score {score:.1}): it compresses the scale of the others",
+                variable.as_str(),
+                labels[*alternative]
+            ),
+            Issue::DominatedAlternative { alternative, by } => format!(
+                "{} is dominated by {}: it can never be the best",
+                labels[*alternative], labels[*by]
+            ),
+        };
+        report.push_str(&format!("[{}] {message}\n", issue.severity()));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustimizer::solver::Compare;
+
+    const CSV: &str = "alternative, cost:min, quality:max
+        a, 10, 3
+        b, 12, 5
+
+        c, 14, 4
+    ";
+
+    #[test]
+    fn test_parse_problem() {
+        let parsed = parse_problem(CSV).unwrap();
+        assert_eq!(parsed.labels, vec!["a", "b", "c"]);
+        assert_eq!(parsed.problem.alternatives(), 3);
+        let names: Vec<&str> = parsed.problem.names().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["cost", "quality"]);
+        assert_eq!(parsed.problem.rank().order(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn test_parse_problem_without_labels() {
+        let parsed = parse_problem("x:min\n2\n1").unwrap();
+        assert_eq!(parsed.labels, vec!["#0", "#1"]);
+        assert_eq!(parsed.problem.solve(), 1);
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights = parse_weights(&["cost=3".to_string(), "quality = 1".to_string()]).unwrap();
+        assert_eq!(weights.get(&Name::try_from("cost").unwrap()), Some(0.75));
+    }
+
+    #[test]
+    fn test_format_comparison() {
+        let parsed = parse_problem(CSV).unwrap();
+        let comparison = Compare::new().run(&parsed.problem).unwrap();
+        let table = format_comparison(&parsed.labels, &comparison, 1);
+        assert!(table.starts_with(
+            "alternative   distance     topsis        saw        wpm     waspas multimoora        gra\n"
+        ));
+        assert!(table.contains(
+            "\nb                    1          1          1          1          1          1          1\n"
+        ));
+        assert!(table.contains("\nspearman"));
+        assert!(table.contains("\nkendall"));
+        assert!(!table.contains("skipped"));
+
+        // a cost of 0 rules out the methods that need positive values, not the comparison
+        let parsed =
+            parse_problem("alternative, cost:min, quality:max\na, 0, 3\nb, 12, 5").unwrap();
+        let comparison = Compare::new().run(&parsed.problem).unwrap();
+        let table = format_comparison(&parsed.labels, &comparison, 1);
+        assert!(table.starts_with("alternative   distance     topsis        gra\n"));
+        assert!(table.ends_with(
+            "\nskipped saw: The values of 'cost' must be positive for the linear normalization\n\
+             skipped wpm: The values of 'cost' must be positive for the linear normalization\n\
+             skipped waspas: The values of 'cost' must be positive for the linear normalization\n\
+             skipped multimoora: The values of 'cost' must be positive for the full multiplicative form\n"
+        ));
+
+        // the columns are as wide as the names of the methods with a normalization
+        let comparison = Compare::new()
+            .with_methods(vec![Method::DistanceToIdeal, Method::Saw])
+            .with_normalizations(vec![Normalization::MinMax, Normalization::Vector])
+            .run(&parsed.problem)
+            .unwrap();
+        let table = format_comparison(&parsed.labels, &comparison, 1);
+        assert!(table.starts_with(
+            "alternative            distance distance/vector      saw/minmax      saw/vector\n"
+        ));
+        assert!(table.contains("\ndistance/vector           1.000           1.000"));
+    }
+
+    #[test]
+    fn test_format_diagnosis() {
+        let parsed = parse_problem(CSV).unwrap();
+        let report = format_diagnosis(&parsed.labels, &parsed.problem.diagnose());
+        assert_eq!(
+            report,
+            "[low] c is dominated by b: it can never be the best\n"
+        );
+
+        let parsed = parse_problem("alternative, cost:min, region:min\na, 1, 1\nb, 2, 1").unwrap();
+        let report = format_diagnosis(&parsed.labels, &parsed.problem.diagnose());
+        assert_eq!(
+            report,
+            "[high] 'region' has the same value for all the alternatives: it does not count\n\
+             [low] b is dominated by a: it can never be the best\n"
+        );
+
+        let parsed = parse_problem("x:min, y:max\n1, 1\n2, 2").unwrap();
+        let report = format_diagnosis(&parsed.labels, &parsed.problem.diagnose());
+        assert_eq!(report, "no issues found\n");
+    }
+
+    #[test]
+    fn parse_problem_failure() {
+        assert_eq!(parse_problem("").unwrap_err(), CliError::NoHeader);
+        assert_eq!(
+            parse_problem("x").unwrap_err(),
+            CliError::InvalidColumn("x".to_string())
+        );
+        assert_eq!(
+            parse_problem("x:min\n1,2").unwrap_err(),
+            CliError::RowSizeMismatch(2)
+        );
+        assert_eq!(
+            parse_problem("x:min\n1\nfoo").unwrap_err(),
+            CliError::InvalidNumber(3, "foo".to_string())
+        );
+        // the blank lines count as rows of the file
+        assert_eq!(
+            parse_problem("\nx:min, y:min\na,1,2\n\nb,x,3").unwrap_err(),
+            CliError::RowSizeMismatch(3)
+        );
+        assert_eq!(
+            parse_problem("x:min, y:min\n1,2\n\n3,x").unwrap_err(),
+            CliError::InvalidNumber(4, "x".to_string())
+        );
+        assert_eq!(
+            parse_problem("x:min").unwrap_err(),
+            CliError::Values(ValuesError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_parse_methods() {
+        let methods =
+            parse_methods(&["topsis".to_string(), "waspas".to_string()], 0.5, 0.5).unwrap();
+        assert_eq!(methods, vec![Method::Topsis, Method::Waspas(0.5)]);
+        let methods = parse_methods(&[], 0.2, 0.8).unwrap();
+        assert_eq!(methods.len(), Method::ALL.len());
+        assert!(methods.contains(&Method::Waspas(0.2)));
+        assert!(methods.contains(&Method::GreyRelational(0.8)));
+        assert_eq!(
+            parse_methods(&["foo".to_string()], 0.5, 0.5).unwrap_err(),
+            CliError::UnknownMethod("foo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_normalizations() {
+        let normalizations =
+            parse_normalizations(&["vector".to_string(), "minmax".to_string()]).unwrap();
+        assert_eq!(
+            normalizations,
+            vec![Normalization::Vector, Normalization::MinMax]
+        );
+        assert_eq!(
+            parse_normalizations(&["foo".to_string()]).unwrap_err(),
+            CliError::UnknownNormalization("foo".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_weights_failure() {
+        assert_eq!(
+            parse_weights(&["cost".to_string()]).unwrap_err(),
+            CliError::InvalidWeight("cost".to_string())
+        );
+        assert_eq!(
+            parse_weights(&[]).unwrap_err(),
+            CliError::Weights(WeightsError::Empty)
+        );
+    }
+}
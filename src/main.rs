@@ -1,10 +1,110 @@
-use clap::Parser;
+mod cli;
+
+use clap::{Parser, Subcommand};
+use rustimizer::solver::Compare;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-struct Args {}
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Ranks a problem with all the available methods, side by side.
+    Compare {
+        /// CSV file: one column for each variable (`name:min` or `name:max`), one row for each alternative.
+        file: PathBuf,
+        /// The weight of a variable, as `name=value`. Without weights, all variables weigh the same.
+        #[arg(short, long)]
+        weight: Vec<String>,
+        /// A method to compare: distance, topsis, saw, wpm, waspas, multimoora or gra. Without methods, all of them are
+        /// compared.
+        #[arg(short, long)]
+        method: Vec<String>,
+        /// A normalization to rank the values with: minmax, linear or vector. Without normalizations, each method
+        /// ranks the values with its own normalization.
+        #[arg(short, long)]
+        normalization: Vec<String>,
+        /// The lambda of WASPAS, from the weighted product (0) to the weighted sum (1).
+        #[arg(long, default_value_t = 0.5)]
+        lambda: f64,
+        /// The distinguishing coefficient of GRA, in (0, 1].
+        #[arg(long, default_value_t = 0.5)]
+        zeta: f64,
+        /// Flags the alternatives whose position varies by at least this much across the methods.
+        #[arg(short, long, default_value_t = 2)]
+        spread: usize,
+    },
+    /// Reports the issues of a problem: constant and correlated variables, outliers, dominated alternatives.
+    Diagnose {
+        /// CSV file: one column for each variable (`name:min` or `name:max`), one row for each alternative.
+        file: PathBuf,
+    },
+}
+
+fn read_problem(file: &PathBuf) -> Result<cli::LabelledProblem, String> {
+    let csv = std::fs::read_to_string(file).map_err(|e| format!("{}: {e}", file.display()))?;
+    cli::parse_problem(&csv).map_err(|e| e.to_string())
+}
+
+fn compare(
+    file: &PathBuf,
+    weights: &[String],
+    methods: &[String],
+    normalizations: &[String],
+    lambda: f64,
+    zeta: f64,
+    spread: usize,
+) -> Result<String, String> {
+    let parsed = read_problem(file)?;
+    let methods = cli::parse_methods(methods, lambda, zeta).map_err(|e| e.to_string())?;
+    let mut compare = Compare::new().with_methods(methods);
+    if !normalizations.is_empty() {
+        compare = compare.with_normalizations(
+            cli::parse_normalizations(normalizations).map_err(|e| e.to_string())?,
+        );
+    }
+    if !weights.is_empty() {
+        compare = compare.with_weights(cli::parse_weights(weights).map_err(|e| e.to_string())?);
+    }
+    let comparison = compare.run(&parsed.problem).map_err(|e| e.to_string())?;
+    Ok(cli::format_comparison(&parsed.labels, &comparison, spread))
+}
+
+fn diagnose(file: &PathBuf) -> Result<String, String> {
+    let parsed = read_problem(file)?;
+    Ok(cli::format_diagnosis(
+        &parsed.labels,
+        &parsed.problem.diagnose(),
+    ))
+}
 
-fn main() {
+fn main() -> ExitCode {
     let args = Args::parse();
-    println!("Hello rustimizer");
+    let output = match &args.command {
+        Command::Compare {
+            file,
+            weight,
+            method,
+            normalization,
+            lambda,
+            zeta,
+            spread,
+        } => compare(file, weight, method, normalization, *lambda, *zeta, *spread),
+        Command::Diagnose { file } => diagnose(file),
+    };
+    match output {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -1,6 +1,66 @@
+mod aggregation;
+mod bestworst;
+mod compare;
+mod dea;
+mod diagnosis;
+mod fuzzy;
+mod goal;
+mod greyrelational;
+mod group;
+mod hierarchy;
+mod interval;
+mod linprog;
+mod montecarlo;
+mod multimoora;
+mod nsga2;
+mod ordinalregression;
+mod portfolio;
 mod problem;
+mod ranking;
+mod referencepoint;
+mod reversal;
+mod smaa;
+mod swing;
 mod vector;
+mod waspas;
+mod weights;
 
-pub use self::problem::Problem;
+pub use self::aggregation::{AggregationError, KEMENY_EXACT_LIMIT, RankAggregation};
+pub use self::bestworst::{BestWorst, BestWorstError, BestWorstWeights};
+pub use self::compare::{
+    Compare, CompareError, Comparison, Method, Normalization, Variant, kendall_correlation,
+    spearman_correlation,
+};
+pub use self::dea::{Dea, DeaError, DeaResult, ReturnsToScale};
+pub use self::diagnosis::{Diagnosis, Issue, Severity};
+pub use self::fuzzy::FuzzyProblem;
+pub use self::goal::{Goal, GoalDeviations, GoalMode, GoalProgramming, GoalProgrammingError};
+pub use self::greyrelational::{GreyRelational, GreyRelationalError, GreyRelationalGrades};
+pub use self::group::{
+    Aggregation, DecisionMaker, GroupDecision, GroupDecisionError, GroupRanking,
+};
+pub use self::hierarchy::{Criterion, Hierarchy, HierarchyError, HierarchyRanking};
+pub use self::interval::{IntervalProblem, IntervalRanking};
+pub use self::linprog::{LinearProgram, LinearProgramError, LinearProgramSolution};
+pub use self::montecarlo::{MonteCarlo, MonteCarloError, MonteCarloResult};
+pub use self::multimoora::{Multimoora, MultimooraError, MultimooraRanking};
+pub use self::nsga2::{
+    DesignVariable, Nsga2, Nsga2Error, Objective, ObjectiveFunction, ParetoFront,
+};
+pub use self::ordinalregression::{
+    MarginalValue, OrdinalRegression, OrdinalRegressionError, OrdinalRegressionResult, Preference,
+    ValueModel,
+};
+pub use self::portfolio::{PORTFOLIO_EXACT_LIMIT, Portfolio, PortfolioError, PortfolioSelection};
+pub use self::problem::{Problem, ProblemError};
+pub use self::ranking::{Ranking, RankingError};
+pub use self::referencepoint::{ReferencePoint, ReferencePointError, ReferencePointRanking};
+pub use self::reversal::{
+    Modification, RankReversal, RankReversalError, RankReversalReport, Reversal,
+};
+pub use self::smaa::{Smaa, SmaaError, SmaaResult};
+pub use self::swing::{Swing, SwingError, SwingWeighting};
+pub use self::waspas::ScoringError;
+pub use self::weights::{Weights, WeightsError};
 
 pub mod prelude {}
@@ -0,0 +1,338 @@
+//! This module aggregates several rankings of the same alternatives into a consensus ranking.
+//!
+//! The rankings may come from different solvers, decision makers or weights. Ties, such as in the rankings built with
+//! `Ranking::from_best`, express no preference between the tied alternatives.
+use super::ranking::Ranking;
+use ndarray::{Array1, Array2};
+use std::cmp::Ordering;
+
+/// Above this number of alternatives, Kemeny-Young uses a local search instead of the exact solution.
+pub const KEMENY_EXACT_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankAggregation {
+    /// Each alternative scores the number of alternatives it beats in each ranking.
+    Borda,
+    /// Each alternative scores its pairwise majority wins minus its losses.
+    Copeland,
+    /// The ranking that agrees the most with the pairwise preferences of all rankings.
+    KemenyYoung,
+    /// Ranks by the strongest paths of the pairwise majority graph.
+    Schulze,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum AggregationError {
+    #[error("At least one ranking is required")]
+    NoRankings,
+    #[error("All rankings must have the same alternatives")]
+    AlternativesMismatch,
+}
+
+/// `preferences[[a, b]]` is the number of rankings where `a` is strictly better than `b`.
+fn pairwise_preferences(rankings: &[Ranking]) -> Array2<f64> {
+    let size = rankings[0].len();
+    let mut preferences = Array2::zeros((size, size));
+    for ranking in rankings {
+        let positions = ranking.positions();
+        let scores = ranking.scores();
+        for a in 0..size {
+            for b in 0..size {
+                if scores[a] != scores[b] && positions[a] < positions[b] {
+                    preferences[[a, b]] += 1.;
+                }
+            }
+        }
+    }
+    preferences
+}
+
+fn borda(preferences: &Array2<f64>) -> Ranking {
+    // beating an alternative in a ranking is worth one point
+    Ranking::descending(preferences.sum_axis(ndarray::Axis(1)))
+}
+
+fn copeland(preferences: &Array2<f64>) -> Ranking {
+    let size = preferences.nrows();
+    let scores = (0..size)
+        .map(|a| {
+            (0..size)
+                .map(
+                    |b| match preferences[[a, b]].total_cmp(&preferences[[b, a]]) {
+                        Ordering::Greater => 1.,
+                        Ordering::Less => -1.,
+                        Ordering::Equal => 0.,
+                    },
+                )
+                .sum()
+        })
+        .collect();
+    Ranking::descending(scores)
+}
+
+fn schulze(preferences: &Array2<f64>) -> Ranking {
+    let size = preferences.nrows();
+    // widest paths of the graph of the pairwise majority wins
+    let mut strength = Array2::<f64>::zeros((size, size));
+    for a in 0..size {
+        for b in 0..size {
+            if preferences[[a, b]] > preferences[[b, a]] {
+                strength[[a, b]] = preferences[[a, b]];
+            }
+        }
+    }
+    for k in 0..size {
+        for a in (0..size).filter(|&a| a != k) {
+            for b in (0..size).filter(|&b| b != k && b != a) {
+                let through = strength[[a, k]].min(strength[[k, b]]);
+                if through > strength[[a, b]] {
+                    strength[[a, b]] = through;
+                }
+            }
+        }
+    }
+    let scores = (0..size)
+        .map(|a| {
+            (0..size)
+                .filter(|&b| strength[[a, b]] > strength[[b, a]])
+                .count() as f64
+        })
+        .collect();
+    Ranking::descending(scores)
+}
+
+/// The total agreement of an order with the pairwise preferences.
+fn kemeny_score(preferences: &Array2<f64>, order: &[usize]) -> f64 {
+    let mut score = 0.;
+    for (i, &a) in order.iter().enumerate() {
+        for &b in &order[i + 1..] {
+            score += preferences[[a, b]];
+        }
+    }
+    score
+}
+
+fn kemeny_exact(preferences: &Array2<f64>) -> Vec<usize> {
+    // dynamic programming on the subsets of alternatives already placed at the top of the ranking
+    let size = preferences.nrows();
+    let subsets = 1usize << size;
+    let mut best = vec![f64::NEG_INFINITY; subsets];
+    let mut last = vec![0usize; subsets];
+    best[0] = 0.;
+    for placed in 0..subsets {
+        if best[placed] == f64::NEG_INFINITY {
+            continue;
+        }
+        for next in (0..size).filter(|&x| placed & (1 << x) == 0) {
+            let gain: f64 = (0..size)
+                .filter(|&b| b != next && placed & (1 << b) == 0)
+                .map(|b| preferences[[next, b]])
+                .sum();
+            let subset = placed | (1 << next);
+            if best[placed] + gain > best[subset] {
+                best[subset] = best[placed] + gain;
+                last[subset] = next;
+            }
+        }
+    }
+    let mut order = Vec::with_capacity(size);
+    let mut subset = subsets - 1;
+    while subset != 0 {
+        order.push(last[subset]);
+        subset &= !(1 << last[subset]);
+    }
+    order.reverse();
+    order
+}
+
+fn kemeny_local_search(preferences: &Array2<f64>) -> Vec<usize> {
+    // start from Borda and move single alternatives while the agreement improves
+    let mut order = borda(preferences).order().to_vec();
+    let mut score = kemeny_score(preferences, &order);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for from in 0..order.len() {
+            for to in 0..order.len() {
+                if from == to {
+                    continue;
+                }
+                let mut candidate = order.clone();
+                let moved = candidate.remove(from);
+                candidate.insert(to, moved);
+                let candidate_score = kemeny_score(preferences, &candidate);
+                if candidate_score > score {
+                    order = candidate;
+                    score = candidate_score;
+                    improved = true;
+                }
+            }
+        }
+    }
+    order
+}
+
+fn kemeny_young(preferences: &Array2<f64>) -> Ranking {
+    let order = if preferences.nrows() <= KEMENY_EXACT_LIMIT {
+        kemeny_exact(preferences)
+    } else {
+        kemeny_local_search(preferences)
+    };
+    let mut positions = Array1::zeros(order.len());
+    for (position, &alternative) in order.iter().enumerate() {
+        positions[alternative] = position as f64;
+    }
+    Ranking::ascending(positions)
+}
+
+impl RankAggregation {
+    pub fn aggregate(&self, rankings: &[Ranking]) -> Result<Ranking, AggregationError> {
+        if rankings.is_empty() {
+            return Err(AggregationError::NoRankings);
+        }
+        if rankings.iter().any(|r| r.len() != rankings[0].len()) {
+            return Err(AggregationError::AlternativesMismatch);
+        }
+        let preferences = pairwise_preferences(rankings);
+        Ok(match self {
+            RankAggregation::Borda => borda(&preferences),
+            RankAggregation::Copeland => copeland(&preferences),
+            RankAggregation::KemenyYoung => kemeny_young(&preferences),
+            RankAggregation::Schulze => schulze(&preferences),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn ranking(order: &[usize]) -> Ranking {
+        Ranking::from_order(order.to_vec()).unwrap()
+    }
+
+    fn all_methods() -> [RankAggregation; 4] {
+        [
+            RankAggregation::Borda,
+            RankAggregation::Copeland,
+            RankAggregation::KemenyYoung,
+            RankAggregation::Schulze,
+        ]
+    }
+
+    #[test]
+    fn test_pairwise_preferences_with_ties() {
+        let preferences =
+            pairwise_preferences(&[ranking(&[0, 1, 2]), Ranking::from_best(2, 3).unwrap()]);
+        assert_eq!(
+            preferences,
+            array![[0., 1., 1.], [0., 0., 1.], [1., 1., 0.]]
+        );
+    }
+
+    #[test]
+    fn test_unanimous_rankings() {
+        let rankings = vec![ranking(&[2, 0, 1]); 3];
+        for method in all_methods() {
+            assert_eq!(method.aggregate(&rankings).unwrap().order(), &[2, 0, 1]);
+        }
+    }
+
+    #[test]
+    fn test_majority() {
+        let rankings = [
+            ranking(&[0, 1, 2]),
+            ranking(&[0, 2, 1]),
+            ranking(&[1, 0, 2]),
+        ];
+        for method in all_methods() {
+            assert_eq!(method.aggregate(&rankings).unwrap().best(), 0);
+        }
+        assert_eq!(
+            RankAggregation::KemenyYoung
+                .aggregate(&rankings)
+                .unwrap()
+                .order(),
+            &[0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_borda_differs_from_condorcet() {
+        // 0 is the majority winner, but 1 has the highest Borda count
+        let mut rankings = vec![ranking(&[0, 1, 2, 3]); 3];
+        rankings.extend(vec![ranking(&[1, 2, 3, 0]); 2]);
+        assert_eq!(
+            RankAggregation::Borda.aggregate(&rankings).unwrap().best(),
+            1
+        );
+        for method in [
+            RankAggregation::Copeland,
+            RankAggregation::KemenyYoung,
+            RankAggregation::Schulze,
+        ] {
+            assert_eq!(method.aggregate(&rankings).unwrap().best(), 0);
+        }
+    }
+
+    #[test]
+    fn test_schulze_breaks_cycles() {
+        // a Condorcet cycle 0 > 1 > 2 > 0, where 2 > 0 is the weakest preference
+        let mut rankings = vec![ranking(&[0, 1, 2]); 4];
+        rankings.extend(vec![ranking(&[1, 2, 0]); 2]);
+        rankings.extend(vec![ranking(&[2, 0, 1]); 3]);
+        let copeland = RankAggregation::Copeland.aggregate(&rankings).unwrap();
+        assert_eq!(copeland.scores(), array![0., 0., 0.]);
+        assert_eq!(
+            RankAggregation::Schulze
+                .aggregate(&rankings)
+                .unwrap()
+                .order(),
+            &[0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_kemeny_exact_matches_local_search() {
+        let rankings = [
+            ranking(&[0, 1, 2, 3, 4]),
+            ranking(&[1, 0, 3, 2, 4]),
+            ranking(&[4, 3, 2, 1, 0]),
+            ranking(&[0, 2, 1, 4, 3]),
+        ];
+        let preferences = pairwise_preferences(&rankings);
+        let exact = kemeny_exact(&preferences);
+        let heuristic = kemeny_local_search(&preferences);
+        assert_eq!(
+            kemeny_score(&preferences, &exact),
+            kemeny_score(&preferences, &heuristic)
+        );
+        // the exact solution is the best among all the permutations
+        assert_eq!(kemeny_score(&preferences, &exact), 26.);
+    }
+
+    #[test]
+    fn test_kemeny_large() {
+        let size = KEMENY_EXACT_LIMIT + 2;
+        let order: Vec<usize> = (0..size).collect();
+        let reversed: Vec<usize> = (0..size).rev().collect();
+        let rankings = [ranking(&order), ranking(&order), ranking(&reversed)];
+        let aggregated = RankAggregation::KemenyYoung.aggregate(&rankings).unwrap();
+        assert_eq!(aggregated.order(), order.as_slice());
+    }
+
+    #[test]
+    fn aggregate_no_rankings_failure() {
+        let err = RankAggregation::Borda.aggregate(&[]).unwrap_err();
+        assert_eq!(err, AggregationError::NoRankings);
+    }
+
+    #[test]
+    fn aggregate_alternatives_mismatch_failure() {
+        let err = RankAggregation::Schulze
+            .aggregate(&[ranking(&[0, 1]), ranking(&[0, 1, 2])])
+            .unwrap_err();
+        assert_eq!(err, AggregationError::AlternativesMismatch);
+    }
+}
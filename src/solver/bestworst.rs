@@ -0,0 +1,394 @@
+//! This module elicits the weights of the variables with the linear Best-Worst Method (BWM).
+//!
+//! The decision maker picks the best and the worst variables, then compares the best one with each variable
+//! (best-to-others) and each variable with the worst one (others-to-worst), on a scale from 1 (equally important) to
+//! 9 (extremely more important). That is 2n - 3 comparisons, instead of the n(n - 1) / 2 of a full pairwise matrix.
+//! The linear model finds the weights that minimize the biggest deviation `ksi` from the comparisons. The input-based
+//! consistency ratio measures how far the comparisons are from the transitivity `a_Bj * a_jW = a_BW`: 0 is fully
+//! consistent.
+use super::linprog::{LinearProgram, LinearProgramError};
+use super::problem::{EntryError, Problem, entries_by_name};
+use super::weights::Weights;
+use crate::variables::Name;
+use ndarray::{Array1, Array2};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct BestWorst {
+    best: Name,
+    worst: Name,
+    best_to_others: Vec<(Name, f64)>,
+    others_to_worst: Vec<(Name, f64)>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum BestWorstError {
+    #[error("The variable '{0}' is not a variable of the problem")]
+    UnknownVariable(String),
+    #[error("The comparison with '{0}' is missing: each variable must have one in both vectors")]
+    MissingComparison(String),
+    #[error("Each variable can have only one comparison in each vector: '{0}' has more than one")]
+    RedefinitionComparison(String),
+    #[error("The comparison with '{0}' must be at least 1, and exactly 1 with itself")]
+    InvalidComparison(String),
+    #[error("The best-to-worst comparison must be the same in both vectors")]
+    BestToWorstMismatch,
+    #[error("The best and the worst variables must be different: '{0}' is both")]
+    SameBestAndWorst(String),
+    #[error(transparent)]
+    LinearProgram(#[from] LinearProgramError),
+}
+
+impl From<EntryError> for BestWorstError {
+    fn from(error: EntryError) -> Self {
+        match error {
+            EntryError::Unknown(name) => BestWorstError::UnknownVariable(name),
+            EntryError::Invalid(name) => BestWorstError::InvalidComparison(name),
+            EntryError::Redefinition(name) => BestWorstError::RedefinitionComparison(name),
+            EntryError::Missing(name) => BestWorstError::MissingComparison(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BestWorstWeights {
+    weights: Weights,
+    ksi: f64,
+    consistency_ratio: f64,
+}
+
+impl BestWorstWeights {
+    pub fn weights(&self) -> &Weights {
+        &self.weights
+    }
+
+    /// The biggest deviation of the weights from the comparisons: the closer to 0, the better.
+    pub fn ksi(&self) -> f64 {
+        self.ksi
+    }
+
+    /// The input-based consistency ratio, from 0 (fully consistent) to 1.
+    pub fn consistency_ratio(&self) -> f64 {
+        self.consistency_ratio
+    }
+}
+
+fn comparisons_of(
+    problem: &Problem,
+    comparisons: &[(Name, f64)],
+) -> Result<BTreeMap<Name, f64>, BestWorstError> {
+    let comparisons = comparisons.iter().map(|(name, c)| (name, *c));
+    Ok(entries_by_name(problem.names(), comparisons, true, |c| {
+        c.is_finite() && *c >= 1.
+    })?)
+}
+
+impl BestWorst {
+    pub fn new(
+        best: Name,
+        worst: Name,
+        best_to_others: Vec<(Name, f64)>,
+        others_to_worst: Vec<(Name, f64)>,
+    ) -> Self {
+        Self {
+            best,
+            worst,
+            best_to_others,
+            others_to_worst,
+        }
+    }
+
+    /// The comparisons, in the same order of the variables of the problem.
+    fn get_comparisons(
+        &self,
+        problem: &Problem,
+    ) -> Result<(Array1<f64>, Array1<f64>), BestWorstError> {
+        for name in [&self.best, &self.worst] {
+            if problem.values_of(name).is_none() {
+                return Err(BestWorstError::UnknownVariable(name.as_str().to_string()));
+            }
+        }
+        if self.best == self.worst {
+            return Err(BestWorstError::SameBestAndWorst(
+                self.best.as_str().to_string(),
+            ));
+        }
+        let best_to_others = comparisons_of(problem, &self.best_to_others)?;
+        let others_to_worst = comparisons_of(problem, &self.others_to_worst)?;
+        if best_to_others[&self.best] != 1. {
+            return Err(BestWorstError::InvalidComparison(
+                self.best.as_str().to_string(),
+            ));
+        }
+        if others_to_worst[&self.worst] != 1. {
+            return Err(BestWorstError::InvalidComparison(
+                self.worst.as_str().to_string(),
+            ));
+        }
+        if best_to_others[&self.worst] != others_to_worst[&self.best] {
+            return Err(BestWorstError::BestToWorstMismatch);
+        }
+        // NOTE: both maps have all and only the variables of the problem, in the same order
+        Ok((
+            best_to_others.into_values().collect(),
+            others_to_worst.into_values().collect(),
+        ))
+    }
+
+    pub fn weights(&self, problem: &Problem) -> Result<BestWorstWeights, BestWorstError> {
+        let (best_to_others, others_to_worst) = self.get_comparisons(problem)?;
+        let names: Vec<&Name> = problem.names().collect();
+        let size = names.len();
+        // NOTE: best and worst are variables of the problem
+        let best = names.iter().position(|&n| n == &self.best).unwrap();
+        let worst = names.iter().position(|&n| n == &self.worst).unwrap();
+
+        // the variables are the weights and ksi: |w_B - a_Bj w_j| <= ksi and |w_j - a_jW w_W| <= ksi
+        let ksi = size;
+        let mut a_ub = Array2::zeros((4 * size, size + 1));
+        for j in 0..size {
+            let mut deviation = Array1::<f64>::zeros(size + 1);
+            deviation[best] += 1.;
+            deviation[j] -= best_to_others[j];
+            a_ub.row_mut(4 * j).assign(&deviation);
+            a_ub.row_mut(4 * j + 1).assign(&-&deviation);
+
+            let mut deviation = Array1::<f64>::zeros(size + 1);
+            deviation[j] += 1.;
+            deviation[worst] -= others_to_worst[j];
+            a_ub.row_mut(4 * j + 2).assign(&deviation);
+            a_ub.row_mut(4 * j + 3).assign(&-&deviation);
+        }
+        a_ub.column_mut(ksi).fill(-1.);
+        let mut a_eq = Array2::ones((1, size + 1));
+        a_eq[[0, ksi]] = 0.;
+        let mut c = Array1::zeros(size + 1);
+        c[ksi] = 1.;
+        let solution = LinearProgram::minimize(c)
+            .with_less_equal(a_ub, Array1::zeros(4 * size))
+            .with_equal(a_eq, Array1::ones(1))
+            .solve()?;
+
+        let weights = names
+            .iter()
+            .zip(solution.x())
+            .map(|(&name, &w)| (name.clone(), w.max(0.)))
+            .collect::<Vec<_>>();
+        // the input-based consistency ratio of each variable, the biggest one for all of them
+        let best_to_worst = best_to_others[worst];
+        let consistency_ratio = if best_to_worst > 1. {
+            (0..size)
+                .map(|j| {
+                    (best_to_others[j] * others_to_worst[j] - best_to_worst).abs()
+                        / (best_to_worst * best_to_worst - best_to_worst)
+                })
+                .fold(0., f64::max)
+        } else {
+            0.
+        };
+        Ok(BestWorstWeights {
+            // NOTE: the weights are non-negative and sum to 1
+            weights: Weights::try_from(weights).unwrap(),
+            ksi: solution.x()[ksi],
+            consistency_ratio,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableProperties};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(
+            ["cost", "risk", "time"]
+                .iter()
+                .map(|&n| {
+                    Box::new(VariableAutoscale::new(
+                        Name::try_from(n).unwrap(),
+                        Values::try_from(array![1., 2.]).unwrap(),
+                    )) as Box<dyn VariableProperties>
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn create_test_method(risk: f64) -> BestWorst {
+        BestWorst::new(
+            Name::try_from("cost").unwrap(),
+            Name::try_from("time").unwrap(),
+            vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("risk").unwrap(), risk),
+                (Name::try_from("time").unwrap(), 8.),
+            ],
+            vec![
+                (Name::try_from("cost").unwrap(), 8.),
+                (Name::try_from("risk").unwrap(), 4.),
+                (Name::try_from("time").unwrap(), 1.),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_consistent_weights() {
+        let result = create_test_method(2.)
+            .weights(&create_test_problem())
+            .unwrap();
+        let weights = result.weights();
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("cost").unwrap()).unwrap(),
+            8. / 13.,
+            epsilon = 1e-9
+        );
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("risk").unwrap()).unwrap(),
+            4. / 13.,
+            epsilon = 1e-9
+        );
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("time").unwrap()).unwrap(),
+            1. / 13.,
+            epsilon = 1e-9
+        );
+        assert_ulps_eq!(result.ksi(), 0., epsilon = 1e-9);
+        assert_ulps_eq!(result.consistency_ratio(), 0.);
+    }
+
+    #[test]
+    fn test_inconsistent_weights() {
+        let result = create_test_method(3.)
+            .weights(&create_test_problem())
+            .unwrap();
+        // 3 * 4 is not 8
+        assert_ulps_eq!(result.consistency_ratio(), 4. / 56.);
+        assert!(result.ksi() > 0.);
+        let weights = result.weights();
+        let cost = weights.get(&Name::try_from("cost").unwrap()).unwrap();
+        let risk = weights.get(&Name::try_from("risk").unwrap()).unwrap();
+        let time = weights.get(&Name::try_from("time").unwrap()).unwrap();
+        assert!(cost > risk && risk > time);
+        // no deviation is above ksi
+        assert!((cost - 3. * risk).abs() <= result.ksi() + 1e-9);
+        assert!((risk - 4. * time).abs() <= result.ksi() + 1e-9);
+    }
+
+    #[test]
+    fn weights_missing_comparison_failure() {
+        let method = BestWorst::new(
+            Name::try_from("cost").unwrap(),
+            Name::try_from("time").unwrap(),
+            vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("time").unwrap(), 8.),
+            ],
+            vec![
+                (Name::try_from("cost").unwrap(), 8.),
+                (Name::try_from("risk").unwrap(), 4.),
+                (Name::try_from("time").unwrap(), 1.),
+            ],
+        );
+        let err = method.weights(&create_test_problem()).unwrap_err();
+        assert_eq!(err, BestWorstError::MissingComparison("risk".to_string()));
+    }
+
+    #[test]
+    fn weights_unknown_variable_failure() {
+        let method = BestWorst::new(
+            Name::try_from("speed").unwrap(),
+            Name::try_from("time").unwrap(),
+            vec![],
+            vec![],
+        );
+        let err = method.weights(&create_test_problem()).unwrap_err();
+        assert_eq!(err, BestWorstError::UnknownVariable("speed".to_string()));
+    }
+
+    #[test]
+    fn weights_invalid_comparison_failure() {
+        let err = create_test_method(0.5)
+            .weights(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, BestWorstError::InvalidComparison("risk".to_string()));
+
+        let method = BestWorst::new(
+            Name::try_from("cost").unwrap(),
+            Name::try_from("time").unwrap(),
+            vec![
+                (Name::try_from("cost").unwrap(), 2.),
+                (Name::try_from("risk").unwrap(), 2.),
+                (Name::try_from("time").unwrap(), 8.),
+            ],
+            vec![
+                (Name::try_from("cost").unwrap(), 8.),
+                (Name::try_from("risk").unwrap(), 4.),
+                (Name::try_from("time").unwrap(), 1.),
+            ],
+        );
+        let err = method.weights(&create_test_problem()).unwrap_err();
+        assert_eq!(err, BestWorstError::InvalidComparison("cost".to_string()));
+    }
+
+    #[test]
+    fn weights_redefinition_comparison_failure() {
+        let method = BestWorst::new(
+            Name::try_from("cost").unwrap(),
+            Name::try_from("time").unwrap(),
+            vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("cost").unwrap(), 2.),
+            ],
+            vec![],
+        );
+        let err = method.weights(&create_test_problem()).unwrap_err();
+        assert_eq!(
+            err,
+            BestWorstError::RedefinitionComparison("cost".to_string())
+        );
+    }
+
+    #[test]
+    fn weights_best_to_worst_mismatch_failure() {
+        let method = BestWorst::new(
+            Name::try_from("cost").unwrap(),
+            Name::try_from("time").unwrap(),
+            vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("risk").unwrap(), 2.),
+                (Name::try_from("time").unwrap(), 8.),
+            ],
+            vec![
+                (Name::try_from("cost").unwrap(), 7.),
+                (Name::try_from("risk").unwrap(), 4.),
+                (Name::try_from("time").unwrap(), 1.),
+            ],
+        );
+        let err = method.weights(&create_test_problem()).unwrap_err();
+        assert_eq!(err, BestWorstError::BestToWorstMismatch);
+    }
+
+    #[test]
+    fn weights_same_best_and_worst_failure() {
+        let method = BestWorst::new(
+            Name::try_from("cost").unwrap(),
+            Name::try_from("cost").unwrap(),
+            vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("risk").unwrap(), 2.),
+                (Name::try_from("time").unwrap(), 8.),
+            ],
+            vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("risk").unwrap(), 4.),
+                (Name::try_from("time").unwrap(), 1.),
+            ],
+        );
+        let err = method.weights(&create_test_problem()).unwrap_err();
+        assert_eq!(err, BestWorstError::SameBestAndWorst("cost".to_string()));
+    }
+}
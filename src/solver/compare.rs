@@ -0,0 +1,730 @@
+//! This module compares the rankings of the same problem computed by different methods and normalizations.
+//!
+//! Reviewers trust a recommendation more when several methods agree: the comparison reports the ranking of each
+//! method side by side, the rank correlations between each pair of methods and the alternatives whose rank varies the
+//! most. The methods that do not apply to the problem, such as SAW or MULTIMOORA with values that are not positive,
+//! are skipped and reported with the reason.
+//!
+//! Each method ranks the values with the normalization it is defined with, unless the comparison is given other
+//! normalizations: then every method ranks the values with each of them, so that a disagreement can be traced to the
+//! method or to the normalization.
+use super::greyrelational::{GreyRelational, GreyRelationalError};
+use super::multimoora::{Multimoora, MultimooraError};
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::vector::{weighted_closeness_vectors, weighted_l2_norm_vectors};
+use super::waspas::{ScoringError, get_linear_matrix, weighted_scores};
+use super::weights::Weights;
+use crate::variables::normalize_vector;
+use ndarray::{Array1, Array2, ArrayView2, Axis};
+
+/// A normalization of the values of each variable to [0, 1], 1 being the best value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// The rescaling of the range of the values, as `Problem::get_problem_matrix`.
+    MinMax,
+    /// The ratio to the best value: `x / max` for benefits and `min / x` for costs. The values must be positive.
+    Linear,
+    /// The ratio to the euclidean norm of the values, `x / norm`, and `1 - x / norm` for costs. The values must not be
+    /// negative.
+    Vector,
+}
+
+impl Normalization {
+    /// All the available normalizations.
+    pub const ALL: [Normalization; 3] = [
+        Normalization::MinMax,
+        Normalization::Linear,
+        Normalization::Vector,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Normalization::MinMax => "minmax",
+            Normalization::Linear => "linear",
+            Normalization::Vector => "vector",
+        }
+    }
+
+    /// The normalized values, the higher the better, and their deviations from the best value of the scale, the lower
+    /// the better: one row for each alternative, one column for each variable.
+    fn normalize(&self, problem: &Problem) -> Result<(Array2<f64>, Array2<f64>), CompareError> {
+        Ok(match self {
+            Normalization::MinMax => {
+                let deviations = problem.get_problem_matrix();
+                (deviations.mapv(|x| 1. - x), deviations)
+            }
+            Normalization::Linear => {
+                let benefits = get_linear_matrix(problem)?;
+                let deviations = benefits.mapv(|x| 1. - x);
+                (benefits, deviations)
+            }
+            Normalization::Vector => {
+                let mut benefits = Array2::zeros((problem.alternatives(), problem.names().count()));
+                for (mut column, variable) in
+                    benefits.axis_iter_mut(Axis(1)).zip(problem.variables())
+                {
+                    if variable.values().values().iter().any(|&x| x < 0.) {
+                        return Err(CompareError::NegativeValues(
+                            variable.name().as_str().to_string(),
+                        ));
+                    }
+                    let normalized = normalize_vector(variable.values());
+                    let values = normalized.values();
+                    if variable.higher_is_better() {
+                        column.assign(&values);
+                    } else {
+                        column.assign(&values.mapv(|x| 1. - x));
+                    }
+                }
+                let deviations = benefits.mapv(|x| 1. - x);
+                (benefits, deviations)
+            }
+        })
+    }
+}
+
+/// A method to rank the alternatives of a problem. Each method has its own normalization of the values, the one it is
+/// defined with, but it can rank the values with any other normalization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    /// The weighted distance from the ideal, as `Problem::rank_weighted`.
+    DistanceToIdeal,
+    /// The closeness to the ideal rather than to the anti-ideal.
+    Topsis,
+    /// The weighted sum of the linearly normalized values, as `Problem::rank_saw`.
+    Saw,
+    /// The weighted product of the linearly normalized values, as `Problem::rank_wpm`.
+    Wpm,
+    /// The blend of SAW and WPM with the given lambda, as `Problem::rank_waspas`.
+    Waspas(f64),
+    /// The dominance of the three MULTIMOORA rankings, as `Multimoora::rank`.
+    Multimoora,
+    /// The grey relational grades with the given distinguishing coefficient, as `GreyRelational::rank`.
+    GreyRelational(f64),
+}
+
+impl Method {
+    /// All the available methods, with their default parameters.
+    pub const ALL: [Method; 7] = [
+        Method::DistanceToIdeal,
+        Method::Topsis,
+        Method::Saw,
+        Method::Wpm,
+        Method::Waspas(0.5),
+        Method::Multimoora,
+        Method::GreyRelational(0.5),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::DistanceToIdeal => "distance",
+            Method::Topsis => "topsis",
+            Method::Saw => "saw",
+            Method::Wpm => "wpm",
+            Method::Waspas(_) => "waspas",
+            Method::Multimoora => "multimoora",
+            Method::GreyRelational(_) => "gra",
+        }
+    }
+
+    /// The normalization the method is defined with.
+    pub fn normalization(&self) -> Normalization {
+        match self {
+            Method::DistanceToIdeal | Method::Topsis | Method::GreyRelational(_) => {
+                Normalization::MinMax
+            }
+            Method::Saw | Method::Wpm | Method::Waspas(_) => Normalization::Linear,
+            Method::Multimoora => Normalization::Vector,
+        }
+    }
+
+    /// Ranks the problem, with equal weights when there are no weights.
+    pub fn rank(
+        &self,
+        problem: &Problem,
+        weights: Option<&Weights>,
+    ) -> Result<Ranking, CompareError> {
+        self.rank_normalized(problem, weights, self.normalization())
+    }
+
+    /// Ranks the problem normalized with the given normalization, with equal weights when there are no weights. The
+    /// distance-based methods measure the distances from the best (1) and the worst (0) values of the scale, and the
+    /// full multiplicative form of MULTIMOORA always works on the raw values.
+    pub fn rank_normalized(
+        &self,
+        problem: &Problem,
+        weights: Option<&Weights>,
+        normalization: Normalization,
+    ) -> Result<Ranking, CompareError> {
+        let weights_vector = problem.get_weights_or_equal(weights)?;
+        let mut multimoora = Multimoora::new();
+        if let Some(weights) = weights {
+            multimoora = multimoora.with_weights(weights.clone());
+        }
+        if let (Method::Multimoora, Normalization::Vector) = (self, normalization) {
+            // MULTIMOORA is defined with the vector normalization, with no need for non-negative values
+            return Ok(multimoora.rank(problem)?.ranking().clone());
+        }
+        let (benefits, deviations) = normalization.normalize(problem)?;
+        Ok(match self {
+            Method::DistanceToIdeal => Ranking::ascending(weighted_l2_norm_vectors(
+                deviations.view(),
+                weights_vector.view(),
+            )),
+            Method::Topsis => Ranking::descending(weighted_closeness_vectors(
+                deviations.view(),
+                weights_vector.view(),
+            )),
+            Method::Saw => Ranking::descending(weighted_scores(&benefits, &weights_vector, 1.)?),
+            Method::Wpm => Ranking::descending(weighted_scores(&benefits, &weights_vector, 0.)?),
+            Method::Waspas(lambda) => {
+                Ranking::descending(weighted_scores(&benefits, &weights_vector, *lambda)?)
+            }
+            Method::Multimoora => multimoora
+                .rank_benefits(problem, &benefits)?
+                .ranking()
+                .clone(),
+            Method::GreyRelational(zeta) => {
+                let mut gra = GreyRelational::new().with_distinguishing_coefficient(*zeta);
+                if let Some(weights) = weights {
+                    gra = gra.with_weights(weights.clone());
+                }
+                gra.rank_deviations(problem, &deviations)?.ranking().clone()
+            }
+        })
+    }
+}
+
+/// A cell of the comparison: a method with the normalization of the values it ranks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Variant {
+    method: Method,
+    normalization: Normalization,
+}
+
+impl Variant {
+    pub fn new(method: Method, normalization: Normalization) -> Self {
+        Self {
+            method,
+            normalization,
+        }
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// The name of the method, followed by the name of the normalization when it is not the one of the method, such
+    /// as `saw/vector`.
+    pub fn name(&self) -> String {
+        if self.normalization == self.method.normalization() {
+            self.method.name().to_string()
+        } else {
+            format!("{}/{}", self.method.name(), self.normalization.name())
+        }
+    }
+
+    pub fn rank(
+        &self,
+        problem: &Problem,
+        weights: Option<&Weights>,
+    ) -> Result<Ranking, CompareError> {
+        self.method
+            .rank_normalized(problem, weights, self.normalization)
+    }
+}
+
+impl From<Method> for Variant {
+    /// The method with its own normalization.
+    fn from(method: Method) -> Self {
+        Self::new(method, method.normalization())
+    }
+}
+
+/// Spearman's rank correlation of two rankings of the same alternatives, from -1 (reversed) to 1 (identical).
+pub fn spearman_correlation(a: &Ranking, b: &Ranking) -> f64 {
+    // NOTE: the caller guarantees that the rankings have the same alternatives
+    let size = a.len() as f64;
+    if a.len() < 2 {
+        return 1.;
+    }
+    let squared_differences: f64 = a
+        .positions()
+        .into_iter()
+        .zip(b.positions())
+        .map(|(x, y)| (x as f64 - y as f64).powi(2))
+        .sum();
+    1. - 6. * squared_differences / (size * (size.powi(2) - 1.))
+}
+
+/// Kendall's rank correlation (tau) of two rankings of the same alternatives, from -1 (reversed) to 1 (identical).
+pub fn kendall_correlation(a: &Ranking, b: &Ranking) -> f64 {
+    // NOTE: the caller guarantees that the rankings have the same alternatives
+    let size = a.len();
+    if size < 2 {
+        return 1.;
+    }
+    let (a, b) = (a.positions(), b.positions());
+    let mut concordance = 0.;
+    for i in 0..size {
+        for j in i + 1..size {
+            // positions are never tied
+            concordance += if (a[i] < a[j]) == (b[i] < b[j]) {
+                1.
+            } else {
+                -1.
+            };
+        }
+    }
+    concordance / (size * (size - 1) / 2) as f64
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Compare {
+    methods: Option<Vec<Method>>,
+    normalizations: Option<Vec<Normalization>>,
+    weights: Option<Weights>,
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum CompareError {
+    #[error("At least one method is required")]
+    NoMethods,
+    #[error("At least one normalization is required")]
+    NoNormalizations,
+    #[error("The values of '{0}' must not be negative for the vector normalization")]
+    NegativeValues(String),
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+    #[error(transparent)]
+    Scoring(#[from] ScoringError),
+    #[error(transparent)]
+    Multimoora(#[from] MultimooraError),
+    #[error(transparent)]
+    GreyRelational(#[from] GreyRelationalError),
+}
+
+impl CompareError {
+    /// Whether the method does not apply to the problem, rather than being misconfigured.
+    fn is_inapplicable(&self) -> bool {
+        matches!(
+            self,
+            CompareError::Scoring(ScoringError::NonPositiveValues(_))
+                | CompareError::Multimoora(MultimooraError::NonPositiveValues(_))
+                | CompareError::NegativeValues(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    variants: Vec<Variant>,
+    skipped: Vec<(Variant, CompareError)>,
+    rankings: Vec<Ranking>,
+    positions: Array2<f64>,
+    spearman: Array2<f64>,
+    kendall: Array2<f64>,
+}
+
+impl Comparison {
+    /// The methods, with their normalizations, that ranked the problem.
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    /// The methods, with their normalizations, that do not apply to the problem, with the reason: they are not part of
+    /// the comparison.
+    pub fn skipped(&self) -> &[(Variant, CompareError)] {
+        &self.skipped
+    }
+
+    /// The ranking of each method, in the same order of the variants.
+    pub fn rankings(&self) -> &[Ranking] {
+        &self.rankings
+    }
+
+    /// The side-by-side table: one row for each alternative, one column for each variant, with the position of the
+    /// alternative in the ranking of the method (0 being the best).
+    pub fn positions(&self) -> ArrayView2<'_, f64> {
+        self.positions.view()
+    }
+
+    /// Spearman's rank correlation between each pair of methods.
+    pub fn spearman(&self) -> ArrayView2<'_, f64> {
+        self.spearman.view()
+    }
+
+    /// Kendall's rank correlation between each pair of methods.
+    pub fn kendall(&self) -> ArrayView2<'_, f64> {
+        self.kendall.view()
+    }
+
+    /// The difference between the worst and the best position of each alternative across the methods.
+    pub fn spreads(&self) -> Array1<f64> {
+        self.positions.map_axis(ndarray::Axis(1), |row| {
+            let best = row.iter().copied().fold(f64::INFINITY, f64::min);
+            let worst = row.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            worst - best
+        })
+    }
+
+    /// The alternatives whose position varies by at least `spread` across the methods.
+    pub fn unstable_alternatives(&self, spread: usize) -> Vec<usize> {
+        self.spreads()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &s)| s >= spread as f64)
+            .map(|(alternative, _)| alternative)
+            .collect()
+    }
+}
+
+fn correlations(rankings: &[Ranking], correlation: fn(&Ranking, &Ranking) -> f64) -> Array2<f64> {
+    let size = rankings.len();
+    Array2::from_shape_fn((size, size), |(a, b)| {
+        correlation(&rankings[a], &rankings[b])
+    })
+}
+
+impl Compare {
+    /// A comparison of all the available methods, each one with its own normalization, with equal weights.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_methods(mut self, methods: Vec<Method>) -> Self {
+        self.methods = Some(methods);
+        self
+    }
+
+    /// Ranks the values with each of the given normalizations, instead of the own normalization of each method.
+    pub fn with_normalizations(mut self, normalizations: Vec<Normalization>) -> Self {
+        self.normalizations = Some(normalizations);
+        self
+    }
+
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    pub fn run(&self, problem: &Problem) -> Result<Comparison, CompareError> {
+        let methods = self.methods.clone().unwrap_or_else(|| Method::ALL.to_vec());
+        if methods.is_empty() {
+            return Err(CompareError::NoMethods);
+        }
+
+        let variants: Vec<Variant> = match &self.normalizations {
+            None => methods.into_iter().map(Variant::from).collect(),
+            Some(normalizations) if normalizations.is_empty() => {
+                return Err(CompareError::NoNormalizations);
+            }
+            Some(normalizations) => methods
+                .into_iter()
+                .flat_map(|m| normalizations.iter().map(move |&n| Variant::new(m, n)))
+                .collect(),
+        };
+
+        let mut ranked = Vec::with_capacity(variants.len());
+        let mut rankings = Vec::with_capacity(variants.len());
+        let mut skipped = Vec::new();
+        for variant in variants {
+            match variant.rank(problem, self.weights.as_ref()) {
+                Ok(ranking) => {
+                    ranked.push(variant);
+                    rankings.push(ranking);
+                }
+                Err(err) if err.is_inapplicable() => skipped.push((variant, err)),
+                Err(err) => return Err(err),
+            }
+        }
+        // without any method left, the reason of the first one is the reason of all of them
+        if rankings.is_empty() {
+            // NOTE: there is at least one method, so it is skipped
+            return Err(skipped.swap_remove(0).1);
+        }
+        let positions = Array2::from_shape_fn((problem.alternatives(), ranked.len()), |(a, m)| {
+            rankings[m].positions()[a] as f64
+        });
+
+        Ok(Comparison {
+            spearman: correlations(&rankings, spearman_correlation),
+            kendall: correlations(&rankings, kendall_correlation),
+            variants: ranked,
+            skipped,
+            rankings,
+            positions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{
+        Name, Values, VariableAutoscale, VariableInvertedAutoscale, VariableProperties,
+    };
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        // the methods disagree on the second and the third alternatives
+        let variable = |n: &str, values| -> Box<dyn VariableProperties> {
+            Box::new(VariableAutoscale::new(
+                Name::try_from(n).unwrap(),
+                Values::try_from(values).unwrap(),
+            ))
+        };
+        Problem::define(vec![
+            variable("x", array![0., 0., 1.]),
+            variable("y", array![0., 1., 0.5]),
+            variable("z", array![0., 1., 0.5]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_correlations() {
+        let a = Ranking::from_order(vec![0, 1, 2, 3]).unwrap();
+        let b = Ranking::from_order(vec![3, 2, 1, 0]).unwrap();
+        let c = Ranking::from_order(vec![1, 0, 2, 3]).unwrap();
+        assert_ulps_eq!(spearman_correlation(&a, &a), 1.);
+        assert_ulps_eq!(spearman_correlation(&a, &b), -1.);
+        assert_ulps_eq!(spearman_correlation(&a, &c), 0.8);
+        assert_ulps_eq!(kendall_correlation(&a, &a), 1.);
+        assert_ulps_eq!(kendall_correlation(&a, &b), -1.);
+        assert_ulps_eq!(kendall_correlation(&a, &c), 2. / 3.);
+        let single = Ranking::from_order(vec![0]).unwrap();
+        assert_ulps_eq!(kendall_correlation(&single, &single), 1.);
+    }
+
+    #[test]
+    fn test_topsis() {
+        let ranking = Method::Topsis.rank(&create_test_problem(), None).unwrap();
+        assert_ulps_eq!(ranking.scores()[0], 1.);
+        assert_ulps_eq!(ranking.scores()[1], std::f64::consts::SQRT_2 - 1.);
+        assert_eq!(ranking.order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_compare() {
+        let comparison = Compare::new().run(&create_test_problem()).unwrap();
+        assert_eq!(
+            comparison.variants(),
+            &[
+                Variant::from(Method::DistanceToIdeal),
+                Variant::from(Method::Topsis),
+                Variant::from(Method::GreyRelational(0.5))
+            ]
+        );
+        assert_eq!(comparison.rankings().len(), 3);
+        assert_eq!(comparison.positions().dim(), (3, 3));
+        assert_eq!(comparison.positions().column(0), array![0., 2., 1.]);
+        assert_eq!(comparison.positions().column(1), array![0., 1., 2.]);
+        assert_ulps_eq!(comparison.spearman()[[0, 1]], 0.5);
+        assert_ulps_eq!(comparison.kendall()[[1, 0]], 1. / 3.);
+        assert_ulps_eq!(comparison.spearman().diag(), array![1., 1., 1.]);
+        assert_eq!(comparison.spreads(), array![0., 1., 1.]);
+        assert_eq!(comparison.unstable_alternatives(1), vec![1, 2]);
+        assert!(comparison.unstable_alternatives(2).is_empty());
+    }
+
+    #[test]
+    fn test_compare_all_methods() {
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                Values::try_from(array![1., 2., 3.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("y").unwrap(),
+                Values::try_from(array![1., 3., 2.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        let comparison = Compare::new().run(&p).unwrap();
+        let methods: Vec<Method> = comparison.variants().iter().map(|v| v.method()).collect();
+        assert_eq!(methods, Method::ALL);
+        // all the methods agree on the second alternative being the best
+        assert!(comparison.rankings().iter().all(|r| r.best() == 1));
+    }
+
+    #[test]
+    fn test_compare_with_weights() {
+        let weights = Weights::try_from(vec![
+            (Name::try_from("x").unwrap(), 1.),
+            (Name::try_from("y").unwrap(), 0.),
+            (Name::try_from("z").unwrap(), 0.),
+        ])
+        .unwrap();
+        let comparison = Compare::new()
+            .with_methods(vec![Method::DistanceToIdeal])
+            .with_weights(weights)
+            .run(&create_test_problem())
+            .unwrap();
+        assert_eq!(comparison.rankings()[0].order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn run_no_methods_failure() {
+        let err = Compare::new()
+            .with_methods(vec![])
+            .run(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, CompareError::NoMethods);
+    }
+
+    #[test]
+    fn test_compare_skips_inapplicable_methods() {
+        // the linear normalization of SAW, WPM and WASPAS and the multiplicative form of MULTIMOORA need positive
+        // values, and x has zeros
+        let comparison = Compare::new().run(&create_test_problem()).unwrap();
+        let skipped: Vec<Method> = comparison
+            .skipped()
+            .iter()
+            .map(|(v, _)| v.method())
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                Method::Saw,
+                Method::Wpm,
+                Method::Waspas(0.5),
+                Method::Multimoora
+            ]
+        );
+        assert_eq!(
+            comparison.skipped()[0].1,
+            CompareError::Scoring(ScoringError::NonPositiveValues("x".to_string()))
+        );
+        assert!(!comparison.variants().contains(&Variant::from(Method::Saw)));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                Values::try_from(array![3., 4.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("y").unwrap(),
+                Values::try_from(array![1., 2.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        let (benefits, deviations) = Normalization::MinMax.normalize(&p).unwrap();
+        assert_ulps_eq!(benefits, array![[1., 0.], [0., 1.]]);
+        assert_ulps_eq!(deviations, array![[0., 1.], [1., 0.]]);
+        let (benefits, _) = Normalization::Linear.normalize(&p).unwrap();
+        assert_ulps_eq!(benefits, array![[1., 0.5], [0.75, 1.]]);
+        let (benefits, deviations) = Normalization::Vector.normalize(&p).unwrap();
+        let norm = 5_f64.sqrt();
+        assert_ulps_eq!(benefits, array![[0.4, 1. / norm], [0.2, 2. / norm]]);
+        assert_ulps_eq!(deviations, benefits.mapv(|x| 1. - x));
+    }
+
+    #[test]
+    fn test_rank_normalized_with_own_normalization() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            Values::try_from(array![2., 1., 4.]).unwrap(),
+        ))])
+        .unwrap();
+        for method in Method::ALL {
+            assert_eq!(
+                method.rank(&p, None).unwrap().order(),
+                method
+                    .rank_normalized(&p, None, method.normalization())
+                    .unwrap()
+                    .order()
+            );
+        }
+        // the benefits of the vector normalization give MULTIMOORA the same ranking as the signed weights
+        let ranking = Method::Multimoora.rank(&p, None).unwrap();
+        let benefits = Multimoora::new()
+            .rank_benefits(&p, &Normalization::Vector.normalize(&p).unwrap().0)
+            .unwrap();
+        assert_eq!(ranking.order(), benefits.ranking().order());
+    }
+
+    #[test]
+    fn test_compare_normalizations() {
+        let comparison = Compare::new()
+            .with_methods(vec![Method::Saw, Method::Topsis])
+            .with_normalizations(vec![Normalization::MinMax, Normalization::Vector])
+            .run(&create_test_problem())
+            .unwrap();
+        let names: Vec<String> = comparison.variants().iter().map(|v| v.name()).collect();
+        assert_eq!(
+            names,
+            vec!["saw/minmax", "saw/vector", "topsis", "topsis/vector"]
+        );
+        assert_eq!(comparison.positions().dim(), (3, 4));
+        // the first alternative has the best value of every variable
+        assert!(comparison.rankings().iter().all(|r| r.best() == 0));
+        assert!(comparison.skipped().is_empty());
+    }
+
+    #[test]
+    fn test_compare_skips_negative_values() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            Values::try_from(array![-1., 1.]).unwrap(),
+        ))])
+        .unwrap();
+        let comparison = Compare::new()
+            .with_methods(vec![Method::DistanceToIdeal])
+            .with_normalizations(Normalization::ALL.to_vec())
+            .run(&p)
+            .unwrap();
+        assert_eq!(
+            comparison.variants(),
+            &[Variant::from(Method::DistanceToIdeal)]
+        );
+        assert_eq!(
+            comparison.skipped()[1],
+            (
+                Variant::new(Method::DistanceToIdeal, Normalization::Vector),
+                CompareError::NegativeValues("x".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn run_no_normalizations_failure() {
+        let err = Compare::new()
+            .with_normalizations(vec![])
+            .run(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, CompareError::NoNormalizations);
+    }
+
+    #[test]
+    fn run_no_applicable_methods_failure() {
+        let err = Compare::new()
+            .with_methods(vec![Method::Saw, Method::Wpm])
+            .run(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            CompareError::Scoring(ScoringError::NonPositiveValues("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn run_weights_mismatch_failure() {
+        let weights = Weights::try_from(vec![(Name::try_from("x").unwrap(), 1.)]).unwrap();
+        let err = Compare::new()
+            .with_weights(weights)
+            .run(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, CompareError::Problem(ProblemError::WeightsMismatch));
+    }
+}
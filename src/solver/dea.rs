@@ -0,0 +1,515 @@
+//! This module benchmarks the alternatives (the decision making units) with the Data Envelopment Analysis (DEA).
+//!
+//! The variables of the problem are classified by their direction: the lower, the better (`VariableAutoscale`) are
+//! the inputs, such as costs or headcount; the higher, the better (`VariableInvertedAutoscale`) are the outputs, such
+//! as throughput or revenue. The input-oriented models find, for each unit, the smallest fraction of its inputs that a
+//! combination of the other units needs to produce at least the same outputs: the efficiency. CCR assumes constant
+//! returns to scale, BCC variable returns to scale. A second phase keeps that efficiency and maximizes the slacks, the
+//! input excesses and the output shortfalls left after the radial reduction, so that the targets are Pareto-efficient.
+//! The inputs must be positive: a unit that produces with no input could be replicated without end, and the slacks of
+//! the others would be unbounded. The outputs only need to be non-negative. The efficiencies do not depend on the
+//! units of the values, so the programs of each unit are scaled to its own values, which may differ from the others
+//! by many orders of magnitude.
+use super::linprog::{LinearProgram, LinearProgramError};
+use super::problem::Problem;
+use super::ranking::Ranking;
+use crate::variables::Name;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis, s};
+
+/// Efficiencies closer than this to 1 are efficient, and combination weights below this are not peers.
+const TOLERANCE: f64 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnsToScale {
+    /// The CCR model.
+    Constant,
+    /// The BCC model.
+    Variable,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dea {
+    returns_to_scale: ReturnsToScale,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum DeaError {
+    #[error("At least one input, a variable where the lower is the better, is required")]
+    NoInputs,
+    #[error("At least one output, a variable where the higher is the better, is required")]
+    NoOutputs,
+    #[error("The values of '{0}' must be non-negative")]
+    NegativeValues(String),
+    #[error("The values of '{0}' must be positive, as it is an input")]
+    NonPositiveInputs(String),
+    #[error(transparent)]
+    LinearProgram(#[from] LinearProgramError),
+}
+
+#[derive(Debug, Clone)]
+pub struct DeaResult {
+    inputs: Vec<Name>,
+    outputs: Vec<Name>,
+    efficiencies: Array1<f64>,
+    slacks: Array1<f64>,
+    lambdas: Array2<f64>,
+    input_targets: Array2<f64>,
+    output_targets: Array2<f64>,
+}
+
+impl DeaResult {
+    /// The names of the inputs, in the same order of the columns of the input targets.
+    pub fn inputs(&self) -> &[Name] {
+        &self.inputs
+    }
+
+    /// The names of the outputs, in the same order of the columns of the output targets.
+    pub fn outputs(&self) -> &[Name] {
+        &self.outputs
+    }
+
+    /// The efficiency of each unit, from 0 to 1 (efficient).
+    pub fn efficiencies(&self) -> ArrayView1<'_, f64> {
+        self.efficiencies.view()
+    }
+
+    /// The sum of the input excesses and the output shortfalls of each unit, after the radial reduction of its inputs.
+    pub fn slacks(&self) -> ArrayView1<'_, f64> {
+        self.slacks.view()
+    }
+
+    /// Whether the unit is Pareto-efficient: its efficiency is 1 and it has no slacks.
+    pub fn is_efficient(&self, unit: usize) -> bool {
+        self.efficiencies[unit] >= 1. - TOLERANCE && self.slacks[unit] <= TOLERANCE
+    }
+
+    /// The units ranked by efficiency.
+    pub fn ranking(&self) -> Ranking {
+        Ranking::descending(self.efficiencies.clone())
+    }
+
+    /// The efficient units that, combined, dominate the unit. An efficient unit is its own peer.
+    pub fn peers(&self, unit: usize) -> Vec<usize> {
+        self.lambdas
+            .row(unit)
+            .iter()
+            .enumerate()
+            .filter(|&(_, &lambda)| lambda > TOLERANCE)
+            .map(|(peer, _)| peer)
+            .collect()
+    }
+
+    /// The weight of each peer in the combination: one row for each unit, one column for each peer.
+    pub fn lambdas(&self) -> ArrayView2<'_, f64> {
+        self.lambdas.view()
+    }
+
+    /// The inputs that would make each unit efficient: the radial reduction minus the input excesses. One row for each
+    /// unit, one column for each input.
+    pub fn input_targets(&self) -> ArrayView2<'_, f64> {
+        self.input_targets.view()
+    }
+
+    /// The outputs that would make each unit efficient: the current outputs plus the shortfalls. One row for each unit,
+    /// one column for each output.
+    pub fn output_targets(&self) -> ArrayView2<'_, f64> {
+        self.output_targets.view()
+    }
+}
+
+impl Dea {
+    /// The CCR model, with constant returns to scale.
+    pub fn ccr() -> Self {
+        Self {
+            returns_to_scale: ReturnsToScale::Constant,
+        }
+    }
+
+    /// The BCC model, with variable returns to scale.
+    pub fn bcc() -> Self {
+        Self {
+            returns_to_scale: ReturnsToScale::Variable,
+        }
+    }
+
+    pub fn returns_to_scale(&self) -> ReturnsToScale {
+        self.returns_to_scale
+    }
+
+    /// The highest lambda of each unit in the programs of a unit: the lambda that uses as much of an input as theta
+    /// times the input of the unit, and at most 1 with variable returns to scale. The programs find each lambda as a
+    /// fraction of it, so that the coefficients stay comparable when the values differ by orders of magnitude.
+    fn lambda_scales(&self, unit: usize, theta: f64, inputs: &Array2<f64>) -> Array1<f64> {
+        let highest = if self.returns_to_scale == ReturnsToScale::Variable {
+            1.
+        } else {
+            f64::INFINITY
+        };
+        inputs.map_axis(Axis(1), |other| {
+            other
+                .iter()
+                .zip(inputs.row(unit))
+                .fold(highest, |a, (&x, &x_unit)| a.min(theta * x_unit / x))
+        })
+    }
+
+    /// The scale of each output in the programs of a unit: the most that a unit can produce with its highest lambda.
+    fn output_scales(outputs: &Array2<f64>, lambda_scales: &Array1<f64>) -> Array1<f64> {
+        outputs.map_axis(Axis(0), |output| {
+            let scale = output
+                .iter()
+                .zip(lambda_scales)
+                .fold(0., |a: f64, (&y, &lambda)| a.max(y * lambda));
+            if scale > 0. { scale } else { 1. }
+        })
+    }
+
+    /// The envelopment program of a unit, on the variables theta and the lambdas, returns theta. Each input is
+    /// divided by the input of the unit, each output by its scale.
+    fn solve_efficiency(
+        &self,
+        unit: usize,
+        inputs: &Array2<f64>,
+        outputs: &Array2<f64>,
+    ) -> Result<f64, DeaError> {
+        let units = inputs.nrows();
+        let lambda_scales = self.lambda_scales(unit, 1., inputs);
+        let output_scales = Dea::output_scales(outputs, &lambda_scales);
+        let mut c = Array1::zeros(units + 1);
+        c[0] = 1.;
+
+        // sum(lambda_j * x_ij) - theta * x_io <= 0, and -sum(lambda_j * y_rj) <= -y_ro
+        let rows = inputs.ncols() + outputs.ncols();
+        let mut a_ub = Array2::zeros((rows, units + 1));
+        let mut b_ub = Array1::zeros(rows);
+        for (i, input) in inputs.axis_iter(Axis(1)).enumerate() {
+            a_ub[[i, 0]] = -1.;
+            a_ub.slice_mut(s![i, 1..])
+                .assign(&(&input * &lambda_scales / input[unit]));
+        }
+        for (r, output) in outputs.axis_iter(Axis(1)).enumerate() {
+            let row = inputs.ncols() + r;
+            a_ub.slice_mut(s![row, 1..])
+                .assign(&(&output * &lambda_scales / -output_scales[r]));
+            b_ub[row] = -output[unit] / output_scales[r];
+        }
+
+        let mut program = LinearProgram::minimize(c).with_less_equal(a_ub, b_ub);
+        if self.returns_to_scale == ReturnsToScale::Variable {
+            // the lambdas sum to 1: only convex combinations of the units
+            let mut a_eq = Array2::zeros((1, units + 1));
+            a_eq.slice_mut(s![0, 1..]).assign(&lambda_scales);
+            program = program.with_equal(a_eq, Array1::ones(1));
+        }
+        Ok(program.solve()?.x()[0])
+    }
+
+    /// The slacks program of a unit with the efficiency theta, on the variables lambdas, input excesses and output
+    /// shortfalls, returns the lambdas and the sum of the slacks. The program maximizes the slacks relative to the
+    /// reduced inputs and to the scales of the outputs of the unit.
+    fn solve_slacks(
+        &self,
+        unit: usize,
+        theta: f64,
+        inputs: &Array2<f64>,
+        outputs: &Array2<f64>,
+    ) -> Result<(Array1<f64>, f64), DeaError> {
+        let units = inputs.nrows();
+        let (m, r) = (inputs.ncols(), outputs.ncols());
+        // the inputs are relative to the reduced inputs of the unit, unless there are none
+        let reference = if theta > 0. { theta } else { 1. };
+        let lambda_scales = self.lambda_scales(unit, reference, inputs);
+        let output_scales = Dea::output_scales(outputs, &lambda_scales);
+        let mut c = Array1::ones(units + m + r);
+        c.slice_mut(s![..units]).fill(0.);
+
+        // sum(lambda_j * x_ij) + s_i = theta * x_io, and sum(lambda_j * y_rj) - s_r = y_ro
+        let mut a_eq = Array2::zeros((m + r, units + m + r));
+        let mut b_eq = Array1::zeros(m + r);
+        for (i, input) in inputs.axis_iter(Axis(1)).enumerate() {
+            a_eq.slice_mut(s![i, ..units])
+                .assign(&(&input * &lambda_scales / (reference * input[unit])));
+            a_eq[[i, units + i]] = 1.;
+            b_eq[i] = theta / reference;
+        }
+        for (k, output) in outputs.axis_iter(Axis(1)).enumerate() {
+            let row = m + k;
+            a_eq.slice_mut(s![row, ..units])
+                .assign(&(&output * &lambda_scales / output_scales[k]));
+            a_eq[[row, units + row]] = -1.;
+            b_eq[row] = output[unit] / output_scales[k];
+        }
+
+        let mut program = LinearProgram::maximize(c).with_equal(a_eq, b_eq);
+        if self.returns_to_scale == ReturnsToScale::Variable {
+            let mut a_convex = Array2::zeros((1, units + m + r));
+            a_convex.slice_mut(s![0, ..units]).assign(&lambda_scales);
+            program = program.with_equal(a_convex, Array1::ones(1));
+        }
+        let solution = program.solve()?;
+        let x = solution.x();
+        let scales = ndarray::concatenate![Axis(0), &inputs.row(unit) * reference, output_scales];
+        Ok((
+            &x.slice(s![..units]) * &lambda_scales,
+            x.slice(s![units..]).dot(&scales),
+        ))
+    }
+
+    pub fn evaluate(&self, problem: &Problem) -> Result<DeaResult, DeaError> {
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut input_values = Vec::new();
+        let mut output_values = Vec::new();
+        for variable in problem.variables() {
+            let values = variable.values().values();
+            let name = variable.name().as_str().to_string();
+            if variable.higher_is_better() {
+                if values.iter().any(|&x| x < 0.) {
+                    return Err(DeaError::NegativeValues(name));
+                }
+                outputs.push(variable.name().clone());
+                output_values.push(values);
+            } else {
+                if values.iter().any(|&x| x <= 0.) {
+                    return Err(DeaError::NonPositiveInputs(name));
+                }
+                inputs.push(variable.name().clone());
+                input_values.push(values);
+            }
+        }
+        if inputs.is_empty() {
+            return Err(DeaError::NoInputs);
+        }
+        if outputs.is_empty() {
+            return Err(DeaError::NoOutputs);
+        }
+        // one row for each unit, one column for each input or output
+        let input_values = ndarray::stack(Axis(1), &input_values).unwrap();
+        let output_values = ndarray::stack(Axis(1), &output_values).unwrap();
+
+        let units = problem.alternatives();
+        let mut efficiencies = Array1::zeros(units);
+        let mut slacks = Array1::zeros(units);
+        let mut lambdas = Array2::zeros((units, units));
+        for unit in 0..units {
+            // the unit itself is always feasible with theta 1, and theta is never negative: with variable returns to
+            // scale, a combination of the units needs at least the smallest value of each input
+            let lowest = match self.returns_to_scale {
+                ReturnsToScale::Constant => 0.,
+                ReturnsToScale::Variable => input_values
+                    .axis_iter(Axis(1))
+                    .map(|input| input.fold(f64::INFINITY, |a, &b| a.min(b)) / input[unit])
+                    .fold(0., f64::max),
+            };
+            let efficiency = self
+                .solve_efficiency(unit, &input_values, &output_values)?
+                .clamp(lowest, 1.);
+            let (unit_lambdas, unit_slacks) =
+                self.solve_slacks(unit, efficiency, &input_values, &output_values)?;
+            efficiencies[unit] = efficiency;
+            slacks[unit] = unit_slacks;
+            lambdas.row_mut(unit).assign(&unit_lambdas);
+        }
+
+        Ok(DeaResult {
+            inputs,
+            outputs,
+            efficiencies,
+            slacks,
+            input_targets: lambdas.dot(&input_values),
+            output_targets: lambdas.dot(&output_values),
+            lambdas,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        // four data centres: one input, one output
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![2., 4., 4., 6.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("throughput").unwrap(),
+                Values::try_from(array![2., 6., 3., 7.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    fn create_test_problem_with(cost: Array1<f64>, revenue: Array1<f64>) -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(cost).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("revenue").unwrap(),
+                Values::try_from(revenue).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ccr() {
+        let result = Dea::ccr().evaluate(&create_test_problem()).unwrap();
+        assert_eq!(result.inputs(), &[Name::try_from("cost").unwrap()]);
+        assert_eq!(result.outputs(), &[Name::try_from("throughput").unwrap()]);
+        // the best ratio of throughput to cost is 1.5, of the second unit
+        assert_ulps_eq!(
+            result.efficiencies(),
+            array![1. / 1.5, 1., 0.5, 7. / 9.],
+            epsilon = 1e-9
+        );
+        assert!(result.is_efficient(1));
+        assert!(!result.is_efficient(0));
+        assert_eq!(result.ranking().order(), &[1, 3, 0, 2]);
+        for unit in 0..4 {
+            assert_eq!(result.peers(unit), vec![1]);
+        }
+        // the third unit needs half of its cost to produce the same throughput
+        assert_ulps_eq!(result.input_targets()[[2, 0]], 2., epsilon = 1e-9);
+        assert_ulps_eq!(result.output_targets()[[2, 0]], 3., epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_bcc() {
+        let result = Dea::bcc().evaluate(&create_test_problem()).unwrap();
+        // the smallest and the biggest units are efficient with variable returns to scale
+        assert_ulps_eq!(result.efficiencies()[0], 1., epsilon = 1e-9);
+        assert_ulps_eq!(result.efficiencies()[1], 1., epsilon = 1e-9);
+        assert_ulps_eq!(result.efficiencies()[3], 1., epsilon = 1e-9);
+        // the third unit is dominated by a mix of the first two units
+        assert!(!result.is_efficient(2));
+        assert_eq!(result.peers(2), vec![0, 1]);
+        assert_ulps_eq!(result.lambdas().row(2).sum(), 1., epsilon = 1e-9);
+        assert!(result.output_targets()[[2, 0]] >= 3. - 1e-9);
+        assert!(result.input_targets()[[2, 0]] <= result.efficiencies()[2] * 4. + 1e-9);
+    }
+
+    #[test]
+    fn test_slacks() {
+        // the third unit has an efficiency of 1, but the first one produces the same with less storage
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cpu").unwrap(),
+                Values::try_from(array![1., 2., 1.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("storage").unwrap(),
+                Values::try_from(array![2., 1., 3.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("throughput").unwrap(),
+                Values::try_from(array![1., 1., 1.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        let result = Dea::ccr().evaluate(&p).unwrap();
+        assert_ulps_eq!(result.efficiencies()[2], 1., epsilon = 1e-9);
+        assert_ulps_eq!(result.slacks()[2], 1., epsilon = 1e-9);
+        assert!(!result.is_efficient(2));
+        assert_eq!(result.peers(2), vec![0]);
+        // the target is the first unit, not the third one itself
+        assert_ulps_eq!(
+            result.input_targets().row(2),
+            array![1., 2.],
+            epsilon = 1e-9
+        );
+        assert!(result.is_efficient(0) && result.is_efficient(1));
+    }
+
+    #[test]
+    fn test_values_of_different_magnitudes() {
+        let p = create_test_problem_with(array![1., 2., 3.], array![1e-7, 1., 2.]);
+        let result = Dea::ccr().evaluate(&p).unwrap();
+        assert_ulps_eq!(
+            result.efficiencies(),
+            array![1.5e-7, 0.75, 1.],
+            epsilon = 1e-9
+        );
+        let p = create_test_problem_with(array![1e-5, 1e5, 2.], array![1e5, 1e-5, 1.]);
+        let result = Dea::ccr().evaluate(&p).unwrap();
+        assert_ulps_eq!(
+            result.efficiencies(),
+            array![1., 1e-20, 5e-11],
+            max_ulps = 100
+        );
+        // the theta and the single lambda of the unit itself are always feasible, whatever the magnitudes
+        for spread in [1e5, 1e6, 1e8, 1e50] {
+            let p = create_test_problem_with(
+                array![1. / spread, spread, 2.],
+                array![spread, 1. / spread, 1.],
+            );
+            for dea in [Dea::ccr(), Dea::bcc()] {
+                let result = dea.evaluate(&p).unwrap();
+                assert!(result.is_efficient(0));
+                assert!(
+                    result
+                        .efficiencies()
+                        .iter()
+                        .all(|&e| (0. ..=1.).contains(&e))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_no_outputs_failure() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("cost").unwrap(),
+            Values::try_from(array![1., 2.]).unwrap(),
+        ))])
+        .unwrap();
+        assert_eq!(Dea::ccr().evaluate(&p).unwrap_err(), DeaError::NoOutputs);
+    }
+
+    #[test]
+    fn evaluate_no_inputs_failure() {
+        let p = Problem::define(vec![Box::new(VariableInvertedAutoscale::new(
+            Name::try_from("revenue").unwrap(),
+            Values::try_from(array![1., 2.]).unwrap(),
+        ))])
+        .unwrap();
+        assert_eq!(Dea::bcc().evaluate(&p).unwrap_err(), DeaError::NoInputs);
+    }
+
+    #[test]
+    fn test_zero_outputs() {
+        // a unit can produce nothing: it is not efficient at all
+        let p = create_test_problem_with(array![1., 1.], array![0., 2.]);
+        let result = Dea::ccr().evaluate(&p).unwrap();
+        assert_ulps_eq!(result.efficiencies(), array![0., 1.], epsilon = 1e-9);
+    }
+
+    #[test]
+    fn evaluate_negative_values_failure() {
+        let p = create_test_problem_with(array![1., 2.], array![1., -2.]);
+        assert_eq!(
+            Dea::ccr().evaluate(&p).unwrap_err(),
+            DeaError::NegativeValues("revenue".to_string())
+        );
+    }
+
+    #[test]
+    fn evaluate_non_positive_inputs_failure() {
+        // a unit without inputs would make the slacks of the others unbounded
+        for cost in [array![0., 1.], array![1., -2.]] {
+            let p = create_test_problem_with(cost, array![1., 1.]);
+            assert_eq!(
+                Dea::ccr().evaluate(&p).unwrap_err(),
+                DeaError::NonPositiveInputs("cost".to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! This module diagnoses the issues of a problem that silently bias its solutions.
+//!
+//! A constant variable is rescaled to zeros: it counts for nothing, whatever its weight. Two variables that are highly
+//! correlated in the rescaled matrix (so in the same direction) count the same concern twice. An outlier stretches the
+//! min-max scale of its variable, and compresses the values of all the other alternatives. A dominated alternative can
+//! never be the best one. Each issue comes with a severity, from the most harmful to the least.
+use super::problem::Problem;
+use crate::variables::Name;
+use ndarray::{ArrayView1, Axis};
+use std::fmt;
+
+/// The correlation above which two variables are reported.
+const CORRELATION_THRESHOLD: f64 = 0.9;
+/// The modified z-score above which a value is an outlier.
+const OUTLIER_THRESHOLD: f64 = 3.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// All the values of the variable are the same.
+    ConstantVariable(Name),
+    /// The rescaled values of the variables have this correlation.
+    CorrelatedVariables(Name, Name, f64),
+    /// The value of the alternative is far from the others, with this modified z-score.
+    Outlier {
+        variable: Name,
+        alternative: usize,
+        value: f64,
+        score: f64,
+    },
+    /// The alternative is dominated by another one: not better in any variable, worse in at least one.
+    DominatedAlternative { alternative: usize, by: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnosis {
+    issues: Vec<Issue>,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        };
+        write!(f, "{severity}")
+    }
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::ConstantVariable(_) => Severity::High,
+            Issue::CorrelatedVariables(..) | Issue::Outlier { .. } => Severity::Medium,
+            Issue::DominatedAlternative { .. } => Severity::Low,
+        }
+    }
+}
+
+impl Diagnosis {
+    /// The issues, from the most severe. Issues with the same severity keep the order of the variables and of the
+    /// alternatives.
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The severity of the worst issue, if any.
+    pub fn severity(&self) -> Option<Severity> {
+        self.issues.iter().map(Issue::severity).max()
+    }
+}
+
+fn pearson_correlation(x: ArrayView1<f64>, y: ArrayView1<f64>) -> f64 {
+    // NOTE: the caller guarantees that neither vector is constant
+    let dx = &x - x.mean().unwrap();
+    let dy = &y - y.mean().unwrap();
+    dx.dot(&dy) / (dx.dot(&dx) * dy.dot(&dy)).sqrt()
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.
+    } else {
+        sorted[middle]
+    }
+}
+
+/// The modified z-score of each value, with the median absolute deviation. When half of the values are the same, the
+/// median absolute deviation is 0: the mean absolute deviation takes its place. None when all the values are the same.
+fn modified_z_scores(values: ArrayView1<f64>) -> Option<Vec<f64>> {
+    let values = values.to_vec();
+    let center = median(&values);
+    let deviations: Vec<f64> = values.iter().map(|x| (x - center).abs()).collect();
+    let mad = median(&deviations);
+    if mad > 0. {
+        return Some(values.iter().map(|x| 0.6745 * (x - center) / mad).collect());
+    }
+    // 1.253314 * the mean absolute deviation estimates the standard deviation of a normal distribution
+    let mean_ad = deviations.iter().sum::<f64>() / deviations.len() as f64;
+    if mean_ad == 0. {
+        return None;
+    }
+    Some(
+        values
+            .iter()
+            .map(|x| (x - center) / (1.253314 * mean_ad))
+            .collect(),
+    )
+}
+
+impl Problem {
+    /// Reports the constant variables, the highly correlated variables, the outliers and the dominated alternatives.
+    pub fn diagnose(&self) -> Diagnosis {
+        let names: Vec<&Name> = self.names().collect();
+        let matrix = self.get_problem_matrix();
+        let mut issues = Vec::new();
+
+        let constant: Vec<bool> = matrix
+            .axis_iter(Axis(1))
+            .map(|column| column.iter().all(|&x| x == column[0]))
+            .collect();
+        for (name, _) in names.iter().zip(&constant).filter(|(_, c)| **c) {
+            issues.push(Issue::ConstantVariable((*name).clone()));
+        }
+
+        for a in 0..names.len() {
+            for b in a + 1..names.len() {
+                if constant[a] || constant[b] {
+                    continue;
+                }
+                let correlation = pearson_correlation(matrix.column(a), matrix.column(b));
+                if correlation >= CORRELATION_THRESHOLD {
+                    issues.push(Issue::CorrelatedVariables(
+                        names[a].clone(),
+                        names[b].clone(),
+                        correlation,
+                    ));
+                }
+            }
+        }
+
+        for (name, variable) in names.iter().zip(self.variables()) {
+            let values = variable.values().values();
+            let Some(scores) = modified_z_scores(values) else {
+                continue;
+            };
+            for (alternative, &score) in scores.iter().enumerate() {
+                if score.abs() > OUTLIER_THRESHOLD {
+                    issues.push(Issue::Outlier {
+                        variable: (*name).clone(),
+                        alternative,
+                        value: values[alternative],
+                        score,
+                    });
+                }
+            }
+        }
+
+        let rows: Vec<ArrayView1<f64>> = matrix.rows().into_iter().collect();
+        for (alternative, row) in rows.iter().enumerate() {
+            let dominating = rows.iter().position(|other| {
+                other.iter().zip(row).all(|(x, y)| x <= y)
+                    && other.iter().zip(row).any(|(x, y)| x < y)
+            });
+            if let Some(by) = dominating {
+                issues.push(Issue::DominatedAlternative { alternative, by });
+            }
+        }
+
+        // the sort is stable: the issues with the same severity keep their order
+        issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity()));
+        Diagnosis { issues }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![10., 12., 11., 13., 100.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("price").unwrap(),
+                Values::try_from(array![20., 24., 22., 26., 190.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![5., 3., 4., 2., 1.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("region").unwrap(),
+                Values::try_from(array![1., 1., 1., 1., 1.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let diagnosis = create_test_problem().diagnose();
+        let issues = diagnosis.issues();
+        assert_eq!(diagnosis.severity(), Some(Severity::High));
+        assert_eq!(
+            issues[0],
+            Issue::ConstantVariable(Name::try_from("region").unwrap())
+        );
+
+        // cost and price are the same concern
+        let Issue::CorrelatedVariables(a, b, correlation) = &issues[1] else {
+            panic!("expected correlated variables, found {:?}", issues[1]);
+        };
+        assert_eq!(
+            (a, b),
+            (
+                &Name::try_from("cost").unwrap(),
+                &Name::try_from("price").unwrap()
+            )
+        );
+        assert!(*correlation > 0.99);
+
+        // the last alternative is far from the others in cost and price
+        let outliers: Vec<(&Name, usize)> = issues
+            .iter()
+            .filter_map(|issue| match issue {
+                Issue::Outlier {
+                    variable,
+                    alternative,
+                    ..
+                } => Some((variable, *alternative)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            outliers,
+            vec![
+                (&Name::try_from("cost").unwrap(), 4),
+                (&Name::try_from("price").unwrap(), 4)
+            ]
+        );
+
+        // the first alternative is the cheapest and the best
+        let dominated: Vec<&Issue> = issues
+            .iter()
+            .filter(|i| i.severity() == Severity::Low)
+            .collect();
+        assert_eq!(dominated.len(), 4);
+        assert_eq!(
+            dominated[0],
+            &Issue::DominatedAlternative {
+                alternative: 1,
+                by: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_diagnose_no_issues() {
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![1., 2., 3.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("time").unwrap(),
+                Values::try_from(array![3., 2., 1.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        let diagnosis = p.diagnose();
+        assert!(diagnosis.is_empty());
+        assert_eq!(diagnosis.severity(), None);
+    }
+
+    #[test]
+    fn test_opposite_directions_are_not_correlated() {
+        // the same raw values, one to minimize and one to maximize: a trade-off, not a double count
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![1., 2., 3.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![1., 2., 3.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        assert!(p.diagnose().is_empty());
+    }
+
+    #[test]
+    fn test_modified_z_scores() {
+        let scores = modified_z_scores(array![1., 2., 3., 4., 50.].view()).unwrap();
+        assert_ulps_eq!(scores[2], 0.);
+        assert_ulps_eq!(scores[4], 0.6745 * 47.);
+        assert!(modified_z_scores(array![1., 1., 1.].view()).is_none());
+    }
+
+    #[test]
+    fn test_modified_z_scores_zero_mad() {
+        // most of the values are the same: the median absolute deviation is 0
+        let values = array![10., 10., 10., 11., 1000.];
+        let scores = modified_z_scores(values.view()).unwrap();
+        assert_ulps_eq!(scores[0], 0.);
+        assert_ulps_eq!(scores[4], 990. / (1.253314 * 198.2));
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("cost").unwrap(),
+            Values::try_from(values).unwrap(),
+        ))])
+        .unwrap();
+        let diagnosis = p.diagnose();
+        let outliers: Vec<usize> = diagnosis
+            .issues()
+            .iter()
+            .filter_map(|issue| match issue {
+                Issue::Outlier { alternative, .. } => Some(*alternative),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(outliers, vec![4]);
+    }
+
+    #[test]
+    fn test_severity_display() {
+        assert_eq!(Severity::High.to_string(), "high");
+        assert!(Severity::High > Severity::Low);
+    }
+}
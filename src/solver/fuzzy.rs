@@ -0,0 +1,136 @@
+//! This module define a problem as a matrix of fuzzy variables, ranked with fuzzy TOPSIS.
+//!
+//! As for crisp problems, the fuzzy ideal is the zero vector (the best rescaled values) and the fuzzy anti-ideal is
+//! the ones vector. Distances between fuzzy numbers are vertex distances, summed over the variables.
+use super::problem::{ProblemError, define_variables};
+use super::ranking::Ranking;
+use crate::variables::{FuzzyNumber, FuzzyVariableProperties, Name};
+use ndarray::Array1;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct FuzzyProblem {
+    variables: BTreeMap<Name, Box<dyn FuzzyVariableProperties>>,
+}
+
+impl FuzzyProblem {
+    pub fn define(variables: Vec<Box<dyn FuzzyVariableProperties>>) -> Result<Self, ProblemError> {
+        let variables = define_variables(variables, |v| (v.name(), v.values().len()))?;
+        Ok(Self { variables })
+    }
+
+    fn get_distances(&self) -> (Array1<f64>, Array1<f64>) {
+        let ideal = FuzzyNumber::crisp(0.).unwrap();
+        let anti_ideal = FuzzyNumber::crisp(1.).unwrap();
+        let size = self.variables.values().next().unwrap().values().len();
+
+        let mut to_ideal = Array1::zeros(size);
+        let mut to_anti_ideal = Array1::zeros(size);
+        for variable in self.variables.values() {
+            for (i, n) in variable.rescale().values().iter().enumerate() {
+                to_ideal[i] += n.distance(&ideal);
+                to_anti_ideal[i] += n.distance(&anti_ideal);
+            }
+        }
+        (to_ideal, to_anti_ideal)
+    }
+
+    /// Ranks the alternatives by closeness coefficient, from 1 (the ideal) to 0 (the anti-ideal).
+    pub fn rank(&self) -> Ranking {
+        let (to_ideal, to_anti_ideal) = self.get_distances();
+        // NOTE: denominators are always positive, as the two distances of a fuzzy number sum to at least 1
+        let closeness = &to_anti_ideal / (&to_ideal + &to_anti_ideal);
+        Ranking::descending(closeness)
+    }
+
+    pub fn solve(&self) -> usize {
+        self.rank().best()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{
+        FuzzyValues, FuzzyVariableAutoscale, FuzzyVariableInvertedAutoscale, LinguisticScale,
+        Values,
+    };
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> FuzzyProblem {
+        let scale = LinguisticScale::five_point();
+        FuzzyProblem::define(vec![
+            // cost: the lower, the better
+            Box::new(FuzzyVariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                FuzzyValues::from(Values::try_from(array![10., 12., 14.]).unwrap()),
+            )),
+            // experts' judgement: the higher, the better
+            Box::new(FuzzyVariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                scale.values(&["poor", "very good", "good"]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_problem_is_ranked() {
+        let p = create_test_problem();
+        let ranking = p.rank();
+        assert_eq!(ranking.order(), &[1, 0, 2]);
+        assert_eq!(p.solve(), 1);
+        assert!(ranking.scores().iter().all(|&c| (0. ..=1.).contains(&c)));
+    }
+
+    #[test]
+    fn crisp_problem_closeness() {
+        let p = FuzzyProblem::define(vec![Box::new(FuzzyVariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            FuzzyValues::from(Values::try_from(array![1., 2., 3.]).unwrap()),
+        ))])
+        .unwrap();
+        assert_ulps_eq!(p.rank().scores(), array![1., 0.5, 0.]);
+    }
+
+    #[test]
+    fn define_problem_empty_failure() {
+        let err = FuzzyProblem::define(vec![]).unwrap_err();
+        assert_eq!(err, ProblemError::Empty)
+    }
+
+    #[test]
+    fn define_problem_redefine_variable_failure() {
+        let values = FuzzyValues::from(Values::try_from(array![1.]).unwrap());
+        let err = FuzzyProblem::define(vec![
+            Box::new(FuzzyVariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                values.clone(),
+            )),
+            Box::new(FuzzyVariableInvertedAutoscale::new(
+                Name::try_from("x").unwrap(),
+                values,
+            )),
+        ])
+        .unwrap_err();
+        assert_eq!(err, ProblemError::RedefinitionVariable)
+    }
+
+    #[test]
+    fn define_problem_variable_with_different_size_failure() {
+        let err = FuzzyProblem::define(vec![
+            Box::new(FuzzyVariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                FuzzyValues::from(Values::try_from(array![1., 2.]).unwrap()),
+            )),
+            Box::new(FuzzyVariableInvertedAutoscale::new(
+                Name::try_from("y").unwrap(),
+                FuzzyValues::from(Values::try_from(array![1.]).unwrap()),
+            )),
+        ])
+        .unwrap_err();
+        assert_eq!(err, ProblemError::VariableSizeMismatch)
+    }
+}
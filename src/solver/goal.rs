@@ -0,0 +1,407 @@
+//! This module ranks problems with goal programming: the decision maker sets a target for some variables, and the
+//! alternatives are ranked by their weighted deviation from the targets.
+//!
+//! Each goal has a weight for the under-achievement (the value is below the target) and one for the over-achievement
+//! (the value is above the target): "under 50ms" only penalizes the over-achievement, "over 99.95%" only the
+//! under-achievement. The deviations are divided by the range of the rescaling of the variable (its bounds), to compare
+//! variables of different units. The weighted mode sums all the weighted deviations; the lexicographic mode compares
+//! the sums of each priority level, from the first one.
+use super::problem::{EntryError, Problem, entries_by_name, rescaled_distance};
+use super::ranking::Ranking;
+use crate::variables::Name;
+use ndarray::{Array2, ArrayView2};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal {
+    name: Name,
+    target: f64,
+    under_weight: f64,
+    over_weight: f64,
+    priority: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalMode {
+    /// All the weighted deviations are summed.
+    Weighted,
+    /// The weighted deviations are summed by priority level, and compared from the first level.
+    Lexicographic,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoalProgramming {
+    goals: Vec<Goal>,
+    mode: GoalMode,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum GoalProgrammingError {
+    #[error("At least one goal is required")]
+    NoGoals,
+    #[error("The goal '{0}' is not a variable of the problem")]
+    UnknownVariable(String),
+    #[error("Each variable can have one goal only: '{0}' has more than one")]
+    RedefinitionGoal(String),
+    #[error("The goal '{0}' must have a finite target and finite, non-negative weights")]
+    InvalidGoal(String),
+}
+
+impl From<EntryError> for GoalProgrammingError {
+    fn from(error: EntryError) -> Self {
+        match error {
+            EntryError::Unknown(name) => GoalProgrammingError::UnknownVariable(name),
+            EntryError::Invalid(name) => GoalProgrammingError::InvalidGoal(name),
+            EntryError::Redefinition(name) => GoalProgrammingError::RedefinitionGoal(name),
+            // NOTE: the goals can leave some variables out
+            EntryError::Missing(_) => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GoalDeviations {
+    goals: Vec<Name>,
+    under: Array2<f64>,
+    over: Array2<f64>,
+    priorities: Vec<usize>,
+    achievements: Array2<f64>,
+    ranking: Ranking,
+}
+
+impl Goal {
+    /// A goal with explicit weights for the under-achievement and the over-achievement.
+    pub fn new(name: Name, target: f64, under_weight: f64, over_weight: f64) -> Self {
+        Self {
+            name,
+            target,
+            under_weight,
+            over_weight,
+            priority: 1,
+        }
+    }
+
+    /// The value should be at least the target: only the under-achievement counts.
+    pub fn at_least(name: Name, target: f64) -> Self {
+        Self::new(name, target, 1., 0.)
+    }
+
+    /// The value should be at most the target: only the over-achievement counts.
+    pub fn at_most(name: Name, target: f64) -> Self {
+        Self::new(name, target, 0., 1.)
+    }
+
+    /// The value should be exactly the target: both deviations count.
+    pub fn exactly(name: Name, target: f64) -> Self {
+        Self::new(name, target, 1., 1.)
+    }
+
+    /// The priority level for the lexicographic mode: the lower, the more important. The default is 1.
+    pub fn with_priority(mut self, priority: usize) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    fn is_valid(&self) -> bool {
+        self.target.is_finite()
+            && [self.under_weight, self.over_weight]
+                .iter()
+                .all(|w| w.is_finite() && *w >= 0.)
+    }
+}
+
+impl GoalDeviations {
+    /// The names of the goals, in the same order of the columns of the deviations.
+    pub fn goals(&self) -> &[Name] {
+        &self.goals
+    }
+
+    /// The normalized under-achievements: one row for each alternative, one column for each goal.
+    pub fn under(&self) -> ArrayView2<'_, f64> {
+        self.under.view()
+    }
+
+    /// The normalized over-achievements: one row for each alternative, one column for each goal.
+    pub fn over(&self) -> ArrayView2<'_, f64> {
+        self.over.view()
+    }
+
+    /// The priority levels, from the most important, in the same order of the columns of the achievements.
+    pub fn priorities(&self) -> &[usize] {
+        &self.priorities
+    }
+
+    /// The sum of the weighted deviations: one row for each alternative, one column for each priority level (a single
+    /// column in the weighted mode). The lower, the better.
+    pub fn achievements(&self) -> ArrayView2<'_, f64> {
+        self.achievements.view()
+    }
+
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+}
+
+impl GoalProgramming {
+    pub fn weighted(goals: Vec<Goal>) -> Self {
+        Self {
+            goals,
+            mode: GoalMode::Weighted,
+        }
+    }
+
+    pub fn lexicographic(goals: Vec<Goal>) -> Self {
+        Self {
+            goals,
+            mode: GoalMode::Lexicographic,
+        }
+    }
+
+    pub fn mode(&self) -> GoalMode {
+        self.mode
+    }
+
+    fn check(&self, problem: &Problem) -> Result<(), GoalProgrammingError> {
+        if self.goals.is_empty() {
+            return Err(GoalProgrammingError::NoGoals);
+        }
+        let goals = self.goals.iter().map(|g| (&g.name, g));
+        entries_by_name(problem.names(), goals, false, |g| g.is_valid())?;
+        Ok(())
+    }
+
+    pub fn rank(&self, problem: &Problem) -> Result<GoalDeviations, GoalProgrammingError> {
+        self.check(problem)?;
+        let size = problem.alternatives();
+        let mut under = Array2::zeros((size, self.goals.len()));
+        let mut over = Array2::zeros((size, self.goals.len()));
+        for (g, goal) in self.goals.iter().enumerate() {
+            // NOTE: the goals are checked against the variables of the problem
+            let variable = problem
+                .variables()
+                .find(|v| v.name() == &goal.name)
+                .unwrap();
+            let values = variable.values().values();
+            let distance = rescaled_distance(variable);
+            for (a, &x) in values.iter().enumerate() {
+                under[[a, g]] = distance(x, goal.target).max(0.);
+                over[[a, g]] = distance(goal.target, x).max(0.);
+            }
+        }
+        // a deviation without weight does not count, even an infinite one
+        let weighted = |deviation: f64, weight: f64| {
+            if weight == 0. { 0. } else { deviation * weight }
+        };
+        let mut deviations = Array2::zeros((size, self.goals.len()));
+        for (g, goal) in self.goals.iter().enumerate() {
+            for a in 0..size {
+                deviations[[a, g]] = weighted(under[[a, g]], goal.under_weight)
+                    + weighted(over[[a, g]], goal.over_weight);
+            }
+        }
+
+        let priorities: Vec<usize> = match self.mode {
+            GoalMode::Weighted => vec![1],
+            GoalMode::Lexicographic => self
+                .goals
+                .iter()
+                .map(|g| g.priority)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+        let mut achievements = Array2::zeros((size, priorities.len()));
+        for (g, goal) in self.goals.iter().enumerate() {
+            let level = match self.mode {
+                GoalMode::Weighted => 0,
+                // NOTE: the priorities are collected from the goals
+                GoalMode::Lexicographic => priorities.binary_search(&goal.priority).unwrap(),
+            };
+            let mut column = achievements.column_mut(level);
+            column += &deviations.column(g);
+        }
+
+        let ranking = match self.mode {
+            GoalMode::Weighted => Ranking::ascending(achievements.column(0).to_owned()),
+            GoalMode::Lexicographic => {
+                let mut order: Vec<usize> = (0..size).collect();
+                order.sort_by(|&a, &b| {
+                    achievements
+                        .row(a)
+                        .iter()
+                        .zip(achievements.row(b))
+                        .map(|(x, y)| x.total_cmp(y))
+                        .find(|&o| o != Ordering::Equal)
+                        .unwrap_or(Ordering::Equal)
+                });
+                // NOTE: the order is a permutation of the alternatives
+                Ranking::from_order(order).unwrap()
+            }
+        };
+        Ok(GoalDeviations {
+            goals: self.goals.iter().map(|g| g.name.clone()).collect(),
+            under,
+            over,
+            priorities,
+            achievements,
+            ranking,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("latency").unwrap(),
+                Values::try_from(array![40., 60., 45.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("uptime").unwrap(),
+                Values::try_from(array![99.9, 99.97, 99.96]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    fn create_test_goals() -> Vec<Goal> {
+        // under 50ms and over 99.95% uptime
+        vec![
+            Goal::at_most(Name::try_from("latency").unwrap(), 50.),
+            Goal::at_least(Name::try_from("uptime").unwrap(), 99.95).with_priority(2),
+        ]
+    }
+
+    #[test]
+    fn test_weighted() {
+        let result = GoalProgramming::weighted(create_test_goals())
+            .rank(&create_test_problem())
+            .unwrap();
+        assert_eq!(
+            result.goals(),
+            &[
+                Name::try_from("latency").unwrap(),
+                Name::try_from("uptime").unwrap()
+            ]
+        );
+        assert_ulps_eq!(
+            result.over(),
+            array![[0., 0.], [0.5, 2. / 7.], [0., 1. / 7.]],
+            epsilon = 1e-9
+        );
+        assert_ulps_eq!(
+            result.under(),
+            array![[0.5, 5. / 7.], [0., 0.], [0.25, 0.]],
+            epsilon = 1e-9
+        );
+        // only the deviations in the wrong direction count
+        assert_ulps_eq!(
+            result.achievements(),
+            array![[5. / 7.], [0.5], [0.]],
+            epsilon = 1e-9
+        );
+        assert_eq!(result.priorities(), &[1]);
+        assert_eq!(result.ranking().order(), &[2, 1, 0]);
+    }
+
+    #[test]
+    fn test_lexicographic() {
+        let result = GoalProgramming::lexicographic(create_test_goals())
+            .rank(&create_test_problem())
+            .unwrap();
+        assert_eq!(result.priorities(), &[1, 2]);
+        assert_ulps_eq!(
+            result.achievements(),
+            array![[0., 5. / 7.], [0.5, 0.], [0., 0.]],
+            epsilon = 1e-9
+        );
+        // the latency goal comes first: the slow alternative is the worst
+        assert_eq!(result.ranking().order(), &[2, 0, 1]);
+    }
+
+    #[test]
+    fn test_exactly() {
+        let goals = vec![Goal::exactly(Name::try_from("latency").unwrap(), 50.)];
+        let result = GoalProgramming::weighted(goals)
+            .rank(&create_test_problem())
+            .unwrap();
+        assert_ulps_eq!(result.achievements().column(0), array![0.5, 0.5, 0.25]);
+        assert_eq!(result.ranking().best(), 2);
+    }
+
+    #[test]
+    fn test_huge_range() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("latency").unwrap(),
+            Values::try_from(array![-1e308, 1e308]).unwrap(),
+        ))])
+        .unwrap();
+        let goals = vec![Goal::at_most(Name::try_from("latency").unwrap(), 1e308)];
+        let result = GoalProgramming::weighted(goals).rank(&p).unwrap();
+        assert_ulps_eq!(result.under(), array![[1.], [0.]]);
+        assert_ulps_eq!(result.achievements().column(0), array![0., 0.]);
+
+        let goals = vec![Goal::exactly(Name::try_from("latency").unwrap(), 0.)];
+        let result = GoalProgramming::weighted(goals).rank(&p).unwrap();
+        assert_ulps_eq!(result.achievements().column(0), array![0.5, 0.5]);
+    }
+
+    #[test]
+    fn rank_no_goals_failure() {
+        let err = GoalProgramming::weighted(vec![])
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, GoalProgrammingError::NoGoals);
+    }
+
+    #[test]
+    fn rank_unknown_variable_failure() {
+        let goals = vec![Goal::at_most(Name::try_from("cost").unwrap(), 1.)];
+        let err = GoalProgramming::weighted(goals)
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            GoalProgrammingError::UnknownVariable("cost".to_string())
+        );
+    }
+
+    #[test]
+    fn rank_redefinition_goal_failure() {
+        let goals = vec![
+            Goal::at_most(Name::try_from("latency").unwrap(), 50.),
+            Goal::at_least(Name::try_from("latency").unwrap(), 10.),
+        ];
+        let err = GoalProgramming::weighted(goals)
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            GoalProgrammingError::RedefinitionGoal("latency".to_string())
+        );
+    }
+
+    #[test]
+    fn rank_invalid_goal_failure() {
+        let goals = vec![Goal::new(Name::try_from("latency").unwrap(), 50., -1., 1.)];
+        let err = GoalProgramming::weighted(goals)
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            GoalProgrammingError::InvalidGoal("latency".to_string())
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! This module ranks problems with the Grey Relational Analysis (GRA).
+//!
+//! The reference sequence is the ideal of the rescaled matrix, the zero vector. The grey relational coefficient of a
+//! value measures how close it is to the reference, relative to the smallest and the biggest deviations of the whole
+//! matrix. The grade of an alternative is the weighted mean of its coefficients: the higher, the better.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::weights::Weights;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+
+#[derive(Debug, Clone)]
+pub struct GreyRelational {
+    distinguishing_coefficient: f64,
+    weights: Option<Weights>,
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum GreyRelationalError {
+    #[error("The distinguishing coefficient must be in (0, 1]")]
+    InvalidDistinguishingCoefficient,
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct GreyRelationalGrades {
+    coefficients: Array2<f64>,
+    ranking: Ranking,
+}
+
+impl GreyRelationalGrades {
+    /// The grey relational coefficients: one row for each alternative, one column for each variable.
+    pub fn coefficients(&self) -> ArrayView2<'_, f64> {
+        self.coefficients.view()
+    }
+
+    /// The grey relational grade of each alternative, from 0 to 1 (the reference).
+    pub fn grades(&self) -> ArrayView1<'_, f64> {
+        self.ranking.scores()
+    }
+
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+}
+
+fn coefficients(matrix: &Array2<f64>, zeta: f64) -> Array2<f64> {
+    // the deviations from the zero reference are the rescaled values themselves
+    let min = matrix.fold(f64::INFINITY, |a, &b| a.min(b));
+    let max = matrix.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    if max == min {
+        // all the deviations are the same: every value is as close to the reference as possible
+        return Array2::ones(matrix.dim());
+    }
+    matrix.mapv(|deviation| (min + zeta * max) / (deviation + zeta * max))
+}
+
+impl Default for GreyRelational {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GreyRelational {
+    /// GRA with the usual distinguishing coefficient of 0.5 and equal weights.
+    pub fn new() -> Self {
+        Self {
+            distinguishing_coefficient: 0.5,
+            weights: None,
+        }
+    }
+
+    /// The smaller the distinguishing coefficient, the more the coefficients are spread apart.
+    pub fn with_distinguishing_coefficient(mut self, zeta: f64) -> Self {
+        self.distinguishing_coefficient = zeta;
+        self
+    }
+
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    pub fn rank(&self, problem: &Problem) -> Result<GreyRelationalGrades, GreyRelationalError> {
+        self.rank_deviations(problem, &problem.get_problem_matrix())
+    }
+
+    /// Ranks the deviations of the values from another reference than the rescaled one: one row for each
+    /// alternative, one column for each variable, 0 being the reference.
+    pub(super) fn rank_deviations(
+        &self,
+        problem: &Problem,
+        deviations: &Array2<f64>,
+    ) -> Result<GreyRelationalGrades, GreyRelationalError> {
+        let zeta = self.distinguishing_coefficient;
+        if !(zeta > 0. && zeta <= 1.) {
+            return Err(GreyRelationalError::InvalidDistinguishingCoefficient);
+        }
+        let weights = problem.get_weights_or_equal(self.weights.as_ref())?;
+        let coefficients = coefficients(deviations, zeta);
+        let grades: Array1<f64> = coefficients.dot(&weights);
+        Ok(GreyRelationalGrades {
+            coefficients,
+            ranking: Ranking::descending(grades),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Name, Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("roughness").unwrap(),
+                Values::try_from(array![1., 2., 3.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("removal_rate").unwrap(),
+                Values::try_from(array![3., 5., 4.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rank() {
+        let result = GreyRelational::new().rank(&create_test_problem()).unwrap();
+        // the rescaled matrix is [[1, 0], [0, 0.5], [0.5, 1]]: coefficients are 0.5 / (x + 0.5)
+        assert_ulps_eq!(
+            result.coefficients(),
+            array![[1., 0.], [0., 0.5], [0.5, 1.]].mapv(|x| 0.5 / (x + 0.5))
+        );
+        assert_ulps_eq!(result.grades(), array![2. / 3., 0.75, 5. / 12.]);
+        assert_eq!(result.ranking().order(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn test_distinguishing_coefficient() {
+        let p = create_test_problem();
+        let narrow = GreyRelational::new()
+            .with_distinguishing_coefficient(0.1)
+            .rank(&p)
+            .unwrap();
+        let wide = GreyRelational::new()
+            .with_distinguishing_coefficient(1.)
+            .rank(&p)
+            .unwrap();
+        // the worst coefficient goes from 1 / 11 to 1 / 2
+        assert_ulps_eq!(narrow.coefficients()[[0, 0]], 1. / 11.);
+        assert_ulps_eq!(wide.coefficients()[[0, 0]], 0.5);
+        assert_eq!(narrow.ranking().order(), wide.ranking().order());
+    }
+
+    #[test]
+    fn test_rank_with_weights() {
+        let weights = Weights::try_from(vec![
+            (Name::try_from("roughness").unwrap(), 1.),
+            (Name::try_from("removal_rate").unwrap(), 0.),
+        ])
+        .unwrap();
+        let result = GreyRelational::new()
+            .with_weights(weights)
+            .rank(&create_test_problem())
+            .unwrap();
+        assert_eq!(result.ranking().order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_constant_problem() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            Values::try_from(array![1., 1.]).unwrap(),
+        ))])
+        .unwrap();
+        let result = GreyRelational::new().rank(&p).unwrap();
+        assert_ulps_eq!(result.grades(), array![1., 1.]);
+    }
+
+    #[test]
+    fn rank_invalid_distinguishing_coefficient_failure() {
+        let err = GreyRelational::new()
+            .with_distinguishing_coefficient(0.)
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, GreyRelationalError::InvalidDistinguishingCoefficient);
+    }
+
+    #[test]
+    fn rank_weights_mismatch_failure() {
+        let weights = Weights::try_from(vec![(Name::try_from("roughness").unwrap(), 1.)]).unwrap();
+        let err = GreyRelational::new()
+            .with_weights(weights)
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            GreyRelationalError::Problem(ProblemError::WeightsMismatch)
+        );
+    }
+}
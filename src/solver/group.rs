@@ -0,0 +1,449 @@
+//! This module supports group decisions: several decision makers share the structure of a problem, each one with
+//! their own weights and, optionally, their own values.
+//!
+//! Judgements can be aggregated before solving (input level), or each decision maker solves the problem and their
+//! rankings are aggregated (output level). Either way, the agreement of the individual rankings is measured with
+//! Kendall's coefficient of concordance.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::weights::{Weights, WeightsError};
+use crate::variables::{Name, Values};
+use ndarray::{Array1, Array2, Axis, stack};
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct DecisionMaker {
+    importance: f64,
+    weights: Weights,
+    problem: Option<Problem>,
+}
+
+/// How to aggregate the values of the decision makers, weighted by their importance. The weights are always averaged
+/// arithmetically: with the geometric mean, a variable that one decision maker ignores would weigh nothing for all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    ArithmeticMean,
+    /// Only for positive values.
+    GeometricMean,
+}
+
+#[derive(Debug)]
+pub struct GroupDecision {
+    problem: Problem,
+    decision_makers: Vec<DecisionMaker>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum GroupDecisionError {
+    #[error("At least one decision maker is required")]
+    NoDecisionMakers,
+    #[error("The importance must be finite and non-negative, and at least one positive")]
+    InvalidImportance,
+    #[error("Each decision maker's problem must have the variables and alternatives of the group")]
+    ProblemMismatch,
+    #[error("The geometric mean requires positive values")]
+    NonPositiveValues,
+    #[error(transparent)]
+    Weights(#[from] WeightsError),
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupRanking {
+    ranking: Ranking,
+    individual: Vec<Ranking>,
+    consensus: f64,
+}
+
+impl GroupRanking {
+    /// The ranking of the group.
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+
+    /// The ranking of each decision maker.
+    pub fn individual(&self) -> &[Ranking] {
+        &self.individual
+    }
+
+    /// Kendall's coefficient of concordance of the individual rankings: from 0 (no agreement) to 1 (unanimity).
+    pub fn consensus(&self) -> f64 {
+        self.consensus
+    }
+}
+
+impl DecisionMaker {
+    pub fn new(importance: f64, weights: Weights) -> Self {
+        Self {
+            importance,
+            weights,
+            problem: None,
+        }
+    }
+
+    /// The decision maker's own values, instead of the ones of the group's problem.
+    pub fn with_problem(mut self, problem: Problem) -> Self {
+        self.problem = Some(problem);
+        self
+    }
+}
+
+/// Kendall's W of the rankings, which must have the same alternatives.
+pub(crate) fn concordance(rankings: &[Ranking]) -> f64 {
+    // NOTE: the caller guarantees for at least one ranking
+    if rankings.len() < 2 || rankings[0].len() < 2 {
+        // a single judge or a single alternative always agrees with itself
+        return 1.;
+    }
+    let judges = rankings.len() as f64;
+    let alternatives = rankings[0].len() as f64;
+    let mut totals = Array1::<f64>::zeros(rankings[0].len());
+    for ranking in rankings {
+        for (total, position) in totals.iter_mut().zip(ranking.positions()) {
+            *total += position as f64;
+        }
+    }
+    let mean = totals.mean().unwrap();
+    let deviations = totals.mapv(|t| (t - mean).powi(2)).sum();
+    12. * deviations / (judges.powi(2) * (alternatives.powi(3) - alternatives))
+}
+
+fn aggregate(
+    values: &Array2<f64>,
+    importance: &Array1<f64>,
+    aggregation: Aggregation,
+) -> Array1<f64> {
+    // one row for each decision maker, one column for each value
+    match aggregation {
+        Aggregation::ArithmeticMean => importance.dot(values),
+        Aggregation::GeometricMean => importance.dot(&values.mapv(f64::ln)).mapv(f64::exp),
+    }
+}
+
+impl GroupDecision {
+    pub fn new(
+        problem: Problem,
+        decision_makers: Vec<DecisionMaker>,
+    ) -> Result<Self, GroupDecisionError> {
+        if decision_makers.is_empty() {
+            return Err(GroupDecisionError::NoDecisionMakers);
+        }
+        if decision_makers
+            .iter()
+            .any(|d| !d.importance.is_finite() || d.importance < 0.)
+            || decision_makers.iter().all(|d| d.importance == 0.)
+        {
+            return Err(GroupDecisionError::InvalidImportance);
+        }
+        for own in decision_makers.iter().filter_map(|d| d.problem.as_ref()) {
+            if own.alternatives() != problem.alternatives() || !own.names().eq(problem.names()) {
+                return Err(GroupDecisionError::ProblemMismatch);
+            }
+        }
+        Ok(Self {
+            problem,
+            decision_makers,
+        })
+    }
+
+    fn problem_of<'a>(&'a self, decision_maker: &'a DecisionMaker) -> &'a Problem {
+        decision_maker.problem.as_ref().unwrap_or(&self.problem)
+    }
+
+    /// The importance of the decision makers, normalized to sum to 1.
+    fn importance(&self) -> Array1<f64> {
+        let importance: Array1<f64> = self.decision_makers.iter().map(|d| d.importance).collect();
+        // NOTE: the importances are validated, so the highest one is positive
+        let highest = importance.fold(0., |a: f64, &b| a.max(b));
+        // divided by the highest importance first, so that the total of big importances does not overflow
+        let importance = importance / highest;
+        let total = importance.sum();
+        importance / total
+    }
+
+    /// Each decision maker ranks their own problem with their own weights.
+    pub fn individual_rankings(&self) -> Result<Vec<Ranking>, GroupDecisionError> {
+        self.decision_makers
+            .iter()
+            .map(|d| Ok(self.problem_of(d).rank_weighted(&d.weights)?))
+            .collect()
+    }
+
+    /// Aggregates weights and values of the decision makers, then ranks the resulting problem.
+    pub fn aggregate_inputs(
+        &self,
+        aggregation: Aggregation,
+    ) -> Result<GroupRanking, GroupDecisionError> {
+        let individual = self.individual_rankings()?;
+        let importance = self.importance();
+
+        let names: Vec<_> = self.problem.names().cloned().collect();
+        let mut weights = Array2::<f64>::zeros((self.decision_makers.len(), names.len()));
+        for (mut row, d) in weights.axis_iter_mut(Axis(0)).zip(&self.decision_makers) {
+            // NOTE: the weights have already been validated by ranking each decision maker's problem
+            for (w, name) in row.iter_mut().zip(&names) {
+                *w = d.weights.get(name).unwrap();
+            }
+        }
+        let weights = aggregate(&weights, &importance, Aggregation::ArithmeticMean);
+        let weights = Weights::try_from(names.into_iter().zip(weights).collect::<Vec<_>>())?;
+
+        let all_values: BTreeMap<&Name, Array2<f64>> = self
+            .problem
+            .names()
+            .map(|name| {
+                let rows: Vec<_> = self
+                    .decision_makers
+                    .iter()
+                    .map(|d| self.problem_of(d).values_of(name).unwrap().values())
+                    .collect();
+                (name, stack(Axis(0), &rows).unwrap())
+            })
+            .collect();
+        if aggregation == Aggregation::GeometricMean
+            && all_values.values().any(|v| v.iter().any(|&x| x <= 0.))
+        {
+            return Err(GroupDecisionError::NonPositiveValues);
+        }
+
+        let problem = self.problem.map_values(|v| {
+            let aggregated = aggregate(&all_values[v.name()], &importance, aggregation);
+            Values::try_from(aggregated).unwrap()
+        })?;
+
+        Ok(GroupRanking {
+            ranking: problem.rank_weighted(&weights)?,
+            consensus: concordance(&individual),
+            individual,
+        })
+    }
+
+    /// Ranks the alternatives by the average position in the individual rankings, weighted by importance.
+    pub fn aggregate_outputs(&self) -> Result<GroupRanking, GroupDecisionError> {
+        let individual = self.individual_rankings()?;
+        let importance = self.importance();
+
+        let mut positions = Array1::<f64>::zeros(self.problem.alternatives());
+        for (ranking, alpha) in individual.iter().zip(&importance) {
+            for (total, position) in positions.iter_mut().zip(ranking.positions()) {
+                *total += alpha * position as f64;
+            }
+        }
+
+        Ok(GroupRanking {
+            ranking: Ranking::ascending(positions),
+            consensus: concordance(&individual),
+            individual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem(cost: Array1<f64>, quality: Array1<f64>) -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(cost).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(quality).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    fn weights(cost: f64, quality: f64) -> Weights {
+        Weights::try_from(vec![
+            (Name::try_from("cost").unwrap(), cost),
+            (Name::try_from("quality").unwrap(), quality),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_concordance() {
+        let a = Ranking::ascending(array![0., 1., 2.]);
+        let b = Ranking::ascending(array![2., 1., 0.]);
+        assert_ulps_eq!(concordance(&[a.clone(), a.clone()]), 1.);
+        assert_ulps_eq!(concordance(&[a.clone(), b]), 0.);
+        assert_ulps_eq!(concordance(&[a]), 1.);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let values = array![[1., 4.], [4., 1.]];
+        let importance = array![0.5, 0.5];
+        assert_ulps_eq!(
+            aggregate(&values, &importance, Aggregation::ArithmeticMean),
+            array![2.5, 2.5]
+        );
+        assert_ulps_eq!(
+            aggregate(&values, &importance, Aggregation::GeometricMean),
+            array![2., 2.]
+        );
+    }
+
+    #[test]
+    fn test_unanimous_group() {
+        let group = GroupDecision::new(
+            create_test_problem(array![1., 2., 3.], array![3., 4., 5.]),
+            vec![
+                DecisionMaker::new(1., weights(1., 1.)),
+                DecisionMaker::new(2., weights(2., 2.)),
+            ],
+        )
+        .unwrap();
+        for ranking in [
+            group.aggregate_inputs(Aggregation::ArithmeticMean).unwrap(),
+            group.aggregate_inputs(Aggregation::GeometricMean).unwrap(),
+            group.aggregate_outputs().unwrap(),
+        ] {
+            assert_eq!(ranking.ranking().order(), &[1, 0, 2]);
+            assert_ulps_eq!(ranking.consensus(), 1.);
+            assert_eq!(ranking.individual().len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_divided_group() {
+        let group = GroupDecision::new(
+            create_test_problem(array![1., 2., 3.], array![3., 4., 5.]),
+            vec![
+                // cares only about cost
+                DecisionMaker::new(4., weights(1., 0.)),
+                // cares only about quality
+                DecisionMaker::new(1., weights(0., 1.)),
+            ],
+        )
+        .unwrap();
+        let individual = group.individual_rankings().unwrap();
+        assert_eq!(individual[0].order(), &[0, 1, 2]);
+        assert_eq!(individual[1].order(), &[2, 1, 0]);
+
+        for aggregation in [Aggregation::ArithmeticMean, Aggregation::GeometricMean] {
+            let inputs = group.aggregate_inputs(aggregation).unwrap();
+            assert_eq!(inputs.ranking().best(), 0);
+            assert_ulps_eq!(inputs.consensus(), 0.);
+        }
+        let outputs = group.aggregate_outputs().unwrap();
+        assert_eq!(outputs.ranking().order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_zero_importance_geometric() {
+        // the decision maker without importance counts for nothing, even with a zero weight
+        let group = GroupDecision::new(
+            create_test_problem(array![1., 2., 3.], array![3., 4., 5.]),
+            vec![
+                DecisionMaker::new(1., weights(1., 0.)),
+                DecisionMaker::new(0., weights(0., 1.)),
+            ],
+        )
+        .unwrap();
+        let inputs = group.aggregate_inputs(Aggregation::GeometricMean).unwrap();
+        assert_eq!(inputs.ranking().order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_huge_importance() {
+        let group = |importance| {
+            GroupDecision::new(
+                create_test_problem(array![1., 2., 3.], array![3., 4., 5.]),
+                vec![
+                    DecisionMaker::new(importance, weights(1., 0.)),
+                    DecisionMaker::new(importance, weights(0., 1.)),
+                ],
+            )
+            .unwrap()
+        };
+        let (huge, unit) = (group(f64::MAX), group(1.));
+        assert_ulps_eq!(huge.importance(), array![0.5, 0.5]);
+        assert_ulps_eq!(
+            huge.aggregate_inputs(Aggregation::ArithmeticMean)
+                .unwrap()
+                .ranking()
+                .scores(),
+            unit.aggregate_inputs(Aggregation::ArithmeticMean)
+                .unwrap()
+                .ranking()
+                .scores()
+        );
+    }
+
+    #[test]
+    fn test_own_values() {
+        let group = GroupDecision::new(
+            create_test_problem(array![1., 2., 3.], array![3., 4., 5.]),
+            vec![
+                DecisionMaker::new(1., weights(1., 1.)),
+                DecisionMaker::new(1., weights(1., 1.))
+                    .with_problem(create_test_problem(array![3., 2., 1.], array![5., 4., 3.])),
+            ],
+        )
+        .unwrap();
+        let individual = group.individual_rankings().unwrap();
+        assert_eq!(individual[1].order(), &[1, 0, 2]);
+        // the averages are all the same
+        let inputs = group.aggregate_inputs(Aggregation::ArithmeticMean).unwrap();
+        assert_ulps_eq!(inputs.ranking().scores(), array![0., 0., 0.]);
+    }
+
+    #[test]
+    fn new_no_decision_makers_failure() {
+        let p = create_test_problem(array![1.], array![1.]);
+        let err = GroupDecision::new(p, vec![]).unwrap_err();
+        assert_eq!(err, GroupDecisionError::NoDecisionMakers);
+    }
+
+    #[test]
+    fn new_invalid_importance_failure() {
+        let p = create_test_problem(array![1.], array![1.]);
+        let err = GroupDecision::new(p, vec![DecisionMaker::new(0., weights(1., 1.))]).unwrap_err();
+        assert_eq!(err, GroupDecisionError::InvalidImportance);
+    }
+
+    #[test]
+    fn new_problem_mismatch_failure() {
+        let p = create_test_problem(array![1.], array![1.]);
+        let err = GroupDecision::new(
+            p,
+            vec![
+                DecisionMaker::new(1., weights(1., 1.))
+                    .with_problem(create_test_problem(array![1., 2.], array![1., 2.])),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err, GroupDecisionError::ProblemMismatch);
+    }
+
+    #[test]
+    fn aggregate_geometric_non_positive_failure() {
+        let p = create_test_problem(array![0., 1.], array![1., 2.]);
+        let group = GroupDecision::new(p, vec![DecisionMaker::new(1., weights(1., 1.))]).unwrap();
+        let err = group
+            .aggregate_inputs(Aggregation::GeometricMean)
+            .unwrap_err();
+        assert_eq!(err, GroupDecisionError::NonPositiveValues);
+    }
+
+    #[test]
+    fn aggregate_weights_mismatch_failure() {
+        let p = create_test_problem(array![0., 1.], array![1., 2.]);
+        let w = Weights::try_from(vec![(Name::try_from("cost").unwrap(), 1.)]).unwrap();
+        let group = GroupDecision::new(p, vec![DecisionMaker::new(1., w)]).unwrap();
+        let err = group.aggregate_outputs().unwrap_err();
+        assert_eq!(
+            err,
+            GroupDecisionError::Problem(ProblemError::WeightsMismatch)
+        );
+    }
+}
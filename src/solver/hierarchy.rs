@@ -0,0 +1,373 @@
+//! This module organizes the variables of a problem in a tree of criteria, with weights local to each node.
+//!
+//! Each group of criteria weights its children, and the weight of a variable (a leaf) is the product of the weights
+//! along its path from the root. Besides the overall ranking, each group ranks the alternatives using only its own
+//! variables, so that subtotals such as "best on cost, worst on quality" are available.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::weights::{Weights, WeightsError};
+use crate::variables::Name;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A node of the tree: either a variable of the problem (a leaf) or a group of criteria.
+#[derive(Debug, Clone)]
+pub struct Criterion {
+    name: Name,
+    weight: f64,
+    children: Vec<Criterion>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Hierarchy {
+    criteria: Vec<Criterion>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum HierarchyError {
+    #[error("The hierarchy cannot be empty")]
+    Empty,
+    #[error("The weights must be finite and non-negative")]
+    InvalidWeight,
+    #[error("At least one criterion of '{0}' must have a positive weight")]
+    AllZero(String),
+    #[error("Each criterion must have an unique name: '{0}' is defined more than once")]
+    RedefinitionCriterion(String),
+    #[error(transparent)]
+    Weights(#[from] WeightsError),
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct HierarchyRanking {
+    overall: Ranking,
+    groups: BTreeMap<Name, Ranking>,
+}
+
+impl HierarchyRanking {
+    /// The ranking with all the variables.
+    pub fn overall(&self) -> &Ranking {
+        &self.overall
+    }
+
+    /// The ranking with only the variables of the group.
+    pub fn group(&self, name: &Name) -> Option<&Ranking> {
+        self.groups.get(name)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&Name, &Ranking)> {
+        self.groups.iter()
+    }
+}
+
+impl Criterion {
+    /// A variable of the problem.
+    pub fn leaf(name: Name, weight: f64) -> Self {
+        Self {
+            name,
+            weight,
+            children: Vec::new(),
+        }
+    }
+
+    /// A group of criteria. A group without criteria is a leaf.
+    pub fn group(name: Name, weight: f64, children: Vec<Criterion>) -> Self {
+        Self {
+            name,
+            weight,
+            children,
+        }
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+fn check_level(group: &str, criteria: &[Criterion]) -> Result<(), HierarchyError> {
+    if criteria
+        .iter()
+        .any(|c| !c.weight.is_finite() || c.weight < 0.)
+    {
+        return Err(HierarchyError::InvalidWeight);
+    }
+    if criteria.iter().all(|c| c.weight == 0.) {
+        return Err(HierarchyError::AllZero(group.to_string()));
+    }
+    Ok(())
+}
+
+fn check_tree<'a>(
+    criteria: &'a [Criterion],
+    names: &mut BTreeSet<&'a Name>,
+) -> Result<(), HierarchyError> {
+    for criterion in criteria {
+        if !names.insert(&criterion.name) {
+            return Err(HierarchyError::RedefinitionCriterion(
+                criterion.name.as_str().to_string(),
+            ));
+        }
+        if !criterion.is_leaf() {
+            check_level(criterion.name.as_str(), &criterion.children)?;
+            check_tree(&criterion.children, names)?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_groups<'a>(criteria: &'a [Criterion], groups: &mut Vec<&'a Criterion>) {
+    for criterion in criteria.iter().filter(|c| !c.is_leaf()) {
+        groups.push(criterion);
+        collect_groups(&criterion.children, groups);
+    }
+}
+
+impl Hierarchy {
+    pub fn new(criteria: Vec<Criterion>) -> Result<Self, HierarchyError> {
+        if criteria.is_empty() {
+            return Err(HierarchyError::Empty);
+        }
+        check_level("root", &criteria)?;
+        check_tree(&criteria, &mut BTreeSet::new())?;
+        Ok(Self { criteria })
+    }
+
+    /// Collects the weights of the leaves: each level splits `share` proportionally to the local weights.
+    fn collect_weights(criteria: &[Criterion], share: f64, weights: &mut Vec<(Name, f64)>) {
+        // NOTE: the levels are validated, so the highest weight is positive
+        let highest = criteria.iter().fold(0., |a: f64, c| a.max(c.weight));
+        // divided by the highest weight first, so that the total of big weights does not overflow
+        let total: f64 = criteria.iter().map(|c| c.weight / highest).sum();
+        for criterion in criteria {
+            let criterion_share = share * (criterion.weight / highest) / total;
+            if criterion.is_leaf() {
+                weights.push((criterion.name.clone(), criterion_share));
+            } else {
+                Hierarchy::collect_weights(&criterion.children, criterion_share, weights);
+            }
+        }
+    }
+
+    /// The weights of the leaves under `criteria`. The products of the local weights can still underflow to zero in a
+    /// deep tree of tiny weights.
+    fn weights_of(criteria: &[Criterion]) -> Result<Weights, HierarchyError> {
+        let mut weights = Vec::new();
+        Hierarchy::collect_weights(criteria, 1., &mut weights);
+        Ok(Weights::try_from(weights)?)
+    }
+
+    /// The global weights of the variables: the product of the local weights from the root to each leaf.
+    pub fn weights(&self) -> Result<Weights, HierarchyError> {
+        Hierarchy::weights_of(&self.criteria)
+    }
+
+    /// The weights of all the problem's variables when only the group matters: the variables outside of the group
+    /// have no weight.
+    fn group_weights(problem: &Problem, group: &Criterion) -> Result<Weights, HierarchyError> {
+        let local = Hierarchy::weights_of(&group.children)?;
+        let weights: Vec<(Name, f64)> = problem
+            .names()
+            .map(|name| (name.clone(), local.get(name).unwrap_or(0.)))
+            .collect();
+        Ok(Weights::try_from(weights)?)
+    }
+
+    pub fn rank(&self, problem: &Problem) -> Result<HierarchyRanking, HierarchyError> {
+        // the leaves must match the variables of the problem
+        let overall = problem.rank_weighted(&self.weights()?)?;
+
+        let mut groups = Vec::new();
+        collect_groups(&self.criteria, &mut groups);
+        let groups = groups
+            .into_iter()
+            .map(|group| {
+                let weights = Hierarchy::group_weights(problem, group)?;
+                Ok((group.name.clone(), problem.rank_weighted(&weights)?))
+            })
+            .collect::<Result<_, HierarchyError>>()?;
+
+        Ok(HierarchyRanking { overall, groups })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_hierarchy() -> Hierarchy {
+        Hierarchy::new(vec![
+            Criterion::group(
+                Name::try_from("cost").unwrap(),
+                3.,
+                vec![
+                    Criterion::leaf(Name::try_from("capex").unwrap(), 1.),
+                    Criterion::leaf(Name::try_from("opex").unwrap(), 1.),
+                ],
+            ),
+            Criterion::group(
+                Name::try_from("quality").unwrap(),
+                1.,
+                vec![
+                    Criterion::leaf(Name::try_from("latency").unwrap(), 1.),
+                    Criterion::leaf(Name::try_from("uptime").unwrap(), 3.),
+                ],
+            ),
+        ])
+        .unwrap()
+    }
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("capex").unwrap(),
+                Values::try_from(array![1., 2., 3.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("opex").unwrap(),
+                Values::try_from(array![1., 3., 2.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("latency").unwrap(),
+                Values::try_from(array![50., 20., 10.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("uptime").unwrap(),
+                Values::try_from(array![99., 99.9, 99.99]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_weights() {
+        let w = create_test_hierarchy().weights().unwrap();
+        assert_eq!(w.len(), 4);
+        assert_ulps_eq!(w.get(&Name::try_from("capex").unwrap()).unwrap(), 0.375);
+        assert_ulps_eq!(w.get(&Name::try_from("opex").unwrap()).unwrap(), 0.375);
+        assert_ulps_eq!(w.get(&Name::try_from("latency").unwrap()).unwrap(), 0.0625);
+        assert_ulps_eq!(w.get(&Name::try_from("uptime").unwrap()).unwrap(), 0.1875);
+    }
+
+    #[test]
+    fn test_rank() {
+        let ranking = create_test_hierarchy()
+            .rank(&create_test_problem())
+            .unwrap();
+        // best on cost, worst on quality
+        assert_eq!(
+            ranking
+                .group(&Name::try_from("cost").unwrap())
+                .unwrap()
+                .best(),
+            0
+        );
+        assert_eq!(
+            ranking
+                .group(&Name::try_from("quality").unwrap())
+                .unwrap()
+                .order(),
+            &[2, 1, 0]
+        );
+        assert_eq!(ranking.overall().best(), 0);
+        assert_eq!(ranking.groups().count(), 2);
+        assert!(ranking.group(&Name::try_from("capex").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_nested_groups() {
+        let h = Hierarchy::new(vec![
+            Criterion::leaf(Name::try_from("a").unwrap(), 1.),
+            Criterion::group(
+                Name::try_from("g").unwrap(),
+                1.,
+                vec![
+                    Criterion::leaf(Name::try_from("b").unwrap(), 1.),
+                    Criterion::group(
+                        Name::try_from("h").unwrap(),
+                        1.,
+                        vec![Criterion::leaf(Name::try_from("c").unwrap(), 1.)],
+                    ),
+                ],
+            ),
+        ])
+        .unwrap();
+        let w = h.weights().unwrap();
+        assert_ulps_eq!(w.get(&Name::try_from("a").unwrap()).unwrap(), 0.5);
+        assert_ulps_eq!(w.get(&Name::try_from("b").unwrap()).unwrap(), 0.25);
+        assert_ulps_eq!(w.get(&Name::try_from("c").unwrap()).unwrap(), 0.25);
+    }
+
+    #[test]
+    fn test_huge_weights() {
+        let h = Hierarchy::new(vec![
+            Criterion::leaf(Name::try_from("a").unwrap(), f64::MAX),
+            Criterion::group(
+                Name::try_from("g").unwrap(),
+                f64::MAX,
+                vec![
+                    Criterion::leaf(Name::try_from("b").unwrap(), f64::MAX),
+                    Criterion::leaf(Name::try_from("c").unwrap(), f64::MAX),
+                ],
+            ),
+        ])
+        .unwrap();
+        let w = h.weights().unwrap();
+        assert_ulps_eq!(w.get(&Name::try_from("a").unwrap()).unwrap(), 0.5);
+        assert_ulps_eq!(w.get(&Name::try_from("b").unwrap()).unwrap(), 0.25);
+        assert_ulps_eq!(w.get(&Name::try_from("c").unwrap()).unwrap(), 0.25);
+    }
+
+    #[test]
+    fn new_empty_failure() {
+        let err = Hierarchy::new(vec![]).unwrap_err();
+        assert_eq!(err, HierarchyError::Empty);
+    }
+
+    #[test]
+    fn new_group_all_zero_failure() {
+        let err = Hierarchy::new(vec![Criterion::group(
+            Name::try_from("g").unwrap(),
+            1.,
+            vec![Criterion::leaf(Name::try_from("a").unwrap(), 0.)],
+        )])
+        .unwrap_err();
+        assert_eq!(err, HierarchyError::AllZero("g".to_string()));
+    }
+
+    #[test]
+    fn new_invalid_weight_failure() {
+        let err =
+            Hierarchy::new(vec![Criterion::leaf(Name::try_from("a").unwrap(), -1.)]).unwrap_err();
+        assert_eq!(err, HierarchyError::InvalidWeight);
+    }
+
+    #[test]
+    fn new_redefinition_failure() {
+        let err = Hierarchy::new(vec![
+            Criterion::leaf(Name::try_from("a").unwrap(), 1.),
+            Criterion::group(
+                Name::try_from("g").unwrap(),
+                1.,
+                vec![Criterion::leaf(Name::try_from("a").unwrap(), 1.)],
+            ),
+        ])
+        .unwrap_err();
+        assert_eq!(err, HierarchyError::RedefinitionCriterion("a".to_string()));
+    }
+
+    #[test]
+    fn rank_leaves_mismatch_failure() {
+        let h =
+            Hierarchy::new(vec![Criterion::leaf(Name::try_from("capex").unwrap(), 1.)]).unwrap();
+        let err = h.rank(&create_test_problem()).unwrap_err();
+        assert_eq!(err, HierarchyError::Problem(ProblemError::WeightsMismatch));
+    }
+}
@@ -0,0 +1,242 @@
+//! This module define a problem as a matrix of interval variables, ranked with a TOPSIS-style closeness.
+//!
+//! The ideal alternative is the zero vector (the best rescaled values) and the anti-ideal is the ones vector. Each
+//! alternative gets a closeness interval: the best case uses the most favourable bounds, the worst case the least
+//! favourable ones. Alternatives are ranked by possibility degrees between their closeness intervals.
+use super::problem::{ProblemError, define_variables};
+use super::vector::l2_norm_vectors;
+use crate::variables::{IntervalVariableProperties, Name};
+use ndarray::{Array1, Array2, ArrayView1, Axis, stack};
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct IntervalProblem {
+    variables: BTreeMap<Name, Box<dyn IntervalVariableProperties>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntervalRanking {
+    best_case: Array1<f64>,
+    worst_case: Array1<f64>,
+    possibility: Array2<f64>,
+    order: Vec<usize>,
+}
+
+impl IntervalRanking {
+    /// Closeness of each alternative to the ideal using its most favourable bounds.
+    pub fn best_case(&self) -> ArrayView1<'_, f64> {
+        self.best_case.view()
+    }
+
+    /// Closeness of each alternative to the ideal using its least favourable bounds.
+    pub fn worst_case(&self) -> ArrayView1<'_, f64> {
+        self.worst_case.view()
+    }
+
+    /// The degree of possibility that alternative `a` is at least as good as alternative `b`, in [0, 1].
+    pub fn possibility(&self, a: usize, b: usize) -> f64 {
+        self.possibility[[a, b]]
+    }
+
+    /// Alternatives' indexes, from the best to the worst.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn best(&self) -> usize {
+        self.order[0]
+    }
+}
+
+fn possibility_degree(a_low: f64, a_high: f64, b_low: f64, b_high: f64) -> f64 {
+    let widths = (a_high - a_low) + (b_high - b_low);
+    if widths > 0. {
+        ((a_high - b_low) / widths).clamp(0., 1.)
+    } else if a_low > b_low {
+        1.
+    } else if a_low < b_low {
+        0.
+    } else {
+        0.5
+    }
+}
+
+fn possibility_matrix(low: ArrayView1<f64>, high: ArrayView1<f64>) -> Array2<f64> {
+    let n = low.len();
+    Array2::from_shape_fn((n, n), |(a, b)| {
+        possibility_degree(low[a], high[a], low[b], high[b])
+    })
+}
+
+fn order_by_possibility(possibility: &Array2<f64>) -> Vec<usize> {
+    // the higher the sum of possibility degrees, the better the alternative. The sort is stable, ties keep the
+    // alternatives order.
+    let totals = possibility.sum_axis(Axis(1));
+    let mut order: Vec<usize> = (0..totals.len()).collect();
+    order.sort_by(|&a, &b| totals[b].total_cmp(&totals[a]));
+    order
+}
+
+impl IntervalProblem {
+    pub fn define(
+        variables: Vec<Box<dyn IntervalVariableProperties>>,
+    ) -> Result<Self, ProblemError> {
+        let variables = define_variables(variables, |v| (v.name(), v.values().len()))?;
+        Ok(Self { variables })
+    }
+
+    fn get_problem_matrices(&self) -> (Array2<f64>, Array2<f64>) {
+        let rescaled: Vec<_> = self.variables.values().map(|v| v.rescale()).collect();
+        let lower: Vec<ArrayView1<f64>> = rescaled.iter().map(|v| v.lower()).collect();
+        let upper: Vec<ArrayView1<f64>> = rescaled.iter().map(|v| v.upper()).collect();
+        let lower = stack(Axis(0), &lower).expect("Stack failed");
+        let upper = stack(Axis(0), &upper).expect("Stack failed");
+        (lower.reversed_axes(), upper.reversed_axes())
+    }
+
+    pub fn rank(&self) -> IntervalRanking {
+        let (lower, upper) = self.get_problem_matrices();
+
+        // distances from the ideal (zeros) and from the anti-ideal (ones)
+        let ideal_low = l2_norm_vectors(lower.view());
+        let ideal_high = l2_norm_vectors(upper.view());
+        let anti_ideal_low = l2_norm_vectors((1. - &upper).view());
+        let anti_ideal_high = l2_norm_vectors((1. - &lower).view());
+
+        // NOTE: denominators are always positive, as each distance pair is at least the norm of the ones vector
+        let best_case = &anti_ideal_high / (&ideal_low + &anti_ideal_high);
+        let worst_case = &anti_ideal_low / (&ideal_high + &anti_ideal_low);
+
+        let possibility = possibility_matrix(worst_case.view(), best_case.view());
+        let order = order_by_possibility(&possibility);
+        IntervalRanking {
+            best_case,
+            worst_case,
+            possibility,
+            order,
+        }
+    }
+
+    pub fn solve(&self) -> usize {
+        self.rank().best()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{
+        IntervalValues, IntervalVariableAutoscale, IntervalVariableInvertedAutoscale, Name,
+    };
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> IntervalProblem {
+        IntervalProblem::define(vec![
+            // cost: the lower, the better
+            Box::new(IntervalVariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                IntervalValues::try_from((array![8., 10., 4.], array![12., 10., 6.])).unwrap(),
+            )),
+            // uptime: the higher, the better
+            Box::new(IntervalVariableInvertedAutoscale::new(
+                Name::try_from("uptime").unwrap(),
+                IntervalValues::try_from((array![90., 99., 95.], array![99., 99., 97.])).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_possibility_degree() {
+        assert_ulps_eq!(possibility_degree(0., 1., 0., 1.), 0.5);
+        assert_ulps_eq!(possibility_degree(0.5, 1., 0., 0.5), 1.);
+        assert_ulps_eq!(possibility_degree(0., 0.5, 0.5, 1.), 0.);
+        assert_ulps_eq!(possibility_degree(0., 1., 0.5, 0.5), 0.5);
+        assert_ulps_eq!(possibility_degree(0.2, 0.2, 0.1, 0.1), 1.);
+        assert_ulps_eq!(possibility_degree(0.2, 0.2, 0.2, 0.2), 0.5);
+    }
+
+    #[test]
+    fn test_problem_matrices_are_rescaled() {
+        let p = create_test_problem();
+        let (lower, upper) = p.get_problem_matrices();
+        assert_ulps_eq!(lower, array![[0.5, 0.], [0.75, 0.], [0., 2. / 9.]]);
+        assert_ulps_eq!(upper, array![[1., 1.], [0.75, 0.], [0.25, 4. / 9.]]);
+    }
+
+    #[test]
+    fn test_problem_is_ranked() {
+        let p = create_test_problem();
+        let ranking = p.rank();
+
+        // the best case is never worse than the worst case
+        assert!(
+            ranking
+                .best_case()
+                .iter()
+                .zip(ranking.worst_case())
+                .all(|(best, worst)| best >= worst)
+        );
+        // the crisp alternative has a degenerate closeness interval
+        assert_ulps_eq!(ranking.best_case()[1], ranking.worst_case()[1]);
+        // possibility degrees are complementary
+        assert_ulps_eq!(ranking.possibility(0, 2) + ranking.possibility(2, 0), 1.);
+        assert_ulps_eq!(ranking.possibility(1, 1), 0.5);
+
+        assert_eq!(ranking.order(), &[2, 1, 0]);
+        assert_eq!(p.solve(), 2);
+    }
+
+    #[test]
+    fn crisp_intervals_match_midpoint_ranking() {
+        let p = IntervalProblem::define(vec![Box::new(IntervalVariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            IntervalValues::try_from((array![1., 2., 3.], array![1., 2., 3.])).unwrap(),
+        ))])
+        .unwrap();
+        let ranking = p.rank();
+        assert_ulps_eq!(ranking.best_case(), ranking.worst_case());
+        assert_ulps_eq!(ranking.best_case(), array![1., 0.5, 0.]);
+        assert_eq!(ranking.order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn define_problem_empty_failure() {
+        let err = IntervalProblem::define(vec![]).unwrap_err();
+        assert_eq!(err, ProblemError::Empty)
+    }
+
+    #[test]
+    fn define_problem_redefine_variable_failure() {
+        let err = IntervalProblem::define(vec![
+            Box::new(IntervalVariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                IntervalValues::try_from((array![1.], array![2.])).unwrap(),
+            )),
+            Box::new(IntervalVariableInvertedAutoscale::new(
+                Name::try_from("x").unwrap(),
+                IntervalValues::try_from((array![1.], array![2.])).unwrap(),
+            )),
+        ])
+        .unwrap_err();
+        assert_eq!(err, ProblemError::RedefinitionVariable)
+    }
+
+    #[test]
+    fn define_problem_variable_with_different_size_failure() {
+        let err = IntervalProblem::define(vec![
+            Box::new(IntervalVariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                IntervalValues::try_from((array![1., 2.], array![2., 3.])).unwrap(),
+            )),
+            Box::new(IntervalVariableInvertedAutoscale::new(
+                Name::try_from("y").unwrap(),
+                IntervalValues::try_from((array![1.], array![2.])).unwrap(),
+            )),
+        ])
+        .unwrap_err();
+        assert_eq!(err, ProblemError::VariableSizeMismatch)
+    }
+}
@@ -0,0 +1,482 @@
+//! This module solves small linear programs with a dense two-phase simplex.
+//!
+//! A program minimizes or maximizes a linear objective of non-negative variables, subject to linear constraints
+//! (`A x <= b`, `A x >= b` or `A x = b`). Bland's rule prevents cycling, at the cost of more iterations than needed on
+//! big programs. Each constraint and the objective are divided by their biggest coefficient, so that the tolerance of
+//! the pivots and of the feasibility is relative to them: the variables are not rescaled, so within a constraint the
+//! coefficients many orders of magnitude smaller than the biggest one are treated as zeros. Bland's rule terminates
+//! only in exact arithmetic, so each phase stops after `PIVOTS_PER_COLUMN` pivots for each column of the tableau.
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis, concatenate, s};
+
+/// The tolerance, relative to the biggest coefficient of each constraint and of the objective.
+const EPSILON: f64 = 1e-9;
+
+/// The pivots allowed in each phase, for each column of the tableau.
+const PIVOTS_PER_COLUMN: usize = 50;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum LinearProgramError {
+    #[error(
+        "The constraints must have one coefficient for each variable and one bound for each row"
+    )]
+    DimensionMismatch,
+    #[error("The coefficients and the bounds must be finite")]
+    NotFinite,
+    #[error("No solution satisfies all the constraints")]
+    Infeasible,
+    #[error("The objective is unbounded")]
+    Unbounded,
+    #[error("The simplex did not converge within {0} pivots")]
+    IterationLimit(usize),
+}
+
+/// A linear program on non-negative variables. Each `with_*` call adds a block of constraints, one for each row of
+/// the coefficients.
+#[derive(Debug, Clone)]
+pub struct LinearProgram {
+    objective: Array1<f64>,
+    maximize: bool,
+    less_equal: Vec<(Array2<f64>, Array1<f64>)>,
+    equal: Vec<(Array2<f64>, Array1<f64>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearProgramSolution {
+    x: Array1<f64>,
+    objective: f64,
+}
+
+impl LinearProgramSolution {
+    /// The optimal values of the variables.
+    pub fn x(&self) -> ArrayView1<'_, f64> {
+        self.x.view()
+    }
+
+    /// The optimal value of the objective.
+    pub fn objective(&self) -> f64 {
+        self.objective
+    }
+}
+
+/// The simplex tableau: one row for each constraint plus the reduced costs, the right hand side as last column.
+struct Tableau {
+    table: Array2<f64>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn rows(&self) -> usize {
+        self.basis.len()
+    }
+
+    fn rhs(&self) -> usize {
+        self.table.ncols() - 1
+    }
+
+    fn pivot(&mut self, row: usize, column: usize) {
+        let pivot = self.table[[row, column]];
+        self.table.row_mut(row).mapv_inplace(|x| x / pivot);
+        let pivot_row = self.table.row(row).to_owned();
+        for (i, mut other) in self.table.axis_iter_mut(Axis(0)).enumerate() {
+            let factor = other[column];
+            if i != row && factor != 0. {
+                other.scaled_add(-factor, &pivot_row);
+            }
+        }
+        self.basis[row] = column;
+    }
+
+    /// Sets the reduced costs of the objective row for the given costs of all the columns.
+    fn set_objective(&mut self, costs: &Array1<f64>) {
+        let rows = self.rows();
+        let mut objective = self.table.row_mut(rows);
+        objective.fill(0.);
+        objective.slice_mut(s![..costs.len()]).assign(costs);
+        for i in 0..rows {
+            let cost = costs[self.basis[i]];
+            if cost != 0. {
+                let row = self.table.row(i).to_owned();
+                self.table.row_mut(rows).scaled_add(-cost, &row);
+            }
+        }
+    }
+
+    /// Pivots until no column before `columns` can decrease the objective, at most `max_pivots` times.
+    fn optimize(&mut self, columns: usize, max_pivots: usize) -> Result<(), LinearProgramError> {
+        let rows = self.rows();
+        let rhs = self.rhs();
+        let mut pivots = 0;
+        // Bland's rule: the first improving column, and the first basis variable among the tied rows
+        while let Some(column) = (0..columns).find(|&j| self.table[[rows, j]] < -EPSILON) {
+            if pivots == max_pivots {
+                return Err(LinearProgramError::IterationLimit(max_pivots));
+            }
+            pivots += 1;
+            let mut leaving: Option<(usize, f64)> = None;
+            for i in 0..rows {
+                let coefficient = self.table[[i, column]];
+                if coefficient > EPSILON {
+                    let ratio = self.table[[i, rhs]] / coefficient;
+                    let better = match leaving {
+                        None => true,
+                        Some((best, best_ratio)) => {
+                            // the ratios are tied relative to their size: a tie on tiny ratios must not pick the
+                            // bigger one, or the right hand side of the other row becomes negative
+                            let tolerance = EPSILON * ratio.abs().max(best_ratio.abs());
+                            ratio < best_ratio - tolerance
+                                || (ratio <= best_ratio + tolerance
+                                    && self.basis[i] < self.basis[best])
+                        }
+                    };
+                    if better {
+                        leaving = Some((i, ratio));
+                    }
+                }
+            }
+            match leaving {
+                Some((row, _)) => self.pivot(row, column),
+                None => return Err(LinearProgramError::Unbounded),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_dimensions(
+    variables: usize,
+    a: ArrayView2<f64>,
+    b: ArrayView1<f64>,
+) -> Result<(), LinearProgramError> {
+    if a.nrows() != b.len() || (a.nrows() > 0 && a.ncols() != variables) {
+        return Err(LinearProgramError::DimensionMismatch);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(LinearProgramError::NotFinite);
+    }
+    Ok(())
+}
+
+/// Minimizes `c x` subject to `a_ub x <= b_ub`, `a_eq x = b_eq` and `x >= 0`, and returns the optimal `x`.
+fn simplex(
+    c: ArrayView1<f64>,
+    a_ub: ArrayView2<f64>,
+    b_ub: ArrayView1<f64>,
+    a_eq: ArrayView2<f64>,
+    b_eq: ArrayView1<f64>,
+) -> Result<Array1<f64>, LinearProgramError> {
+    let variables = c.len();
+    check_dimensions(variables, a_ub, b_ub)?;
+    check_dimensions(variables, a_eq, b_eq)?;
+    if c.iter().any(|x| !x.is_finite()) {
+        return Err(LinearProgramError::NotFinite);
+    }
+
+    // columns: the variables, one slack for each inequality, one artificial for each row without a basic slack
+    let inequalities = b_ub.len();
+    let rows = inequalities + b_eq.len();
+    let needs_artificial: Vec<bool> = b_ub
+        .iter()
+        .map(|&b| b < 0.)
+        .chain(b_eq.iter().map(|_| true))
+        .collect();
+    let artificials = needs_artificial.iter().filter(|&&a| a).count();
+    let first_artificial = variables + inequalities;
+    let columns = first_artificial + artificials;
+
+    let mut table = Array2::zeros((rows + 1, columns + 1));
+    let mut basis = Vec::with_capacity(rows);
+    let mut artificial = first_artificial;
+    for i in 0..rows {
+        let (coefficients, bound) = if i < inequalities {
+            table[[i, variables + i]] = 1.;
+            (a_ub.row(i), b_ub[i])
+        } else {
+            (a_eq.row(i - inequalities), b_eq[i - inequalities])
+        };
+        // each constraint is divided by its biggest coefficient
+        let scale = coefficients.fold(bound.abs(), |a, &b| a.max(b.abs()));
+        let scale = if scale > 0. { scale } else { 1. };
+        table
+            .slice_mut(s![i, ..variables])
+            .assign(&(&coefficients / scale));
+        table[[i, columns]] = bound / scale;
+        // the right hand side must be non-negative
+        if bound < 0. {
+            table.row_mut(i).mapv_inplace(|x| -x);
+        }
+        if needs_artificial[i] {
+            table[[i, artificial]] = 1.;
+            basis.push(artificial);
+            artificial += 1;
+        } else {
+            basis.push(variables + i);
+        }
+    }
+    let mut tableau = Tableau { table, basis };
+    let max_pivots = PIVOTS_PER_COLUMN * (columns + 1);
+
+    // phase 1: minimize the sum of the artificials to find a feasible basis
+    if artificials > 0 {
+        let mut costs = Array1::zeros(columns);
+        costs.slice_mut(s![first_artificial..]).fill(1.);
+        tableau.set_objective(&costs);
+        tableau.optimize(columns, max_pivots)?;
+        if -tableau.table[[rows, columns]] > EPSILON * (1. + b_eq.len() as f64) {
+            return Err(LinearProgramError::Infeasible);
+        }
+        // drive the remaining artificials out of the basis: rows without other coefficients are redundant
+        for i in 0..rows {
+            if tableau.basis[i] < first_artificial || tableau.table[[i, columns]] > EPSILON {
+                continue;
+            }
+            // the artificial is zero within the tolerance: with an exact zero, the pivot leaves the right hand side
+            // of the other rows as it is, whatever the sign of the coefficient, and the biggest one is the most stable
+            tableau.table[[i, columns]] = 0.;
+            let pivot = (0..first_artificial)
+                .filter(|&j| tableau.table[[i, j]].abs() > EPSILON)
+                .max_by(|&a, &b| {
+                    tableau.table[[i, a]]
+                        .abs()
+                        .total_cmp(&tableau.table[[i, b]].abs())
+                });
+            if let Some(j) = pivot {
+                tableau.pivot(i, j);
+            }
+        }
+    }
+
+    // phase 2: minimize the objective without the artificials
+    let mut costs = Array1::zeros(columns);
+    let scale = c.fold(0., |a: f64, &b| a.max(b.abs()));
+    costs
+        .slice_mut(s![..variables])
+        .assign(&(&c / if scale > 0. { scale } else { 1. }));
+    tableau.set_objective(&costs);
+    tableau.optimize(first_artificial, max_pivots)?;
+
+    let mut x = Array1::zeros(variables);
+    for (i, &column) in tableau.basis.iter().enumerate() {
+        if column < variables {
+            x[column] = tableau.table[[i, columns]];
+        }
+    }
+    // the rounding errors of the pivots can leave the variables slightly negative, never more than the tolerance
+    let highest = x.fold(1., |a: f64, &b| a.max(b.abs()));
+    if x.iter().any(|&v| v < -EPSILON * highest) {
+        return Err(LinearProgramError::Infeasible);
+    }
+    Ok(x.mapv(|v| v.max(0.)))
+}
+
+impl LinearProgram {
+    /// Minimizes `c x`: one coefficient for each variable.
+    pub fn minimize(c: Array1<f64>) -> Self {
+        Self {
+            objective: c,
+            maximize: false,
+            less_equal: Vec::new(),
+            equal: Vec::new(),
+        }
+    }
+
+    /// Maximizes `c x`: one coefficient for each variable.
+    pub fn maximize(c: Array1<f64>) -> Self {
+        Self {
+            maximize: true,
+            ..Self::minimize(c)
+        }
+    }
+
+    /// Adds the constraints `a x <= b`.
+    pub fn with_less_equal(mut self, a: Array2<f64>, b: Array1<f64>) -> Self {
+        self.less_equal.push((a, b));
+        self
+    }
+
+    /// Adds the constraints `a x >= b`.
+    pub fn with_greater_equal(self, a: Array2<f64>, b: Array1<f64>) -> Self {
+        self.with_less_equal(-a, -b)
+    }
+
+    /// Adds the constraints `a x = b`.
+    pub fn with_equal(mut self, a: Array2<f64>, b: Array1<f64>) -> Self {
+        self.equal.push((a, b));
+        self
+    }
+
+    fn stack(
+        variables: usize,
+        blocks: &[(Array2<f64>, Array1<f64>)],
+    ) -> Result<(Array2<f64>, Array1<f64>), LinearProgramError> {
+        let mut a = Array2::zeros((0, variables));
+        let mut b = Array1::zeros(0);
+        for (block_a, block_b) in blocks {
+            if block_a.ncols() != variables || block_a.nrows() != block_b.len() {
+                return Err(LinearProgramError::DimensionMismatch);
+            }
+            a = concatenate![Axis(0), a, block_a.view()];
+            b = concatenate![Axis(0), b, block_b.view()];
+        }
+        Ok((a, b))
+    }
+
+    pub fn solve(&self) -> Result<LinearProgramSolution, LinearProgramError> {
+        let variables = self.objective.len();
+        let (a_ub, b_ub) = LinearProgram::stack(variables, &self.less_equal)?;
+        let (a_eq, b_eq) = LinearProgram::stack(variables, &self.equal)?;
+        let c = if self.maximize {
+            -&self.objective
+        } else {
+            self.objective.clone()
+        };
+        let x = simplex(c.view(), a_ub.view(), b_ub.view(), a_eq.view(), b_eq.view())?;
+        Ok(LinearProgramSolution {
+            objective: self.objective.dot(&x),
+            x,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+
+    #[test]
+    fn test_maximize() {
+        // maximize 3x + 5y subject to x <= 4, 2y <= 12, 3x + 2y <= 18
+        let solution = LinearProgram::maximize(array![3., 5.])
+            .with_less_equal(array![[1., 0.], [0., 2.], [3., 2.]], array![4., 12., 18.])
+            .solve()
+            .unwrap();
+        assert_ulps_eq!(solution.x(), array![2., 6.], epsilon = 1e-12);
+        assert_ulps_eq!(solution.objective(), 36., epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_tolerance_is_relative() {
+        // the same program in tiny and in huge units
+        for unit in [1e-12, 1e12] {
+            let solution = LinearProgram::maximize(array![3., 5.] * unit)
+                .with_less_equal(
+                    array![[1., 0.], [0., 2.], [3., 2.]] * unit,
+                    array![4., 12., 18.] * unit,
+                )
+                .solve()
+                .unwrap();
+            assert_ulps_eq!(solution.x(), array![2., 6.], epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_minimize_with_equalities() {
+        // minimize x + 2y subject to x + y = 10, x >= 3 and y >= 2
+        let solution = LinearProgram::minimize(array![1., 2.])
+            .with_equal(array![[1., 1.]], array![10.])
+            .with_greater_equal(array![[1., 0.]], array![3.])
+            .with_greater_equal(array![[0., 1.]], array![2.])
+            .solve()
+            .unwrap();
+        assert_ulps_eq!(solution.x(), array![8., 2.], epsilon = 1e-12);
+        assert_ulps_eq!(solution.objective(), 12., epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_redundant_equalities() {
+        let solution = LinearProgram::minimize(array![1., 1.])
+            .with_equal(array![[1., 1.], [2., 2.]], array![1., 2.])
+            .solve()
+            .unwrap();
+        assert_ulps_eq!(solution.objective(), 1., epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        // Beale's example, where the simplex cycles without Bland's rule
+        let solution = LinearProgram::minimize(array![-0.75, 20., -0.5, 6.])
+            .with_less_equal(
+                array![
+                    [0.25, -8., -1., 9.],
+                    [0.5, -12., -0.5, 3.],
+                    [0., 0., 1., 0.]
+                ],
+                array![0., 0., 1.],
+            )
+            .solve()
+            .unwrap();
+        assert_ulps_eq!(solution.objective(), -1.25, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_coefficients_of_different_magnitudes() {
+        // the envelopment program of DEA for a single unit: the ratios of the first pivot are tied within an absolute
+        // tolerance, but not within a relative one
+        let solution = LinearProgram::minimize(array![1., 0., 0., 0.])
+            .with_less_equal(
+                array![[-1., 1., 2., 3.], [0., -1e-7, -1., -2.]],
+                array![0., -1e-7],
+            )
+            .solve()
+            .unwrap();
+        assert!(solution.x().iter().all(|&x| x >= 0.));
+        assert_ulps_eq!(solution.objective(), 1.5e-7, max_ulps = 1000);
+    }
+
+    #[test]
+    fn test_no_constraints() {
+        let solution = LinearProgram::minimize(array![1., 2.]).solve().unwrap();
+        assert_ulps_eq!(solution.x(), array![0., 0.]);
+    }
+
+    #[test]
+    fn solve_infeasible_failure() {
+        // x <= 1 and x >= 2
+        let err = LinearProgram::minimize(array![1.])
+            .with_less_equal(array![[1.]], array![1.])
+            .with_greater_equal(array![[1.]], array![2.])
+            .solve()
+            .unwrap_err();
+        assert_eq!(err, LinearProgramError::Infeasible);
+    }
+
+    #[test]
+    fn solve_unbounded_failure() {
+        let err = LinearProgram::maximize(array![1., 0.])
+            .with_less_equal(array![[0., 1.]], array![1.])
+            .solve()
+            .unwrap_err();
+        assert_eq!(err, LinearProgramError::Unbounded);
+    }
+
+    #[test]
+    fn optimize_iteration_limit_failure() {
+        // minimize -x subject to x <= 1: one pivot is needed
+        let mut tableau = Tableau {
+            table: array![[1., 1., 1.], [-1., 0., 0.]],
+            basis: vec![1],
+        };
+        let err = tableau.optimize(2, 0).unwrap_err();
+        assert_eq!(err, LinearProgramError::IterationLimit(0));
+        assert!(tableau.optimize(2, 1).is_ok());
+        assert_ulps_eq!(tableau.table[[1, 2]], 1.);
+    }
+
+    #[test]
+    fn solve_dimension_mismatch_failure() {
+        let err = LinearProgram::minimize(array![1., 1.])
+            .with_less_equal(array![[1.]], array![1.])
+            .solve()
+            .unwrap_err();
+        assert_eq!(err, LinearProgramError::DimensionMismatch);
+    }
+
+    #[test]
+    fn solve_not_finite_failure() {
+        let err = LinearProgram::minimize(array![1.])
+            .with_equal(array![[f64::NAN]], array![1.])
+            .solve()
+            .unwrap_err();
+        assert_eq!(err, LinearProgramError::NotFinite);
+    }
+}
@@ -0,0 +1,272 @@
+//! This module propagates the uncertainty of the variables' values to the ranking with Monte Carlo simulations.
+//!
+//! Each iteration draws a realization of all the uncertain values, defines the corresponding problem and ranks it.
+use super::problem::{Problem, ProblemError};
+use super::weights::Weights;
+use crate::variables::UncertainVariable;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use ndarray_stats::QuantileExt;
+use ndarray_stats::interpolate::Linear;
+use noisy_float::types::n64;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    iterations: usize,
+    seed: u64,
+    confidence: f64,
+    weights: Option<Weights>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum MonteCarloError {
+    #[error("At least one iteration is required")]
+    NoIterations,
+    #[error("The confidence level must be in (0, 1)")]
+    InvalidConfidence,
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct MonteCarloResult {
+    rank_distribution: Array2<f64>,
+    mean_scores: Array1<f64>,
+    scores_lower: Array1<f64>,
+    scores_upper: Array1<f64>,
+}
+
+impl MonteCarloResult {
+    /// The probability of each alternative to be the best one.
+    pub fn win_probabilities(&self) -> ArrayView1<'_, f64> {
+        self.rank_distribution.column(0)
+    }
+
+    /// The probability of each rank: one row for each alternative, one column for each rank (0 being the best).
+    pub fn rank_distribution(&self) -> ArrayView2<'_, f64> {
+        self.rank_distribution.view()
+    }
+
+    /// The average distance of each alternative from the ideal: the lower, the better.
+    pub fn mean_scores(&self) -> ArrayView1<'_, f64> {
+        self.mean_scores.view()
+    }
+
+    /// The lower bounds of the confidence intervals of the scores.
+    pub fn scores_lower(&self) -> ArrayView1<'_, f64> {
+        self.scores_lower.view()
+    }
+
+    /// The upper bounds of the confidence intervals of the scores.
+    pub fn scores_upper(&self) -> ArrayView1<'_, f64> {
+        self.scores_upper.view()
+    }
+}
+
+impl MonteCarlo {
+    /// A new simulation with a 95% confidence level for the scores' intervals.
+    pub fn new(iterations: usize, seed: u64) -> Self {
+        Self {
+            iterations,
+            seed,
+            confidence: 0.95,
+            weights: None,
+        }
+    }
+
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Ranks each realization with `Problem::rank_weighted` instead of `Problem::rank`.
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    fn quantile(scores: &mut Array2<f64>, q: f64) -> Array1<f64> {
+        // NOTE: scores are always finite, there are no NaN to skip
+        scores
+            .quantile_axis_skipnan_mut(Axis(0), n64(q), &Linear)
+            .unwrap()
+    }
+
+    pub fn run(
+        &self,
+        variables: &[UncertainVariable],
+    ) -> Result<MonteCarloResult, MonteCarloError> {
+        if self.iterations == 0 {
+            return Err(MonteCarloError::NoIterations);
+        }
+        if !(self.confidence > 0. && self.confidence < 1.) {
+            return Err(MonteCarloError::InvalidConfidence);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut scores: Option<Array2<f64>> = None;
+        let mut rank_counts: Option<Array2<f64>> = None;
+
+        for iteration in 0..self.iterations {
+            let problem = Problem::define(variables.iter().map(|v| v.sample(&mut rng)).collect())?;
+            let ranking = match &self.weights {
+                Some(weights) => problem.rank_weighted(weights)?,
+                None => problem.rank(),
+            };
+
+            let alternatives = ranking.len();
+            let scores =
+                scores.get_or_insert_with(|| Array2::zeros((self.iterations, alternatives)));
+            scores.row_mut(iteration).assign(&ranking.scores());
+            let rank_counts =
+                rank_counts.get_or_insert_with(|| Array2::zeros((alternatives, alternatives)));
+            for (position, &alternative) in ranking.order().iter().enumerate() {
+                rank_counts[[alternative, position]] += 1.;
+            }
+        }
+
+        // NOTE: there is at least one iteration, so both are always defined
+        let mut scores = scores.unwrap();
+        let rank_counts = rank_counts.unwrap();
+        let tail = (1. - self.confidence) * 0.5;
+        Ok(MonteCarloResult {
+            rank_distribution: rank_counts / self.iterations as f64,
+            mean_scores: scores.mean_axis(Axis(0)).unwrap(),
+            scores_lower: MonteCarlo::quantile(&mut scores, tail),
+            scores_upper: MonteCarlo::quantile(&mut scores, 1. - tail),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Distribution, Name, UncertainValues, Values};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_variables() -> Vec<UncertainVariable> {
+        vec![
+            // noisy benchmark: the lower, the better
+            UncertainVariable::autoscale(
+                Name::try_from("latency").unwrap(),
+                UncertainValues::try_from(vec![
+                    Distribution::normal(10., 1.).unwrap(),
+                    Distribution::uniform(9., 13.).unwrap(),
+                    Distribution::triangular(20., 25., 30.).unwrap(),
+                ])
+                .unwrap(),
+            ),
+            UncertainVariable::inverted_autoscale(
+                Name::try_from("throughput").unwrap(),
+                UncertainValues::try_from(vec![
+                    Distribution::empirical(vec![100., 110., 120.]).unwrap(),
+                    Distribution::exact(110.).unwrap(),
+                    Distribution::exact(50.).unwrap(),
+                ])
+                .unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_run() {
+        let result = MonteCarlo::new(500, 42)
+            .run(&create_test_variables())
+            .unwrap();
+
+        assert_ulps_eq!(result.win_probabilities().sum(), 1., epsilon = 1e-12);
+        for sums in [
+            result.rank_distribution().sum_axis(Axis(0)),
+            result.rank_distribution().sum_axis(Axis(1)),
+        ] {
+            assert_ulps_eq!(sums, Array1::ones(3), epsilon = 1e-12);
+        }
+        // the last alternative is always the worst
+        assert_ulps_eq!(result.rank_distribution()[[2, 2]], 1.);
+        assert!(result.win_probabilities()[0] > 0.);
+        assert!(result.win_probabilities()[1] > 0.);
+
+        // NOTE: the last alternative has a constant score, its mean may differ by rounding
+        for i in 0..3 {
+            assert!(result.scores_lower()[i] <= result.mean_scores()[i] + 1e-12);
+            assert!(result.mean_scores()[i] <= result.scores_upper()[i] + 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_run_is_reproducible() {
+        let variables = create_test_variables();
+        let first = MonteCarlo::new(100, 7).run(&variables).unwrap();
+        let second = MonteCarlo::new(100, 7).run(&variables).unwrap();
+        assert_eq!(first.rank_distribution(), second.rank_distribution());
+        assert_eq!(first.scores_upper(), second.scores_upper());
+    }
+
+    #[test]
+    fn test_run_exact_values_match_problem() {
+        let variables = vec![UncertainVariable::autoscale(
+            Name::try_from("x").unwrap(),
+            UncertainValues::from(Values::try_from(array![1., 2., 3.]).unwrap()),
+        )];
+        let result = MonteCarlo::new(10, 0).run(&variables).unwrap();
+        assert_ulps_eq!(result.win_probabilities(), array![1., 0., 0.]);
+        assert_ulps_eq!(result.scores_lower(), array![0., 0.5, 1.]);
+        assert_ulps_eq!(result.scores_upper(), array![0., 0.5, 1.]);
+    }
+
+    #[test]
+    fn test_run_with_weights() {
+        let weights = Weights::try_from(vec![
+            (Name::try_from("latency").unwrap(), 1.),
+            (Name::try_from("throughput").unwrap(), 0.),
+        ])
+        .unwrap();
+        let result = MonteCarlo::new(100, 0)
+            .with_weights(weights)
+            .run(&create_test_variables())
+            .unwrap();
+        assert_ulps_eq!(result.rank_distribution()[[2, 2]], 1.);
+    }
+
+    #[test]
+    fn test_run_huge_values() {
+        // the samples of the normal stay finite, even on its tails, and so do those of a triangular on a huge range
+        let variables = vec![UncertainVariable::autoscale(
+            Name::try_from("x").unwrap(),
+            UncertainValues::try_from(vec![
+                Distribution::normal(f64::MAX, f64::MAX).unwrap(),
+                Distribution::empirical(vec![f64::MAX, f64::MAX]).unwrap(),
+                Distribution::triangular(0., 1e200, 1e200).unwrap(),
+            ])
+            .unwrap(),
+        )];
+        let result = MonteCarlo::new(100, 0).run(&variables).unwrap();
+        assert_eq!(result.win_probabilities().len(), 3);
+    }
+
+    #[test]
+    fn run_no_iterations_failure() {
+        let err = MonteCarlo::new(0, 0)
+            .run(&create_test_variables())
+            .unwrap_err();
+        assert_eq!(err, MonteCarloError::NoIterations);
+    }
+
+    #[test]
+    fn run_invalid_confidence_failure() {
+        let err = MonteCarlo::new(1, 0)
+            .with_confidence(1.)
+            .run(&create_test_variables())
+            .unwrap_err();
+        assert_eq!(err, MonteCarloError::InvalidConfidence);
+    }
+
+    #[test]
+    fn run_empty_problem_failure() {
+        let err = MonteCarlo::new(1, 0).run(&[]).unwrap_err();
+        assert_eq!(err, MonteCarloError::Problem(ProblemError::Empty));
+    }
+}
@@ -0,0 +1,291 @@
+//! This module ranks problems with MULTIMOORA: the ratio system, the reference point approach and the full
+//! multiplicative form, combined with the dominance theory.
+//!
+//! The ratio system and the reference point work on the vector normalized values (each value divided by the euclidean
+//! norm of its variable), the full multiplicative form on the raw values, which must be positive. All of them use the
+//! direction of each variable: the higher, the better for `VariableInvertedAutoscale`, the lower, the better for
+//! `VariableAutoscale`.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::weights::Weights;
+use crate::variables::normalize_vector;
+use ndarray::{Array1, Array2, Axis};
+
+#[derive(Debug, Clone, Default)]
+pub struct Multimoora {
+    weights: Option<Weights>,
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum MultimooraError {
+    #[error("The values of '{0}' must be positive for the full multiplicative form")]
+    NonPositiveValues(String),
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct MultimooraRanking {
+    ratio_system: Ranking,
+    reference_point: Ranking,
+    full_multiplicative: Ranking,
+    ranking: Ranking,
+}
+
+impl MultimooraRanking {
+    /// The weighted sum of the normalized benefits minus the weighted sum of the normalized costs: the higher, the
+    /// better.
+    pub fn ratio_system(&self) -> &Ranking {
+        &self.ratio_system
+    }
+
+    /// The weighted Tchebycheff distance from the best normalized values: the lower, the better.
+    pub fn reference_point(&self) -> &Ranking {
+        &self.reference_point
+    }
+
+    /// The weighted product of the benefits divided by the weighted product of the costs: the higher, the better.
+    pub fn full_multiplicative(&self) -> &Ranking {
+        &self.full_multiplicative
+    }
+
+    /// The three rankings combined with the dominance theory.
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+}
+
+/// One column for each variable, with the signed weights: positive for benefits, negative for costs.
+fn signed_weights(problem: &Problem, weights: &Array1<f64>) -> Array1<f64> {
+    problem
+        .variables()
+        .zip(weights)
+        .map(|(v, &w)| if v.higher_is_better() { w } else { -w })
+        .collect()
+}
+
+fn get_normalized_matrix(problem: &Problem) -> Array2<f64> {
+    let mut matrix = Array2::zeros((problem.alternatives(), problem.names().count()));
+    for (mut column, variable) in matrix.axis_iter_mut(Axis(1)).zip(problem.variables()) {
+        column.assign(&normalize_vector(variable.values()).values());
+    }
+    matrix
+}
+
+fn ratio_system(matrix: &Array2<f64>, signed_weights: &Array1<f64>) -> Ranking {
+    Ranking::descending(matrix.dot(signed_weights))
+}
+
+fn reference_point(matrix: &Array2<f64>, signed_weights: &Array1<f64>) -> Ranking {
+    let mut distances = Array1::<f64>::zeros(matrix.nrows());
+    for (column, &w) in matrix.axis_iter(Axis(1)).zip(signed_weights) {
+        // the reference is the best value of the variable
+        let reference = if w >= 0. {
+            column.fold(f64::NEG_INFINITY, |a, &b| a.max(b))
+        } else {
+            column.fold(f64::INFINITY, |a, &b| a.min(b))
+        };
+        for (distance, &x) in distances.iter_mut().zip(column) {
+            *distance = distance.max(w.abs() * (reference - x).abs());
+        }
+    }
+    Ranking::ascending(distances)
+}
+
+fn full_multiplicative(
+    problem: &Problem,
+    signed_weights: &Array1<f64>,
+) -> Result<Ranking, MultimooraError> {
+    // the logarithm of the utility: benefits are multiplied, costs divided
+    let mut utilities = Array1::<f64>::zeros(problem.alternatives());
+    for (variable, &w) in problem.variables().zip(signed_weights) {
+        let values = variable.values().values();
+        if values.iter().any(|&x| x <= 0.) {
+            return Err(MultimooraError::NonPositiveValues(
+                variable.name().as_str().to_string(),
+            ));
+        }
+        utilities += &values.mapv(|x| w * x.ln());
+    }
+    Ok(Ranking::descending(utilities.mapv(f64::exp)))
+}
+
+/// Combines the rankings: an alternative dominates another one when it is better in the majority of the rankings.
+fn dominance(rankings: &[&Ranking]) -> Ranking {
+    let size = rankings[0].len();
+    let positions: Vec<Vec<usize>> = rankings.iter().map(|r| r.positions()).collect();
+    let scores = (0..size)
+        .map(|a| {
+            let mut score = 0.;
+            for b in (0..size).filter(|&b| b != a) {
+                let better = positions.iter().filter(|p| p[a] < p[b]).count();
+                let worse = positions.iter().filter(|p| p[a] > p[b]).count();
+                score += match better.cmp(&worse) {
+                    std::cmp::Ordering::Greater => 1.,
+                    std::cmp::Ordering::Less => -1.,
+                    std::cmp::Ordering::Equal => 0.,
+                };
+            }
+            // ties are broken by the sum of the positions, which is always lower than the number of rankings times
+            // the number of alternatives
+            let total: usize = positions.iter().map(|p| p[a]).sum();
+            score - total as f64 / (rankings.len() * size) as f64
+        })
+        .collect();
+    Ranking::descending(scores)
+}
+
+impl Multimoora {
+    /// MULTIMOORA with equal weights.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    pub fn rank(&self, problem: &Problem) -> Result<MultimooraRanking, MultimooraError> {
+        let weights = problem.get_weights_or_equal(self.weights.as_ref())?;
+        let signed_weights = signed_weights(problem, &weights);
+        let matrix = get_normalized_matrix(problem);
+
+        let ratio_system = ratio_system(&matrix, &signed_weights);
+        let reference_point = reference_point(&matrix, &signed_weights);
+        let full_multiplicative = full_multiplicative(problem, &signed_weights)?;
+        let ranking = dominance(&[&ratio_system, &reference_point, &full_multiplicative]);
+        Ok(MultimooraRanking {
+            ratio_system,
+            reference_point,
+            full_multiplicative,
+            ranking,
+        })
+    }
+
+    /// Ranks values normalized otherwise than by the vector norm, the higher the better: one row for each
+    /// alternative, one column for each variable. The full multiplicative form still works on the raw values.
+    pub(super) fn rank_benefits(
+        &self,
+        problem: &Problem,
+        benefits: &Array2<f64>,
+    ) -> Result<MultimooraRanking, MultimooraError> {
+        let weights = problem.get_weights_or_equal(self.weights.as_ref())?;
+
+        let ratio_system = ratio_system(benefits, &weights);
+        let reference_point = reference_point(benefits, &weights);
+        let full_multiplicative = full_multiplicative(problem, &signed_weights(problem, &weights))?;
+        let ranking = dominance(&[&ratio_system, &reference_point, &full_multiplicative]);
+        Ok(MultimooraRanking {
+            ratio_system,
+            reference_point,
+            full_multiplicative,
+            ranking,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Name, Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![3., 4., 12.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![4., 3., 12.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalized_matrix() {
+        let matrix = get_normalized_matrix(&create_test_problem());
+        assert_ulps_eq!(
+            matrix,
+            array![[3., 4.], [4., 3.], [12., 12.]] / 13.,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        let result = Multimoora::new().rank(&create_test_problem()).unwrap();
+        // cost and quality weigh the same
+        assert_ulps_eq!(
+            result.ratio_system().scores(),
+            array![0.5, -0.5, 0.] / 13.,
+            epsilon = 1e-12
+        );
+        assert_eq!(result.ratio_system().order(), &[0, 2, 1]);
+        assert_ulps_eq!(
+            result.reference_point().scores(),
+            array![4., 4.5, 4.5] / 13.,
+            epsilon = 1e-12
+        );
+        assert_eq!(result.reference_point().order(), &[0, 1, 2]);
+        assert_ulps_eq!(
+            result.full_multiplicative().scores(),
+            array![(4f64 / 3.).sqrt(), (3f64 / 4.).sqrt(), 1.],
+            epsilon = 1e-12
+        );
+        assert_eq!(result.full_multiplicative().order(), &[0, 2, 1]);
+        // the first alternative is the best in all the rankings, the third beats the second in two of them
+        assert_eq!(result.ranking().order(), &[0, 2, 1]);
+    }
+
+    #[test]
+    fn test_dominance_ties() {
+        // a cycle: each alternative beats another one in two of the three rankings
+        let a = Ranking::from_order(vec![0, 1, 2]).unwrap();
+        let b = Ranking::from_order(vec![1, 2, 0]).unwrap();
+        let c = Ranking::from_order(vec![2, 0, 1]).unwrap();
+        let ranking = dominance(&[&a, &b, &c]);
+        // same dominance and same sum of positions: the order of the alternatives is kept
+        assert_eq!(ranking.order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_rank_with_weights() {
+        let weights = Weights::try_from(vec![
+            (Name::try_from("cost").unwrap(), 0.),
+            (Name::try_from("quality").unwrap(), 1.),
+        ])
+        .unwrap();
+        let result = Multimoora::new()
+            .with_weights(weights)
+            .rank(&create_test_problem())
+            .unwrap();
+        assert_eq!(result.ranking().best(), 2);
+    }
+
+    #[test]
+    fn rank_non_positive_values_failure() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            Values::try_from(array![0., 1.]).unwrap(),
+        ))])
+        .unwrap();
+        let err = Multimoora::new().rank(&p).unwrap_err();
+        assert_eq!(err, MultimooraError::NonPositiveValues("x".to_string()));
+    }
+
+    #[test]
+    fn rank_weights_mismatch_failure() {
+        let weights = Weights::try_from(vec![(Name::try_from("cost").unwrap(), 1.)]).unwrap();
+        let err = Multimoora::new()
+            .with_weights(weights)
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, MultimooraError::Problem(ProblemError::WeightsMismatch));
+    }
+}
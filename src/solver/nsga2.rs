@@ -0,0 +1,634 @@
+//! This module generates alternatives with NSGA-II, a multi-objective evolutionary optimizer.
+//!
+//! The design variables are continuous and bounded, the objectives are closures of the design variables. Each
+//! generation breeds the population with simulated binary crossover and polynomial mutation, then keeps the best
+//! individuals by non-domination rank and crowding distance. The result approximates the Pareto front, and converts to
+//! a `Problem` to pick the compromise point.
+use super::problem::Problem;
+use crate::variables::{
+    Name, Values, VariableAutoscale, VariableInvertedAutoscale, VariableProperties,
+};
+use ndarray::{Array2, ArrayView1, ArrayView2};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignVariable {
+    name: Name,
+    lower: f64,
+    upper: f64,
+}
+
+/// A function of the design variables, in the same order of the design variables.
+pub type ObjectiveFunction = Box<dyn Fn(&[f64]) -> f64>;
+
+pub struct Objective {
+    name: Name,
+    maximize: bool,
+    function: ObjectiveFunction,
+}
+
+#[derive(Debug, Clone)]
+pub struct Nsga2 {
+    population: usize,
+    generations: usize,
+    seed: u64,
+    crossover_probability: f64,
+    crossover_index: f64,
+    mutation_probability: Option<f64>,
+    mutation_index: f64,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum Nsga2Error {
+    #[error(
+        "The bounds and their range must be finite, with the lower bound not above the upper bound"
+    )]
+    InvalidBounds,
+    #[error("At least one design variable is required")]
+    NoDesignVariables,
+    #[error("At least one objective is required")]
+    NoObjectives,
+    #[error("Each objective must have an unique name: '{0}' is defined more than once")]
+    RedefinitionObjective(String),
+    #[error("The population must have at least 2 individuals")]
+    PopulationTooSmall,
+    #[error("The probabilities must be in [0, 1] and the distribution indexes non-negative")]
+    InvalidParameter,
+    #[error("The objective '{0}' must be finite for all the design variables within bounds")]
+    NotFiniteObjective(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ParetoFront {
+    objective_names: Vec<Name>,
+    maximize: Vec<bool>,
+    design: Array2<f64>,
+    objectives: Array2<f64>,
+}
+
+impl DesignVariable {
+    pub fn new(name: Name, lower: f64, upper: f64) -> Result<Self, Nsga2Error> {
+        // the initial population and the mutation are spread over the range, so it must not overflow
+        if !(lower.is_finite()
+            && upper.is_finite()
+            && lower <= upper
+            && (upper - lower).is_finite())
+        {
+            return Err(Nsga2Error::InvalidBounds);
+        }
+        Ok(Self { name, lower, upper })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    fn clamp(&self, x: f64) -> f64 {
+        x.clamp(self.lower, self.upper)
+    }
+}
+
+impl Objective {
+    pub fn minimize(name: Name, function: impl Fn(&[f64]) -> f64 + 'static) -> Self {
+        Self {
+            name,
+            maximize: false,
+            function: Box::new(function),
+        }
+    }
+
+    pub fn maximize(name: Name, function: impl Fn(&[f64]) -> f64 + 'static) -> Self {
+        Self {
+            name,
+            maximize: true,
+            function: Box::new(function),
+        }
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+}
+
+impl std::fmt::Debug for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Objective")
+            .field("name", &self.name)
+            .field("maximize", &self.maximize)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ParetoFront {
+    /// The design variables of each point: one row for each point, one column for each design variable.
+    pub fn design(&self) -> ArrayView2<'_, f64> {
+        self.design.view()
+    }
+
+    /// The objectives of each point: one row for each point, one column for each objective.
+    pub fn objectives(&self) -> ArrayView2<'_, f64> {
+        self.objectives.view()
+    }
+
+    pub fn point(&self, index: usize) -> ArrayView1<'_, f64> {
+        self.design.row(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.design.nrows()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // NOTE: always false, the front of a non-empty population is never empty
+        self.design.nrows() == 0
+    }
+
+    /// A problem with the points as alternatives and the objectives as variables, in the same direction.
+    pub fn to_problem(&self) -> Problem {
+        let variables = self
+            .objective_names
+            .iter()
+            .zip(&self.maximize)
+            .zip(self.objectives.columns())
+            .map(|((name, &maximize), values)| {
+                // NOTE: the objectives are always finite
+                let values = Values::try_from(values.to_owned()).unwrap();
+                let variable: Box<dyn VariableProperties> = if maximize {
+                    Box::new(VariableInvertedAutoscale::new(name.clone(), values))
+                } else {
+                    Box::new(VariableAutoscale::new(name.clone(), values))
+                };
+                variable
+            })
+            .collect();
+        // NOTE: the objectives have unique names and the same number of points
+        Problem::define(variables).unwrap()
+    }
+}
+
+/// Whether `a` is at least as good as `b` on all the objectives, and better on one. All objectives are minimized.
+fn dominates(a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y)
+}
+
+/// The fronts of the points, from the non-dominated ones.
+fn non_dominated_fronts(objectives: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let size = objectives.len();
+    let mut dominated_by = vec![0usize; size];
+    let mut dominating: Vec<Vec<usize>> = vec![Vec::new(); size];
+    for a in 0..size {
+        for b in a + 1..size {
+            if dominates(&objectives[a], &objectives[b]) {
+                dominating[a].push(b);
+                dominated_by[b] += 1;
+            } else if dominates(&objectives[b], &objectives[a]) {
+                dominating[b].push(a);
+                dominated_by[a] += 1;
+            }
+        }
+    }
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..size).filter(|&i| dominated_by[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &a in &front {
+            for &b in &dominating[a] {
+                dominated_by[b] -= 1;
+                if dominated_by[b] == 0 {
+                    next.push(b);
+                }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+/// The crowding distance of each point of the front, in the same order of the front.
+fn crowding_distances(front: &[usize], objectives: &[Vec<f64>]) -> Vec<f64> {
+    let mut distances = vec![0.; front.len()];
+    let count = objectives[front[0]].len();
+    let columns = (0..count).map(|k| {
+        front
+            .iter()
+            .map(|&i| objectives[i][k])
+            .collect::<Vec<f64>>()
+    });
+    for column in columns {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| column[a].total_cmp(&column[b]));
+        let min = column[order[0]];
+        let max = column[order[order.len() - 1]];
+        // the extremes are always kept
+        distances[order[0]] = f64::INFINITY;
+        distances[order[order.len() - 1]] = f64::INFINITY;
+        if max > min {
+            for k in 1..order.len().saturating_sub(1) {
+                distances[order[k]] += (column[order[k + 1]] - column[order[k - 1]]) / (max - min);
+            }
+        }
+    }
+    distances
+}
+
+/// The best `size` points: by front, then by crowding distance. Returns the indexes with their rank and distance.
+fn select(objectives: &[Vec<f64>], size: usize) -> Vec<(usize, usize, f64)> {
+    let mut selected = Vec::with_capacity(size);
+    for (rank, front) in non_dominated_fronts(objectives).into_iter().enumerate() {
+        if selected.len() >= size {
+            break;
+        }
+        let distances = crowding_distances(&front, objectives);
+        let mut members: Vec<(usize, usize, f64)> = front
+            .into_iter()
+            .zip(distances)
+            .map(|(i, distance)| (i, rank, distance))
+            .collect();
+        // the least crowded first, in case the front does not fit
+        members.sort_by(|a, b| b.2.total_cmp(&a.2));
+        members.truncate(size - selected.len());
+        selected.extend(members);
+    }
+    selected
+}
+
+impl Nsga2 {
+    /// NSGA-II with the usual parameters: crossover probability 0.9 and index 15, mutation probability of one
+    /// design variable for each individual and index 20.
+    pub fn new(population: usize, generations: usize, seed: u64) -> Self {
+        Self {
+            population,
+            generations,
+            seed,
+            crossover_probability: 0.9,
+            crossover_index: 15.,
+            mutation_probability: None,
+            mutation_index: 20.,
+        }
+    }
+
+    /// The probability of crossing two parents and the distribution index: the higher, the closer the children to
+    /// their parents.
+    pub fn with_crossover(mut self, probability: f64, index: f64) -> Self {
+        self.crossover_probability = probability;
+        self.crossover_index = index;
+        self
+    }
+
+    /// The probability of mutating each design variable and the distribution index: the higher, the smaller the
+    /// mutations.
+    pub fn with_mutation(mut self, probability: f64, index: f64) -> Self {
+        self.mutation_probability = Some(probability);
+        self.mutation_index = index;
+        self
+    }
+
+    fn check(
+        &self,
+        variables: &[DesignVariable],
+        objectives: &[Objective],
+    ) -> Result<(), Nsga2Error> {
+        if variables.is_empty() {
+            return Err(Nsga2Error::NoDesignVariables);
+        }
+        if objectives.is_empty() {
+            return Err(Nsga2Error::NoObjectives);
+        }
+        let mut names = BTreeSet::new();
+        if let Some(objective) = objectives.iter().find(|o| !names.insert(&o.name)) {
+            return Err(Nsga2Error::RedefinitionObjective(
+                objective.name.as_str().to_string(),
+            ));
+        }
+        if self.population < 2 {
+            return Err(Nsga2Error::PopulationTooSmall);
+        }
+        let probabilities = [
+            self.crossover_probability,
+            self.mutation_probability.unwrap_or(0.),
+        ];
+        if probabilities.iter().any(|p| !(0. ..=1.).contains(p))
+            || !(self.crossover_index >= 0. && self.mutation_index >= 0.)
+        {
+            return Err(Nsga2Error::InvalidParameter);
+        }
+        Ok(())
+    }
+
+    /// The objectives of a point, all to be minimized.
+    fn evaluate(objectives: &[Objective], x: &[f64]) -> Result<Vec<f64>, Nsga2Error> {
+        objectives
+            .iter()
+            .map(|objective| {
+                let value = (objective.function)(x);
+                if !value.is_finite() {
+                    return Err(Nsga2Error::NotFiniteObjective(
+                        objective.name.as_str().to_string(),
+                    ));
+                }
+                Ok(if objective.maximize { -value } else { value })
+            })
+            .collect()
+    }
+
+    /// Simulated binary crossover of two parents.
+    fn crossover<R: Rng>(
+        &self,
+        rng: &mut R,
+        variables: &[DesignVariable],
+        a: &[f64],
+        b: &[f64],
+    ) -> (Vec<f64>, Vec<f64>) {
+        let mut first = a.to_vec();
+        let mut second = b.to_vec();
+        if rng.random::<f64>() >= self.crossover_probability {
+            return (first, second);
+        }
+        let exponent = 1. / (self.crossover_index + 1.);
+        for (j, variable) in variables.iter().enumerate() {
+            if rng.random::<f64>() < 0.5 {
+                let u = rng.random::<f64>();
+                let beta = if u <= 0.5 {
+                    (2. * u).powf(exponent)
+                } else {
+                    (1. / (2. * (1. - u))).powf(exponent)
+                };
+                first[j] = variable.clamp(0.5 * ((1. + beta) * a[j] + (1. - beta) * b[j]));
+                second[j] = variable.clamp(0.5 * ((1. - beta) * a[j] + (1. + beta) * b[j]));
+            }
+        }
+        (first, second)
+    }
+
+    /// Polynomial mutation of a child.
+    fn mutate<R: Rng>(&self, rng: &mut R, variables: &[DesignVariable], x: &mut [f64]) {
+        let probability = self
+            .mutation_probability
+            .unwrap_or(1. / variables.len() as f64);
+        let exponent = 1. / (self.mutation_index + 1.);
+        for (j, variable) in variables.iter().enumerate() {
+            if rng.random::<f64>() < probability {
+                let u = rng.random::<f64>();
+                let delta = if u < 0.5 {
+                    (2. * u).powf(exponent) - 1.
+                } else {
+                    1. - (2. * (1. - u)).powf(exponent)
+                };
+                x[j] = variable.clamp(x[j] + delta * (variable.upper - variable.lower));
+            }
+        }
+    }
+
+    /// Binary tournament: the better rank wins, then the bigger crowding distance.
+    fn tournament<R: Rng>(rng: &mut R, selected: &[(usize, usize, f64)]) -> usize {
+        let a = selected[rng.random_range(0..selected.len())];
+        let b = selected[rng.random_range(0..selected.len())];
+        if a.1 < b.1 || (a.1 == b.1 && a.2 > b.2) {
+            a.0
+        } else {
+            b.0
+        }
+    }
+
+    pub fn optimize(
+        &self,
+        variables: &[DesignVariable],
+        objectives: &[Objective],
+    ) -> Result<ParetoFront, Nsga2Error> {
+        self.check(variables, objectives)?;
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+
+        let mut points: Vec<Vec<f64>> = (0..self.population)
+            .map(|_| {
+                variables
+                    .iter()
+                    .map(|v| v.lower + rng.random::<f64>() * (v.upper - v.lower))
+                    .collect()
+            })
+            .collect();
+        let mut values = points
+            .iter()
+            .map(|x| Nsga2::evaluate(objectives, x))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut selected = select(&values, self.population);
+
+        for _ in 0..self.generations {
+            while points.len() < 2 * self.population {
+                let a = Nsga2::tournament(&mut rng, &selected);
+                let b = Nsga2::tournament(&mut rng, &selected);
+                let (mut first, mut second) =
+                    self.crossover(&mut rng, variables, &points[a], &points[b]);
+                for child in [&mut first, &mut second] {
+                    self.mutate(&mut rng, variables, child);
+                }
+                for child in [first, second] {
+                    if points.len() < 2 * self.population {
+                        values.push(Nsga2::evaluate(objectives, &child)?);
+                        points.push(child);
+                    }
+                }
+            }
+            // parents and children compete for the next generation
+            selected = select(&values, self.population);
+            points = selected.iter().map(|s| points[s.0].clone()).collect();
+            values = selected.iter().map(|s| values[s.0].clone()).collect();
+            selected = selected
+                .iter()
+                .enumerate()
+                .map(|(i, &(_, rank, distance))| (i, rank, distance))
+                .collect();
+        }
+
+        let front: Vec<usize> = selected.iter().filter(|s| s.1 == 0).map(|s| s.0).collect();
+        let design =
+            Array2::from_shape_fn((front.len(), variables.len()), |(i, j)| points[front[i]][j]);
+        let objectives_values = Array2::from_shape_fn((front.len(), objectives.len()), |(i, j)| {
+            // back to the original direction of the objective
+            if objectives[j].maximize {
+                -values[front[i]][j]
+            } else {
+                values[front[i]][j]
+            }
+        });
+        Ok(ParetoFront {
+            objective_names: objectives.iter().map(|o| o.name.clone()).collect(),
+            maximize: objectives.iter().map(|o| o.maximize).collect(),
+            design,
+            objectives: objectives_values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+
+    fn create_test_variables() -> Vec<DesignVariable> {
+        vec![DesignVariable::new(Name::try_from("x").unwrap(), -5., 5.).unwrap()]
+    }
+
+    fn create_test_objectives() -> Vec<Objective> {
+        // Schaffer's problem: the Pareto set is 0 <= x <= 2
+        vec![
+            Objective::minimize(Name::try_from("f1").unwrap(), |x| x[0].powi(2)),
+            Objective::minimize(Name::try_from("f2").unwrap(), |x| (x[0] - 2.).powi(2)),
+        ]
+    }
+
+    #[test]
+    fn test_dominates() {
+        assert!(dominates(&[1., 1.], &[1., 2.]));
+        assert!(!dominates(&[1., 2.], &[1., 2.]));
+        assert!(!dominates(&[0., 3.], &[1., 2.]));
+    }
+
+    #[test]
+    fn test_non_dominated_fronts() {
+        let objectives = vec![
+            vec![1., 4.],
+            vec![2., 2.],
+            vec![3., 3.],
+            vec![4., 4.],
+            vec![4., 1.],
+        ];
+        assert_eq!(
+            non_dominated_fronts(&objectives),
+            vec![vec![0, 1, 4], vec![2], vec![3]]
+        );
+    }
+
+    #[test]
+    fn test_crowding_distances() {
+        let objectives = vec![vec![0., 4.], vec![1., 3.], vec![3., 1.], vec![4., 0.]];
+        let distances = crowding_distances(&[0, 1, 2, 3], &objectives);
+        assert_eq!(distances[0], f64::INFINITY);
+        assert_eq!(distances[3], f64::INFINITY);
+        assert_ulps_eq!(distances[1], 1.5);
+        assert_ulps_eq!(distances[2], 1.5);
+    }
+
+    #[test]
+    fn test_optimize() {
+        let front = Nsga2::new(40, 50, 42)
+            .optimize(&create_test_variables(), &create_test_objectives())
+            .unwrap();
+        assert_eq!(front.len(), 40);
+        assert!(
+            front
+                .design()
+                .iter()
+                .all(|&x| (-1e-3..=2. + 1e-3).contains(&x))
+        );
+        for (point, objectives) in front
+            .design()
+            .rows()
+            .into_iter()
+            .zip(front.objectives().rows())
+        {
+            assert_ulps_eq!(objectives[0], point[0].powi(2));
+        }
+
+        // the compromise point, at the same distance from both ideals
+        let compromise = front.point(front.to_problem().solve());
+        assert!((compromise[0] - 1.).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_optimize_is_reproducible() {
+        let optimizer = Nsga2::new(20, 10, 7);
+        let first = optimizer
+            .optimize(&create_test_variables(), &create_test_objectives())
+            .unwrap();
+        let second = optimizer
+            .optimize(&create_test_variables(), &create_test_objectives())
+            .unwrap();
+        assert_eq!(first.design(), second.design());
+    }
+
+    #[test]
+    fn test_maximize() {
+        let objectives = vec![
+            Objective::maximize(Name::try_from("gain").unwrap(), |x| x[0] + x[1]),
+            Objective::minimize(Name::try_from("risk").unwrap(), |x| x[0]),
+        ];
+        let variables = vec![
+            DesignVariable::new(Name::try_from("a").unwrap(), 0., 1.).unwrap(),
+            DesignVariable::new(Name::try_from("b").unwrap(), 0., 1.).unwrap(),
+        ];
+        let front = Nsga2::new(20, 30, 0)
+            .optimize(&variables, &objectives)
+            .unwrap();
+        // b is free gain: the front pushes it to its upper bound
+        assert!(front.design().column(1).iter().all(|&b| b > 0.9));
+        assert!(front.objectives().column(0).iter().all(|&gain| gain > 0.9));
+
+        let problem = front.to_problem();
+        let names: Vec<&str> = problem.names().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["gain", "risk"]);
+        assert_eq!(problem.alternatives(), front.len());
+    }
+
+    #[test]
+    fn new_design_variable_failure() {
+        assert_eq!(
+            DesignVariable::new(Name::try_from("x").unwrap(), 1., 0.).unwrap_err(),
+            Nsga2Error::InvalidBounds
+        );
+        assert_eq!(
+            DesignVariable::new(Name::try_from("x").unwrap(), 0., f64::INFINITY).unwrap_err(),
+            Nsga2Error::InvalidBounds
+        );
+        assert_eq!(
+            DesignVariable::new(Name::try_from("x").unwrap(), -f64::MAX, f64::MAX).unwrap_err(),
+            Nsga2Error::InvalidBounds
+        );
+    }
+
+    #[test]
+    fn optimize_failure() {
+        let variables = create_test_variables();
+        let objectives = create_test_objectives();
+        let optimizer = Nsga2::new(10, 1, 0);
+        assert_eq!(
+            optimizer.optimize(&[], &objectives).unwrap_err(),
+            Nsga2Error::NoDesignVariables
+        );
+        assert_eq!(
+            optimizer.optimize(&variables, &[]).unwrap_err(),
+            Nsga2Error::NoObjectives
+        );
+        assert_eq!(
+            Nsga2::new(1, 1, 0)
+                .optimize(&variables, &objectives)
+                .unwrap_err(),
+            Nsga2Error::PopulationTooSmall
+        );
+        assert_eq!(
+            optimizer
+                .clone()
+                .with_mutation(2., 20.)
+                .optimize(&variables, &objectives)
+                .unwrap_err(),
+            Nsga2Error::InvalidParameter
+        );
+        let twice = vec![
+            Objective::minimize(Name::try_from("f").unwrap(), |x| x[0]),
+            Objective::minimize(Name::try_from("f").unwrap(), |x| -x[0]),
+        ];
+        assert_eq!(
+            optimizer.optimize(&variables, &twice).unwrap_err(),
+            Nsga2Error::RedefinitionObjective("f".to_string())
+        );
+        let not_finite = vec![Objective::minimize(Name::try_from("f").unwrap(), |x| {
+            x[0].ln()
+        })];
+        assert_eq!(
+            optimizer.optimize(&variables, &not_finite).unwrap_err(),
+            Nsga2Error::NotFiniteObjective("f".to_string())
+        );
+    }
+}
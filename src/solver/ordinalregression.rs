@@ -0,0 +1,608 @@
+//! This module learns an additive value model from past decisions, with UTA-style ordinal regression.
+//!
+//! The decision maker states preferences between the alternatives of a reference problem: one alternative is better
+//! than another one, both are equally good, or one was chosen among some others. The model is the sum of a marginal
+//! value function for each variable: piecewise linear on equal segments between the bounds of its rescaling,
+//! non-decreasing in the better direction, from 0 for the worst bound to the weight of the variable for the best one.
+//! The weights sum to 1.
+//!
+//! A first linear program finds the smallest errors that make all the preferences compatible: the preferences with an
+//! error are the inconsistent ones. A second one picks, among the models of the consistent preferences, the most
+//! representative one: the model that maximizes the smallest difference of value between preferred alternatives. The
+//! model then ranks new problems with the same variables, whatever their alternatives.
+use super::linprog::{LinearProgram, LinearProgramError};
+use super::problem::{Problem, worst_and_best};
+use super::ranking::Ranking;
+use super::weights::Weights;
+use crate::variables::Name;
+use ndarray::{Array1, Array2, ArrayView1, Axis, s};
+
+/// The difference of value that makes a preference strict while looking for the errors.
+const MARGIN: f64 = 1e-3;
+/// Errors below this are ignored.
+const TOLERANCE: f64 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preference {
+    /// The first alternative is better than the second one.
+    Prefers(usize, usize),
+    /// Both alternatives are equally good.
+    Indifferent(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct OrdinalRegression {
+    preferences: Vec<Preference>,
+    segments: usize,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum OrdinalRegressionError {
+    #[error("At least one preference is required")]
+    NoPreferences,
+    #[error("The alternative {0} is not an alternative of the problem")]
+    UnknownAlternative(usize),
+    #[error("The marginal value functions need at least one segment")]
+    InvalidSegments,
+    #[error("The problem must have the same variables of the model")]
+    VariablesMismatch,
+    #[error(transparent)]
+    LinearProgram(#[from] LinearProgramError),
+}
+
+/// The value of a variable, piecewise linear between the breakpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginalValue {
+    name: Name,
+    worst: f64,
+    best: f64,
+    values: Array1<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueModel {
+    marginals: Vec<MarginalValue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrdinalRegressionResult {
+    model: ValueModel,
+    inconsistent: Vec<Preference>,
+    margin: f64,
+}
+
+impl MarginalValue {
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// The values of the variable at the breakpoints, from the worst to the best.
+    pub fn breakpoints(&self) -> Array1<f64> {
+        Array1::linspace(self.worst, self.best, self.values.len())
+    }
+
+    /// The marginal values at the breakpoints, from 0 to the weight of the variable.
+    pub fn values(&self) -> ArrayView1<'_, f64> {
+        self.values.view()
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.values[self.values.len() - 1]
+    }
+
+    /// The position of the value, from 0 (the worst) to 1 (the best).
+    fn position(&self, x: f64) -> f64 {
+        if self.best != self.worst {
+            ((x - self.worst) / (self.best - self.worst)).clamp(0., 1.)
+        } else if x == self.best {
+            1.
+        } else {
+            0.
+        }
+    }
+
+    /// The marginal value of `x`: values beyond the worst and the best are clamped.
+    pub fn value(&self, x: f64) -> f64 {
+        let segments = self.values.len() - 1;
+        let position = self.position(x);
+        let k = ((position * segments as f64).floor() as usize).min(segments - 1);
+        let fraction = position * segments as f64 - k as f64;
+        self.values[k] + fraction * (self.values[k + 1] - self.values[k])
+    }
+}
+
+impl ValueModel {
+    /// The marginal value functions, in the same order of the variables of the problem.
+    pub fn marginals(&self) -> &[MarginalValue] {
+        &self.marginals
+    }
+
+    /// The weights of the variables: the marginal values of their best values.
+    pub fn weights(&self) -> Weights {
+        let weights = self
+            .marginals
+            .iter()
+            .map(|m| (m.name.clone(), m.weight().max(0.)))
+            .collect::<Vec<_>>();
+        // NOTE: the weights are non-negative and sum to 1
+        Weights::try_from(weights).unwrap()
+    }
+
+    /// The value of each alternative of a problem with the same variables, from 0 to 1.
+    pub fn evaluate(&self, problem: &Problem) -> Result<Array1<f64>, OrdinalRegressionError> {
+        if !problem.names().eq(self.marginals.iter().map(|m| &m.name)) {
+            return Err(OrdinalRegressionError::VariablesMismatch);
+        }
+        let mut values = Array1::zeros(problem.alternatives());
+        for (marginal, variable) in self.marginals.iter().zip(problem.variables()) {
+            values += &variable.values().values().mapv(|x| marginal.value(x));
+        }
+        Ok(values)
+    }
+
+    /// Ranks the alternatives of a problem with the same variables: the higher the value, the better.
+    pub fn rank(&self, problem: &Problem) -> Result<Ranking, OrdinalRegressionError> {
+        Ok(Ranking::descending(self.evaluate(problem)?))
+    }
+}
+
+impl OrdinalRegressionResult {
+    /// The most representative model compatible with the consistent preferences.
+    pub fn model(&self) -> &ValueModel {
+        &self.model
+    }
+
+    /// The preferences that no model can satisfy together with the others, with the smallest total error.
+    pub fn inconsistent(&self) -> &[Preference] {
+        &self.inconsistent
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistent.is_empty()
+    }
+
+    /// The smallest difference of value between the alternatives of the consistent preferences, in the model: 0 without
+    /// such preferences.
+    pub fn margin(&self) -> f64 {
+        self.margin
+    }
+}
+
+impl Default for OrdinalRegression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrdinalRegression {
+    /// Ordinal regression with 2 segments for each marginal value function and no preferences.
+    pub fn new() -> Self {
+        Self {
+            preferences: Vec::new(),
+            segments: 2,
+        }
+    }
+
+    /// The number of segments of each marginal value function: 1 learns only the weights.
+    pub fn with_segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// The alternative `a` is better than `b`.
+    pub fn with_preference(mut self, a: usize, b: usize) -> Self {
+        self.preferences.push(Preference::Prefers(a, b));
+        self
+    }
+
+    /// The alternatives `a` and `b` are equally good.
+    pub fn with_indifference(mut self, a: usize, b: usize) -> Self {
+        self.preferences.push(Preference::Indifferent(a, b));
+        self
+    }
+
+    /// The alternative was chosen among the others: it is better than each one of them.
+    pub fn with_choice(mut self, chosen: usize, others: &[usize]) -> Self {
+        for &other in others {
+            self.preferences.push(Preference::Prefers(chosen, other));
+        }
+        self
+    }
+
+    /// A partial ranking, from the best to the worst: each alternative is better than the next one.
+    pub fn with_ranking(mut self, order: &[usize]) -> Self {
+        for pair in order.windows(2) {
+            self.preferences.push(Preference::Prefers(pair[0], pair[1]));
+        }
+        self
+    }
+
+    fn check(&self, problem: &Problem) -> Result<(), OrdinalRegressionError> {
+        if self.preferences.is_empty() {
+            return Err(OrdinalRegressionError::NoPreferences);
+        }
+        if self.segments == 0 {
+            return Err(OrdinalRegressionError::InvalidSegments);
+        }
+        for preference in &self.preferences {
+            let (Preference::Prefers(a, b) | Preference::Indifferent(a, b)) = *preference;
+            if let Some(&unknown) = [a, b].iter().find(|&&x| x >= problem.alternatives()) {
+                return Err(OrdinalRegressionError::UnknownAlternative(unknown));
+            }
+        }
+        Ok(())
+    }
+
+    /// The share of each segment covered by the value of each alternative: one row for each alternative, one column for
+    /// each segment of each variable. The value of an alternative is this matrix times the increments of the
+    /// marginal values.
+    fn coverage(&self, problem: &Problem) -> (Array2<f64>, Vec<MarginalValue>) {
+        let segments = self.segments;
+        let variables = problem.names().count();
+        let mut coverage = Array2::zeros((problem.alternatives(), variables * segments));
+        let mut marginals = Vec::with_capacity(variables);
+        for (j, variable) in problem.variables().enumerate() {
+            let values = variable.values().values();
+            let (worst, best) = worst_and_best(variable);
+            let marginal = MarginalValue {
+                name: variable.name().clone(),
+                worst,
+                best,
+                values: Array1::zeros(segments + 1),
+            };
+            for (a, &x) in values.iter().enumerate() {
+                // the identity marginal value gives the position of the value from 0 (worst) to 1 (best)
+                let position = marginal.position(x);
+                for k in 0..segments {
+                    coverage[[a, j * segments + k]] =
+                        (position * segments as f64 - k as f64).clamp(0., 1.);
+                }
+            }
+            marginals.push(marginal);
+        }
+        (coverage, marginals)
+    }
+
+    pub fn learn(
+        &self,
+        problem: &Problem,
+    ) -> Result<OrdinalRegressionResult, OrdinalRegressionError> {
+        self.check(problem)?;
+        let (coverage, mut marginals) = self.coverage(problem);
+        let increments = coverage.ncols();
+
+        // the variables: the increments, one error for each preference (two for the indifferences), the margin
+        let errors: usize = self
+            .preferences
+            .iter()
+            .map(|p| match p {
+                Preference::Prefers(..) => 1,
+                Preference::Indifferent(..) => 2,
+            })
+            .sum();
+        let columns = increments + errors + 1;
+        let margin = columns - 1;
+        let strict = self
+            .preferences
+            .iter()
+            .filter(|p| matches!(p, Preference::Prefers(..)))
+            .count();
+
+        // U(a) - U(b) + error - margin >= 0, and U(a) - U(b) + over - under = 0
+        let mut a_ge = Array2::zeros((strict, columns));
+        let mut a_eq = Array2::zeros((self.preferences.len() - strict, columns));
+        let (mut ge, mut eq, mut error) = (0, 0, increments);
+        for preference in &self.preferences {
+            match *preference {
+                Preference::Prefers(a, b) => {
+                    let mut row = a_ge.row_mut(ge);
+                    row.slice_mut(s![..increments])
+                        .assign(&(&coverage.row(a) - &coverage.row(b)));
+                    row[error] = 1.;
+                    row[margin] = -1.;
+                    ge += 1;
+                    error += 1;
+                }
+                Preference::Indifferent(a, b) => {
+                    let mut row = a_eq.row_mut(eq);
+                    row.slice_mut(s![..increments])
+                        .assign(&(&coverage.row(a) - &coverage.row(b)));
+                    row[error] = 1.;
+                    row[error + 1] = -1.;
+                    eq += 1;
+                    error += 2;
+                }
+            }
+        }
+        // the weights sum to 1
+        let mut normalization = Array2::zeros((1, columns));
+        normalization.slice_mut(s![0, ..increments]).fill(1.);
+        let mut error_costs = Array1::zeros(columns);
+        error_costs.slice_mut(s![increments..margin]).fill(1.);
+        let mut fixed_margin = Array2::zeros((1, columns));
+        fixed_margin[[0, margin]] = 1.;
+
+        // the smallest errors that make the preferences compatible
+        let first = LinearProgram::minimize(error_costs)
+            .with_greater_equal(a_ge, Array1::zeros(strict))
+            .with_equal(a_eq.clone(), Array1::zeros(a_eq.nrows()))
+            .with_equal(normalization, Array1::ones(1))
+            .with_equal(fixed_margin, Array1::from_elem(1, MARGIN))
+            .solve()?;
+
+        let mut inconsistent = Vec::new();
+        let mut error = increments;
+        for preference in &self.preferences {
+            let size = match preference {
+                Preference::Prefers(..) => 1,
+                Preference::Indifferent(..) => 2,
+            };
+            if first.x().slice(s![error..error + size]).sum() > TOLERANCE {
+                inconsistent.push(*preference);
+            }
+            error += size;
+        }
+
+        // the most representative model of the consistent preferences, without errors: the inconsistent ones would
+        // cap the margin to the one used to find them
+        let consistent: Vec<&Preference> = self
+            .preferences
+            .iter()
+            .filter(|p| !inconsistent.contains(p))
+            .collect();
+        let margin = increments;
+        let strict = consistent
+            .iter()
+            .filter(|p| matches!(p, Preference::Prefers(..)))
+            .count();
+        let mut a_ge = Array2::zeros((strict, increments + 1));
+        let mut a_eq = Array2::zeros((consistent.len() - strict, increments + 1));
+        let (mut ge, mut eq) = (0, 0);
+        for preference in consistent {
+            match *preference {
+                Preference::Prefers(a, b) => {
+                    let mut row = a_ge.row_mut(ge);
+                    row.slice_mut(s![..increments])
+                        .assign(&(&coverage.row(a) - &coverage.row(b)));
+                    row[margin] = -1.;
+                    ge += 1;
+                }
+                Preference::Indifferent(a, b) => {
+                    a_eq.row_mut(eq)
+                        .slice_mut(s![..increments])
+                        .assign(&(&coverage.row(a) - &coverage.row(b)));
+                    eq += 1;
+                }
+            }
+        }
+        let mut normalization = Array2::zeros((1, increments + 1));
+        normalization.slice_mut(s![0, ..increments]).fill(1.);
+        let mut margin_objective = Array1::zeros(increments + 1);
+        margin_objective[margin] = 1.;
+        let second = LinearProgram::maximize(margin_objective.clone())
+            .with_greater_equal(a_ge, Array1::zeros(strict))
+            .with_equal(a_eq.clone(), Array1::zeros(a_eq.nrows()))
+            .with_equal(normalization, Array1::ones(1))
+            .with_less_equal(margin_objective.insert_axis(Axis(0)), Array1::ones(1))
+            .solve()?;
+
+        let x = second.x();
+        for (j, marginal) in marginals.iter_mut().enumerate() {
+            let mut value = 0.;
+            for k in 0..self.segments {
+                value += x[j * self.segments + k];
+                marginal.values[k + 1] = value;
+            }
+        }
+        let values = coverage.dot(&x.slice(s![..increments]));
+        let smallest = self
+            .preferences
+            .iter()
+            .filter(|p| !inconsistent.contains(p))
+            .filter_map(|p| match *p {
+                Preference::Prefers(a, b) => Some(values[a] - values[b]),
+                Preference::Indifferent(..) => None,
+            })
+            .fold(f64::INFINITY, f64::min);
+        Ok(OrdinalRegressionResult {
+            model: ValueModel { marginals },
+            inconsistent,
+            margin: if smallest.is_finite() { smallest } else { 0. },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![1., 2., 3., 4.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![1., 3., 4., 2.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_learn_weights() {
+        // the past decisions followed the quality, whatever the cost
+        let result = OrdinalRegression::new()
+            .with_segments(1)
+            .with_ranking(&[2, 1, 0])
+            .learn(&create_test_problem())
+            .unwrap();
+        assert!(result.is_consistent());
+        let weights = result.model().weights();
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("cost").unwrap()).unwrap(),
+            0.,
+            epsilon = 1e-9
+        );
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("quality").unwrap()).unwrap(),
+            1.,
+            epsilon = 1e-9
+        );
+        assert_ulps_eq!(result.margin(), 1. / 3., epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_learn_marginal_values() {
+        let result = OrdinalRegression::new()
+            .with_choice(0, &[1, 2, 3])
+            .learn(&create_test_problem())
+            .unwrap();
+        assert!(result.is_consistent());
+        let model = result.model();
+        let cost = &model.marginals()[0];
+        assert_eq!(cost.name(), &Name::try_from("cost").unwrap());
+        assert_ulps_eq!(cost.breakpoints(), array![4., 2.5, 1.]);
+        assert_ulps_eq!(cost.values()[0], 0.);
+        // the marginal values are non-decreasing towards the best value
+        for marginal in model.marginals() {
+            let values = marginal.values();
+            assert!(values.windows(2).into_iter().all(|w| w[1] >= w[0] - 1e-12));
+        }
+        let values = model.evaluate(&create_test_problem()).unwrap();
+        for other in 1..4 {
+            assert!(values[0] > values[other]);
+        }
+    }
+
+    #[test]
+    fn test_apply_to_new_problem() {
+        let result = OrdinalRegression::new()
+            .with_segments(1)
+            .with_ranking(&[2, 1, 0])
+            .learn(&create_test_problem())
+            .unwrap();
+        // the values beyond the reference range are clamped
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![0., 10., 2.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![2.5, 5., 1.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        let values = result.model().evaluate(&p).unwrap();
+        assert_ulps_eq!(values, array![0.5, 1., 0.], epsilon = 1e-9);
+        assert_eq!(result.model().rank(&p).unwrap().order(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn test_indifference() {
+        let result = OrdinalRegression::new()
+            .with_segments(1)
+            .with_indifference(0, 3)
+            .learn(&create_test_problem())
+            .unwrap();
+        assert!(result.is_consistent());
+        let values = result.model().evaluate(&create_test_problem()).unwrap();
+        assert_ulps_eq!(values[0], values[3], epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_inconsistent_preferences() {
+        // a cycle: no model can satisfy all the preferences
+        let result = OrdinalRegression::new()
+            .with_ranking(&[2, 1, 0])
+            .with_preference(0, 2)
+            .learn(&create_test_problem())
+            .unwrap();
+        assert!(!result.is_consistent());
+        assert!(result.inconsistent().len() < 3);
+    }
+
+    #[test]
+    fn test_margin_with_inconsistent_preference() {
+        // no model prefers an alternative to itself: the other preferences keep their margin
+        let result = OrdinalRegression::new()
+            .with_segments(1)
+            .with_ranking(&[2, 1, 0])
+            .with_preference(3, 3)
+            .learn(&create_test_problem())
+            .unwrap();
+        assert_eq!(result.inconsistent(), &[Preference::Prefers(3, 3)]);
+        assert_ulps_eq!(result.margin(), 1. / 3., epsilon = 1e-9);
+        let weights = result.model().weights();
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("quality").unwrap()).unwrap(),
+            1.,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_marginal_value() {
+        let marginal = MarginalValue {
+            name: Name::try_from("x").unwrap(),
+            worst: 10.,
+            best: 0.,
+            values: array![0., 0.4, 0.5],
+        };
+        assert_ulps_eq!(marginal.value(10.), 0.);
+        assert_ulps_eq!(marginal.value(7.5), 0.2);
+        assert_ulps_eq!(marginal.value(5.), 0.4);
+        assert_ulps_eq!(marginal.value(-1.), 0.5);
+        assert_ulps_eq!(marginal.weight(), 0.5);
+    }
+
+    #[test]
+    fn learn_no_preferences_failure() {
+        let err = OrdinalRegression::new()
+            .learn(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, OrdinalRegressionError::NoPreferences);
+    }
+
+    #[test]
+    fn learn_unknown_alternative_failure() {
+        let err = OrdinalRegression::new()
+            .with_preference(0, 4)
+            .learn(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, OrdinalRegressionError::UnknownAlternative(4));
+    }
+
+    #[test]
+    fn learn_invalid_segments_failure() {
+        let err = OrdinalRegression::new()
+            .with_segments(0)
+            .with_preference(0, 1)
+            .learn(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, OrdinalRegressionError::InvalidSegments);
+    }
+
+    #[test]
+    fn evaluate_variables_mismatch_failure() {
+        let result = OrdinalRegression::new()
+            .with_preference(0, 1)
+            .learn(&create_test_problem())
+            .unwrap();
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("cost").unwrap(),
+            Values::try_from(array![1., 2.]).unwrap(),
+        ))])
+        .unwrap();
+        assert_eq!(
+            result.model().evaluate(&p).unwrap_err(),
+            OrdinalRegressionError::VariablesMismatch
+        );
+    }
+}
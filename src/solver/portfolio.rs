@@ -0,0 +1,538 @@
+//! This module selects a portfolio of alternatives, rather than the single best one.
+//!
+//! The value of each alternative is its closeness to the ideal in the problem, from 0 to 1. The portfolio maximizes the
+//! sum of the values, under a capacity for each resource: a variable of the problem, such as cost or headcount, that
+//! each selected alternative consumes. Alternatives can be required, mutually exclusive or depend on other
+//! alternatives. Up to `PORTFOLIO_EXACT_LIMIT` alternatives the selection is exact (branch and bound), beyond that it
+//! is greedy by value per resource used.
+use super::problem::{Problem, ProblemError};
+use super::vector::weighted_closeness_vectors;
+use super::weights::Weights;
+use crate::variables::Name;
+use ndarray::{Array1, Array2, ArrayView1};
+
+/// Above this number of alternatives, the selection uses a greedy heuristic instead of the exact solution.
+pub const PORTFOLIO_EXACT_LIMIT: usize = 24;
+
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    weights: Option<Weights>,
+    capacities: Vec<(Name, f64)>,
+    required: Vec<usize>,
+    exclusive: Vec<Vec<usize>>,
+    dependencies: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PortfolioError {
+    #[error("The resource '{0}' is not a variable of the problem")]
+    UnknownResource(String),
+    #[error("The values of the resource '{0}' must be non-negative")]
+    NegativeResource(String),
+    #[error("The capacity of the resource '{0}' must be finite and non-negative")]
+    InvalidCapacity(String),
+    #[error("The alternative {0} is not an alternative of the problem")]
+    UnknownAlternative(usize),
+    #[error("No portfolio satisfies all the capacities and constraints")]
+    Infeasible,
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct PortfolioSelection {
+    values: Array1<f64>,
+    selected: Vec<usize>,
+    resources: Vec<Name>,
+    usage: Array1<f64>,
+    exact: bool,
+}
+
+impl PortfolioSelection {
+    /// The value of each alternative, from 0 to 1 (the ideal).
+    pub fn values(&self) -> ArrayView1<'_, f64> {
+        self.values.view()
+    }
+
+    /// The selected alternatives, in the order of the alternatives.
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    pub fn is_selected(&self, alternative: usize) -> bool {
+        self.selected.contains(&alternative)
+    }
+
+    /// The sum of the values of the selected alternatives.
+    pub fn value(&self) -> f64 {
+        self.selected.iter().map(|&a| self.values[a]).sum()
+    }
+
+    /// The names of the resources, in the same order of the usage.
+    pub fn resources(&self) -> &[Name] {
+        &self.resources
+    }
+
+    /// The amount of each resource used by the selected alternatives.
+    pub fn usage(&self) -> ArrayView1<'_, f64> {
+        self.usage.view()
+    }
+
+    /// Whether the selection is optimal, or the result of the heuristic.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+/// The selection problem: values, resource usage (one row for each alternative) and constraints.
+struct Selection<'a> {
+    values: &'a Array1<f64>,
+    usage: &'a Array2<f64>,
+    capacities: &'a Array1<f64>,
+    required: &'a [usize],
+    exclusive: &'a [Vec<usize>],
+    dependencies: &'a [(usize, usize)],
+}
+
+impl Selection<'_> {
+    fn fits(&self, used: &Array1<f64>) -> bool {
+        used.iter().zip(self.capacities).all(|(u, c)| u <= c)
+    }
+
+    fn respects_exclusive(&self, chosen: &[bool]) -> bool {
+        self.exclusive
+            .iter()
+            .all(|group| group.iter().filter(|&&a| chosen[a]).count() <= 1)
+    }
+
+    fn respects_dependencies(&self, chosen: &[bool]) -> bool {
+        self.dependencies
+            .iter()
+            .all(|&(a, requires)| !chosen[a] || chosen[requires])
+    }
+
+    /// Branch and bound on the alternatives, from the most valuable.
+    fn exact(&self) -> Option<Vec<bool>> {
+        let size = self.values.len();
+        let mut order: Vec<usize> = (0..size).collect();
+        order.sort_by(|&a, &b| self.values[b].total_cmp(&self.values[a]));
+        // the best value that the remaining alternatives can add
+        let mut remaining = vec![0.; size + 1];
+        for k in (0..size).rev() {
+            remaining[k] = remaining[k + 1] + self.values[order[k]];
+        }
+        let mut search = ExactSearch {
+            selection: self,
+            order,
+            remaining,
+            chosen: vec![false; size],
+            best: None,
+        };
+        search.branch(0, 0., Array1::zeros(self.capacities.len()));
+        search.best.map(|(_, chosen)| chosen)
+    }
+
+    /// The alternative with the alternatives it depends on, transitively, that are not chosen yet.
+    fn closure(&self, alternative: usize, chosen: &[bool]) -> Vec<usize> {
+        let mut closure = vec![alternative];
+        let mut k = 0;
+        while k < closure.len() {
+            for &(a, requires) in self.dependencies {
+                if a == closure[k] && !chosen[requires] && !closure.contains(&requires) {
+                    closure.push(requires);
+                }
+            }
+            k += 1;
+        }
+        closure
+    }
+
+    /// Adds the alternatives with their dependencies, when all the capacities and constraints hold.
+    fn try_add(&self, alternatives: &[usize], chosen: &mut [bool], used: &mut Array1<f64>) -> bool {
+        let mut candidate = chosen.to_vec();
+        let mut candidate_used = used.clone();
+        for &a in alternatives {
+            if !candidate[a] {
+                candidate[a] = true;
+                candidate_used += &self.usage.row(a);
+            }
+        }
+        if !(self.fits(&candidate_used) && self.respects_exclusive(&candidate)) {
+            return false;
+        }
+        chosen.copy_from_slice(&candidate);
+        *used = candidate_used;
+        true
+    }
+
+    /// Greedy on the value per share of the capacities used, starting from the required alternatives.
+    fn greedy(&self) -> Option<Vec<bool>> {
+        let size = self.values.len();
+        let mut chosen = vec![false; size];
+        let mut used = Array1::zeros(self.capacities.len());
+        for &a in self.required {
+            let closure = self.closure(a, &chosen);
+            if !self.try_add(&closure, &mut chosen, &mut used) {
+                return None;
+            }
+        }
+        let share = |a: usize| -> f64 {
+            self.usage
+                .row(a)
+                .iter()
+                .zip(self.capacities)
+                .map(|(&u, &c)| {
+                    if c > 0. {
+                        u / c
+                    } else if u > 0. {
+                        f64::INFINITY
+                    } else {
+                        0.
+                    }
+                })
+                .sum()
+        };
+        // the alternatives that use a resource without capacity can never fit, the ones that use no resource come
+        // first: all the ratios are positive, or infinite, and never NaN
+        let mut candidates: Vec<(usize, f64)> = (0..size)
+            .filter(|&a| self.values[a] > 0.)
+            .map(|a| (a, share(a)))
+            .filter(|(_, share)| share.is_finite())
+            .map(|(a, share)| {
+                let ratio = if share > 0. {
+                    self.values[a] / share
+                } else {
+                    f64::INFINITY
+                };
+                (a, ratio)
+            })
+            .collect();
+        candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        for (a, _) in candidates {
+            if !chosen[a] {
+                let closure = self.closure(a, &chosen);
+                self.try_add(&closure, &mut chosen, &mut used);
+            }
+        }
+        Some(chosen)
+    }
+}
+
+struct ExactSearch<'a, 'b> {
+    selection: &'a Selection<'b>,
+    order: Vec<usize>,
+    remaining: Vec<f64>,
+    chosen: Vec<bool>,
+    best: Option<(f64, Vec<bool>)>,
+}
+
+impl ExactSearch<'_, '_> {
+    fn branch(&mut self, k: usize, value: f64, used: Array1<f64>) {
+        // no better portfolio down this branch
+        if let Some((best, _)) = &self.best
+            && value + self.remaining[k] <= *best
+        {
+            return;
+        }
+        if k == self.order.len() {
+            if self.selection.respects_dependencies(&self.chosen) {
+                self.best = Some((value, self.chosen.clone()));
+            }
+            return;
+        }
+        let a = self.order[k];
+        let with = &used + &self.selection.usage.row(a);
+        self.chosen[a] = true;
+        if self.selection.fits(&with) && self.selection.respects_exclusive(&self.chosen) {
+            self.branch(k + 1, value + self.selection.values[a], with);
+        }
+        self.chosen[a] = false;
+        if !self.selection.required.contains(&a) {
+            self.branch(k + 1, value, used);
+        }
+    }
+}
+
+impl Portfolio {
+    /// A portfolio with equal weights, no resources and no constraints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The weights of the variables for the value of the alternatives.
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// The selected alternatives cannot use more than the capacity of the resource, a variable of the problem.
+    pub fn with_capacity(mut self, resource: Name, capacity: f64) -> Self {
+        self.capacities.push((resource, capacity));
+        self
+    }
+
+    /// The alternative must be selected.
+    pub fn with_required(mut self, alternative: usize) -> Self {
+        self.required.push(alternative);
+        self
+    }
+
+    /// At most one of the alternatives can be selected.
+    pub fn with_exclusive(mut self, alternatives: Vec<usize>) -> Self {
+        self.exclusive.push(alternatives);
+        self
+    }
+
+    /// The alternative can be selected only together with the required one.
+    pub fn with_dependency(mut self, alternative: usize, requires: usize) -> Self {
+        self.dependencies.push((alternative, requires));
+        self
+    }
+
+    fn check_alternatives(&self, size: usize) -> Result<(), PortfolioError> {
+        let alternatives = self
+            .required
+            .iter()
+            .chain(self.exclusive.iter().flatten())
+            .chain(self.dependencies.iter().flat_map(|(a, b)| [a, b]));
+        for &a in alternatives {
+            if a >= size {
+                return Err(PortfolioError::UnknownAlternative(a));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn select(&self, problem: &Problem) -> Result<PortfolioSelection, PortfolioError> {
+        let size = problem.alternatives();
+        self.check_alternatives(size)?;
+        let weights = problem.get_weights_or_equal(self.weights.as_ref())?;
+        let values =
+            weighted_closeness_vectors(problem.get_problem_matrix().view(), weights.view());
+
+        let mut usage = Array2::zeros((size, self.capacities.len()));
+        let mut capacities = Array1::zeros(self.capacities.len());
+        for (r, (resource, capacity)) in self.capacities.iter().enumerate() {
+            let values = problem
+                .values_of(resource)
+                .ok_or_else(|| PortfolioError::UnknownResource(resource.as_str().to_string()))?
+                .values();
+            if values.iter().any(|&x| x < 0.) {
+                return Err(PortfolioError::NegativeResource(
+                    resource.as_str().to_string(),
+                ));
+            }
+            if !(capacity.is_finite() && *capacity >= 0.) {
+                return Err(PortfolioError::InvalidCapacity(
+                    resource.as_str().to_string(),
+                ));
+            }
+            usage.column_mut(r).assign(&values);
+            capacities[r] = *capacity;
+        }
+
+        let selection = Selection {
+            values: &values,
+            usage: &usage,
+            capacities: &capacities,
+            required: &self.required,
+            exclusive: &self.exclusive,
+            dependencies: &self.dependencies,
+        };
+        let exact = size <= PORTFOLIO_EXACT_LIMIT;
+        let chosen = if exact {
+            selection.exact()
+        } else {
+            selection.greedy()
+        }
+        .ok_or(PortfolioError::Infeasible)?;
+
+        let selected: Vec<usize> = (0..size).filter(|&a| chosen[a]).collect();
+        let used = selected
+            .iter()
+            .fold(Array1::zeros(capacities.len()), |acc, &a| {
+                acc + usage.row(a)
+            });
+        Ok(PortfolioSelection {
+            values,
+            selected,
+            resources: self.capacities.iter().map(|(n, _)| n.clone()).collect(),
+            usage: used,
+            exact,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("benefit").unwrap(),
+                Values::try_from(array![9., 7., 5., 4., 1.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![5., 3., 2., 2., 9.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("headcount").unwrap(),
+                Values::try_from(array![1., 3., 1., 1., 1.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    fn create_test_portfolio() -> Portfolio {
+        // the value is the benefit only: (benefit - 1) / 8
+        let weights = Weights::try_from(vec![
+            (Name::try_from("benefit").unwrap(), 1.),
+            (Name::try_from("cost").unwrap(), 0.),
+            (Name::try_from("headcount").unwrap(), 0.),
+        ])
+        .unwrap();
+        Portfolio::new()
+            .with_weights(weights)
+            .with_capacity(Name::try_from("cost").unwrap(), 6.)
+    }
+
+    #[test]
+    fn test_select() {
+        let result = create_test_portfolio()
+            .select(&create_test_problem())
+            .unwrap();
+        assert_ulps_eq!(result.values(), array![1., 0.75, 0.5, 0.375, 0.]);
+        // two smaller projects are worth more than the biggest one
+        assert_eq!(result.selected(), &[1, 2]);
+        assert_ulps_eq!(result.value(), 1.25);
+        assert_eq!(result.resources(), &[Name::try_from("cost").unwrap()]);
+        assert_ulps_eq!(result.usage(), array![5.]);
+        assert!(result.is_exact());
+        assert!(result.is_selected(2));
+    }
+
+    #[test]
+    fn test_select_multiple_resources() {
+        let result = create_test_portfolio()
+            .with_capacity(Name::try_from("headcount").unwrap(), 3.)
+            .select(&create_test_problem())
+            .unwrap();
+        assert_eq!(result.selected(), &[0]);
+        assert_ulps_eq!(result.usage(), array![5., 1.]);
+    }
+
+    #[test]
+    fn test_select_with_constraints() {
+        let p = create_test_problem();
+        let required = create_test_portfolio().with_required(3).select(&p).unwrap();
+        assert_eq!(required.selected(), &[1, 3]);
+
+        let exclusive = create_test_portfolio()
+            .with_exclusive(vec![1, 2])
+            .select(&p)
+            .unwrap();
+        assert_eq!(exclusive.selected(), &[1, 3]);
+
+        // the second project needs the first one, and both do not fit
+        let dependency = create_test_portfolio()
+            .with_dependency(1, 0)
+            .select(&p)
+            .unwrap();
+        assert_eq!(dependency.selected(), &[0]);
+    }
+
+    #[test]
+    fn test_greedy() {
+        let values = array![1., 0.75, 0.5, 0.375, 0.];
+        let usage = array![[5.], [3.], [2.], [2.], [9.]];
+        let capacities = array![6.];
+        let selection = Selection {
+            values: &values,
+            usage: &usage,
+            capacities: &capacities,
+            required: &[],
+            exclusive: &[],
+            dependencies: &[(2, 3)],
+        };
+        let best = vec![false, true, false, true, false];
+        assert_eq!(selection.exact().unwrap(), best);
+        // the third project brings the fourth one, and both do not fit with the second one
+        assert_eq!(selection.greedy().unwrap(), best);
+    }
+
+    #[test]
+    fn test_greedy_zero_capacity() {
+        // nothing fits in the second resource: the alternatives that use it are never chosen
+        let values = array![1., 0., 0.5, 0.25, 0.1];
+        let usage = array![[5., 1.], [0., 1.], [2., 0.], [0., 0.], [1., 0.]];
+        let capacities = array![6., 0.];
+        let selection = Selection {
+            values: &values,
+            usage: &usage,
+            capacities: &capacities,
+            required: &[],
+            exclusive: &[],
+            dependencies: &[],
+        };
+        let best = vec![false, false, true, true, true];
+        assert_eq!(selection.exact().unwrap(), best);
+        assert_eq!(selection.greedy().unwrap(), best);
+    }
+
+    #[test]
+    fn select_infeasible_failure() {
+        let err = create_test_portfolio()
+            .with_required(0)
+            .with_required(4)
+            .select(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, PortfolioError::Infeasible);
+    }
+
+    #[test]
+    fn select_unknown_resource_failure() {
+        let err = Portfolio::new()
+            .with_capacity(Name::try_from("budget").unwrap(), 1.)
+            .select(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, PortfolioError::UnknownResource("budget".to_string()));
+    }
+
+    #[test]
+    fn select_unknown_alternative_failure() {
+        let err = Portfolio::new()
+            .with_dependency(0, 5)
+            .select(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, PortfolioError::UnknownAlternative(5));
+    }
+
+    #[test]
+    fn select_invalid_capacity_failure() {
+        let err = Portfolio::new()
+            .with_capacity(Name::try_from("cost").unwrap(), -1.)
+            .select(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, PortfolioError::InvalidCapacity("cost".to_string()));
+    }
+
+    #[test]
+    fn select_negative_resource_failure() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("cost").unwrap(),
+            Values::try_from(array![1., -1.]).unwrap(),
+        ))])
+        .unwrap();
+        let err = Portfolio::new()
+            .with_capacity(Name::try_from("cost").unwrap(), 1.)
+            .select(&p)
+            .unwrap_err();
+        assert_eq!(err, PortfolioError::NegativeResource("cost".to_string()));
+    }
+}
@@ -1,10 +1,12 @@
 //! This module define a problem as a matrix of variables
-use super::vector::index_of_best_vector;
-use crate::variables::{Name, VariableProperties};
+use super::ranking::Ranking;
+use super::vector::{index_of_best_vector, l2_norm_vectors, weighted_l2_norm_vectors};
+use super::weights::Weights;
+use crate::variables::{Name, Values, VariableProperties, halve_huge_range};
 use ndarray::{Array1, Array2, ArrayView1, Axis, stack};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-#[derive(Debug, thiserror::Error, PartialEq)]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
 pub enum ProblemError {
     #[error("The problem cannot be empty")]
     Empty,
@@ -12,6 +14,103 @@ pub enum ProblemError {
     VariableSizeMismatch,
     #[error("Each variable must have an unique name. You cannot redefine existing variables")]
     RedefinitionVariable,
+    #[error("The weights must be defined for all the variables of the problem, and only for them")]
+    WeightsMismatch,
+    #[error("The variable '{0}' cannot be rebuilt with other values")]
+    UnsupportedVariable(String),
+}
+
+/// The variables by name, checked with the rules of every problem: not empty, unique names and the same number of
+/// values. `describe` returns the name and the number of values of a variable.
+pub(super) fn define_variables<V: ?Sized>(
+    variables: Vec<Box<V>>,
+    describe: impl Fn(&V) -> (&Name, usize),
+) -> Result<BTreeMap<Name, Box<V>>, ProblemError> {
+    // an empty problem is not allowed
+    if variables.is_empty() {
+        return Err(ProblemError::Empty);
+    }
+
+    let mut defined = BTreeMap::new();
+    let mut known_size: Option<usize> = Option::None;
+
+    for variable in variables {
+        let (name, current_size) = describe(variable.as_ref());
+        // variable names must be unique
+        if defined.contains_key(name) {
+            return Err(ProblemError::RedefinitionVariable);
+        }
+
+        // all variables must have the same number of values
+        match known_size {
+            None => known_size = Some(current_size),
+            Some(size) if size != current_size => return Err(ProblemError::VariableSizeMismatch),
+            Some(_) => {}
+        }
+        defined.insert(name.clone(), variable);
+    }
+    Ok(defined)
+}
+
+/// Why an entry given by variable (an aspiration, a rating...) is rejected, with the name of the variable.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum EntryError {
+    Unknown(String),
+    Invalid(String),
+    Redefinition(String),
+    Missing(String),
+}
+
+/// The entries by variable: each one must be for one of `names`, be valid and be the only one of its variable. When
+/// `complete`, each variable must have an entry.
+pub(super) fn entries_by_name<'n, 'e, T>(
+    names: impl IntoIterator<Item = &'n Name>,
+    entries: impl IntoIterator<Item = (&'e Name, T)>,
+    complete: bool,
+    is_valid: impl Fn(&T) -> bool,
+) -> Result<BTreeMap<Name, T>, EntryError> {
+    let names: BTreeSet<&Name> = names.into_iter().collect();
+    let mut defined = BTreeMap::new();
+    for (name, entry) in entries {
+        let describe = || name.as_str().to_string();
+        if !names.contains(name) {
+            return Err(EntryError::Unknown(describe()));
+        }
+        if !is_valid(&entry) {
+            return Err(EntryError::Invalid(describe()));
+        }
+        if defined.insert(name.clone(), entry).is_some() {
+            return Err(EntryError::Redefinition(describe()));
+        }
+    }
+    if complete && let Some(name) = names.into_iter().find(|n| !defined.contains_key(*n)) {
+        return Err(EntryError::Missing(name.as_str().to_string()));
+    }
+    Ok(defined)
+}
+
+/// The worst and the best bounds of the rescaling of a variable, in its direction: the values rescaled to 1 and to 0.
+pub(super) fn worst_and_best(variable: &dyn VariableProperties) -> (f64, f64) {
+    let (lower, upper) = variable.bounds();
+    if variable.higher_is_better() {
+        (lower, upper)
+    } else {
+        (upper, lower)
+    }
+}
+
+/// The distance from `from` to `to` in the units of the rescaled values of a variable: divided by the range of its
+/// rescaling, with both halved when the range overflows, as the rescaling does. A constant variable has no range: the
+/// distance stays in raw units.
+pub(super) fn rescaled_distance(variable: &dyn VariableProperties) -> impl Fn(f64, f64) -> f64 {
+    let (worst, best) = worst_and_best(variable);
+    let (factor, worst, best) = halve_huge_range(worst, best);
+    let range = if worst != best {
+        (worst - best).abs()
+    } else {
+        1.
+    };
+    move |from, to| (to * factor - from * factor) / range
 }
 
 #[derive(Debug)]
@@ -27,38 +126,86 @@ impl Problem {
     }
 
     pub fn define(variables: Vec<Box<dyn VariableProperties>>) -> Result<Self, ProblemError> {
-        // an empty problem is not allowed
-        if variables.is_empty() {
-            return Err(ProblemError::Empty);
-        }
+        let variables = define_variables(variables, |v| (v.name(), v.values().values().len()))?;
+        Ok(Self { variables })
+    }
 
-        let mut problem = Problem::new();
-        let mut known_size: Option<usize> = Option::None;
+    fn add_variable(&mut self, variable: Box<dyn VariableProperties>) -> usize {
+        self.variables.insert(variable.name().clone(), variable);
+        self.variables.len()
+    }
 
-        for variable in variables {
-            // variable names must be unique
-            if problem.variables.contains_key(variable.name()) {
-                return Err(ProblemError::RedefinitionVariable);
-            }
+    /// The names of the variables, in the same order of the columns of the problem matrix.
+    pub fn names(&self) -> impl Iterator<Item = &Name> {
+        self.variables.keys()
+    }
 
-            // all variables must have the same number of values
-            let current_size = variable.values().values().len();
-            if known_size.is_none() {
-                known_size = Some(current_size)
-            } else if known_size.unwrap() != current_size {
-                return Err(ProblemError::VariableSizeMismatch);
-            }
-            problem.add_variable(variable);
+    /// The number of alternatives, that is the number of values of each variable.
+    pub fn alternatives(&self) -> usize {
+        self.variables
+            .values()
+            .next()
+            .unwrap()
+            .values()
+            .values()
+            .len()
+    }
+
+    /// The variables, in the same order of the columns of the problem matrix.
+    pub(super) fn variables(&self) -> impl Iterator<Item = &dyn VariableProperties> {
+        self.variables.values().map(|v| v.as_ref())
+    }
+
+    pub(super) fn values_of(&self, name: &Name) -> Option<&Values> {
+        self.variables.get(name).map(|v| v.values())
+    }
+
+    /// A new problem with the same variables, each one with the values returned by `f`. The variables that cannot be
+    /// rebuilt with other values are rejected.
+    pub(super) fn map_values(
+        &self,
+        f: impl Fn(&dyn VariableProperties) -> Values,
+    ) -> Result<Problem, ProblemError> {
+        // NOTE: the caller guarantees that all the new values have the same size
+        let mut problem = Problem::new();
+        for variable in self.variables.values() {
+            let Some(mapped) = variable.with_values(f(variable.as_ref())) else {
+                return Err(ProblemError::UnsupportedVariable(
+                    variable.name().as_str().to_string(),
+                ));
+            };
+            problem.add_variable(mapped);
         }
         Ok(problem)
     }
 
-    fn add_variable(&mut self, variable: Box<dyn VariableProperties>) -> usize {
-        self.variables.insert(variable.name().clone(), variable);
-        self.variables.len()
+    pub(super) fn get_weights_vector(
+        &self,
+        weights: &Weights,
+    ) -> Result<Array1<f64>, ProblemError> {
+        if weights.len() != self.variables.len() {
+            return Err(ProblemError::WeightsMismatch);
+        }
+        self.names()
+            .map(|name| weights.get(name).ok_or(ProblemError::WeightsMismatch))
+            .collect()
     }
 
-    fn get_problem_matrix(&self) -> Array2<f64> {
+    /// The weights vector, or equal weights when there are no weights.
+    pub(super) fn get_weights_or_equal(
+        &self,
+        weights: Option<&Weights>,
+    ) -> Result<Array1<f64>, ProblemError> {
+        match weights {
+            Some(weights) => self.get_weights_vector(weights),
+            None => {
+                let size = self.variables.len();
+                Ok(Array1::from_elem(size, 1. / size as f64))
+            }
+        }
+    }
+
+    pub(super) fn get_problem_matrix(&self) -> Array2<f64> {
         let rows: Vec<Array1<f64>> = self
             .variables
             .values()
@@ -73,12 +220,28 @@ impl Problem {
         let matrix = self.get_problem_matrix();
         index_of_best_vector(matrix.view())
     }
+
+    /// Ranks all the alternatives by their distance from the ideal (zero) vector.
+    pub fn rank(&self) -> Ranking {
+        let matrix = self.get_problem_matrix();
+        Ranking::ascending(l2_norm_vectors(matrix.view()))
+    }
+
+    /// Ranks all the alternatives by their weighted distance from the ideal (zero) vector.
+    pub fn rank_weighted(&self, weights: &Weights) -> Result<Ranking, ProblemError> {
+        let weights = self.get_weights_vector(weights)?;
+        let matrix = self.get_problem_matrix();
+        Ok(Ranking::ascending(weighted_l2_norm_vectors(
+            matrix.view(),
+            weights.view(),
+        )))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::variables::{Name, Values, VariableAutoscale, VariableInvertedAutoscale};
+    use crate::variables::{VariableAutoscale, VariableInvertedAutoscale};
     use ndarray::array;
     use std::convert::TryFrom;
 
@@ -116,6 +279,58 @@ mod tests {
         assert_eq!(p.solve(), 1,)
     }
 
+    #[test]
+    fn test_problem_is_ranked() {
+        let p = create_test_problem();
+        assert_eq!(p.alternatives(), 3);
+        assert_eq!(p.rank().order(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn test_problem_is_ranked_with_weights() {
+        let p = create_test_problem();
+        let weights = Weights::try_from(vec![
+            (Name::try_from("x").unwrap(), 1.),
+            (Name::try_from("y").unwrap(), 4.),
+        ])
+        .unwrap();
+        assert_eq!(p.rank_weighted(&weights).unwrap().order(), &[2, 1, 0]);
+    }
+
+    #[test]
+    fn rank_problem_with_weights_mismatch_failure() {
+        let p = create_test_problem();
+        let weights = Weights::try_from(vec![
+            (Name::try_from("x").unwrap(), 1.),
+            (Name::try_from("z").unwrap(), 3.),
+        ])
+        .unwrap();
+        assert_eq!(
+            p.rank_weighted(&weights).unwrap_err(),
+            ProblemError::WeightsMismatch
+        );
+        let weights = Weights::try_from(vec![(Name::try_from("x").unwrap(), 1.)]).unwrap();
+        assert_eq!(
+            p.rank_weighted(&weights).unwrap_err(),
+            ProblemError::WeightsMismatch
+        );
+    }
+
+    #[test]
+    fn test_map_values() {
+        let p = create_test_problem();
+        let reversed = p
+            .map_values(|v| {
+                Values::try_from(v.values().values().slice(ndarray::s![..;-1]).to_owned()).unwrap()
+            })
+            .unwrap();
+        assert_eq!(reversed.rank().order(), &[1, 0, 2]);
+        assert_eq!(
+            reversed.get_problem_matrix(),
+            array![[1., 0.], [0.5, 0.5], [0., 1.]]
+        );
+    }
+
     #[test]
     fn solve_problem_with_single_value() {
         let p = Problem::define(vec![Box::new(VariableAutoscale::new(
@@ -182,4 +397,39 @@ mod tests {
         .unwrap_err();
         assert_eq!(err, ProblemError::VariableSizeMismatch)
     }
+
+    #[test]
+    fn test_entries_by_name() {
+        let p = create_test_problem();
+        let (x, y, z) = (
+            Name::try_from("x").unwrap(),
+            Name::try_from("y").unwrap(),
+            Name::try_from("z").unwrap(),
+        );
+        let entries = entries_by_name(p.names(), [(&y, 2.), (&x, 1.)], true, |e| *e > 0.);
+        assert_eq!(
+            entries.unwrap().into_iter().collect::<Vec<_>>(),
+            vec![(x.clone(), 1.), (y.clone(), 2.)]
+        );
+        // some entries can be left out when they are not all required
+        assert!(entries_by_name(p.names(), [(&x, 1.)], false, |e| *e > 0.).is_ok());
+        let check =
+            |entries: Vec<(&Name, f64)>| entries_by_name(p.names(), entries, true, |e| *e > 0.);
+        assert_eq!(
+            check(vec![(&z, 1.)]).unwrap_err(),
+            EntryError::Unknown("z".to_string())
+        );
+        assert_eq!(
+            check(vec![(&x, 0.)]).unwrap_err(),
+            EntryError::Invalid("x".to_string())
+        );
+        assert_eq!(
+            check(vec![(&x, 1.), (&x, 1.)]).unwrap_err(),
+            EntryError::Redefinition("x".to_string())
+        );
+        assert_eq!(
+            check(vec![(&x, 1.)]).unwrap_err(),
+            EntryError::Missing("y".to_string())
+        );
+    }
 }
@@ -0,0 +1,163 @@
+//! This module defines the ranking of the alternatives of a problem.
+use ndarray::{Array1, ArrayView1};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranking {
+    scores: Array1<f64>,
+    order: Vec<usize>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RankingError {
+    #[error("The ranking cannot be empty")]
+    Empty,
+    #[error("The ranking must list each alternative exactly once")]
+    NotAPermutation,
+    #[error("The best alternative must be one of the alternatives")]
+    UnknownAlternative,
+}
+
+impl Ranking {
+    /// Ranks the alternatives so that the lower the score, the better (e.g. distances).
+    pub(crate) fn ascending(scores: Array1<f64>) -> Self {
+        // The sort is stable: ties keep the alternatives order.
+        let mut order: Vec<usize> = (0..scores.len()).collect();
+        order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+        Self { scores, order }
+    }
+
+    /// Ranks the alternatives so that the higher the score, the better (e.g. closeness coefficients).
+    pub(crate) fn descending(scores: Array1<f64>) -> Self {
+        // The sort is stable: ties keep the alternatives order.
+        let mut order: Vec<usize> = (0..scores.len()).collect();
+        order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        Self { scores, order }
+    }
+
+    /// A ranking from the alternatives' indexes, from the best to the worst. The scores are the positions.
+    pub fn from_order(order: Vec<usize>) -> Result<Self, RankingError> {
+        if order.is_empty() {
+            return Err(RankingError::Empty);
+        }
+        let mut scores = Array1::from_elem(order.len(), f64::NAN);
+        for (position, &alternative) in order.iter().enumerate() {
+            match scores.get_mut(alternative) {
+                Some(score) if score.is_nan() => *score = position as f64,
+                _ => return Err(RankingError::NotAPermutation),
+            }
+        }
+        Ok(Ranking::ascending(scores))
+    }
+
+    /// A ranking that only knows the best alternative, as returned by `Problem::solve`: all the others are tied.
+    pub fn from_best(best: usize, alternatives: usize) -> Result<Self, RankingError> {
+        if best >= alternatives {
+            return Err(RankingError::UnknownAlternative);
+        }
+        let mut scores = Array1::ones(alternatives);
+        scores[best] = 0.;
+        Ok(Ranking::ascending(scores))
+    }
+
+    /// Whether the alternative `a` is strictly better than `b`. Alternatives with the same score are tied.
+    pub fn prefers(&self, a: usize, b: usize) -> bool {
+        // the order is consistent with the scores, whatever the direction of the scores
+        self.scores[a] != self.scores[b] && self.positions()[a] < self.positions()[b]
+    }
+
+    /// The score of each alternative, as computed by the solver.
+    pub fn scores(&self) -> ArrayView1<'_, f64> {
+        self.scores.view()
+    }
+
+    /// Alternatives' indexes, from the best to the worst.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn best(&self) -> usize {
+        self.order[0]
+    }
+
+    /// The position of each alternative in the ranking, 0 being the best.
+    pub fn positions(&self) -> Vec<usize> {
+        let mut positions = vec![0; self.order.len()];
+        for (position, &alternative) in self.order.iter().enumerate() {
+            positions[alternative] = position;
+        }
+        positions
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_ascending() {
+        let r = Ranking::ascending(array![0.3, 0.1, 0.2]);
+        assert_eq!(r.order(), &[1, 2, 0]);
+        assert_eq!(r.positions(), vec![2, 0, 1]);
+        assert_eq!(r.best(), 1);
+    }
+
+    #[test]
+    fn test_descending() {
+        let r = Ranking::descending(array![0.3, 0.1, 0.2]);
+        assert_eq!(r.order(), &[0, 2, 1]);
+        assert_eq!(r.positions(), vec![0, 2, 1]);
+        assert_eq!(r.best(), 0);
+        assert_eq!(r.scores(), array![0.3, 0.1, 0.2]);
+    }
+
+    #[test]
+    fn test_from_order() {
+        let r = Ranking::from_order(vec![2, 0, 1]).unwrap();
+        assert_eq!(r.order(), &[2, 0, 1]);
+        assert_eq!(r.scores(), array![1., 2., 0.]);
+        assert!(r.prefers(2, 1));
+        assert!(!r.prefers(1, 2));
+    }
+
+    #[test]
+    fn test_from_order_failure() {
+        assert_eq!(Ranking::from_order(vec![]), Err(RankingError::Empty));
+        assert_eq!(
+            Ranking::from_order(vec![0, 0]),
+            Err(RankingError::NotAPermutation)
+        );
+        assert_eq!(
+            Ranking::from_order(vec![0, 2]),
+            Err(RankingError::NotAPermutation)
+        );
+    }
+
+    #[test]
+    fn test_from_best() {
+        let r = Ranking::from_best(1, 3).unwrap();
+        assert_eq!(r.best(), 1);
+        assert!(r.prefers(1, 0));
+        assert!(!r.prefers(0, 2));
+        assert!(!r.prefers(2, 0));
+        assert_eq!(
+            Ranking::from_best(3, 3),
+            Err(RankingError::UnknownAlternative)
+        );
+    }
+
+    #[test]
+    fn test_ties_keep_order() {
+        let r = Ranking::descending(array![0., 1., 0., 1.]);
+        assert_eq!(r.order(), &[1, 3, 0, 2]);
+        assert_eq!(r.len(), 4);
+    }
+}
@@ -0,0 +1,340 @@
+//! This module ranks problems with the reference point method, and the achievement scalarizing function (ASF) of
+//! Wierzbicki.
+//!
+//! The decision maker sets an aspiration for each variable, in the units of its values. The aspirations are rescaled
+//! like the values, so that the reference point lives in the rescaled matrix, where 0 is the best. The achievement of
+//! an alternative is the biggest weighted shortfall from the reference point, plus a small augmentation with the sum of
+//! the weighted shortfalls, so that a dominated alternative never ties with the one that dominates it. With the
+//! reference point in the ideal (the zero vector), the method is the weighted Tchebycheff distance from the ideal.
+use super::problem::{
+    EntryError, Problem, ProblemError, entries_by_name, rescaled_distance, worst_and_best,
+};
+use super::ranking::Ranking;
+use super::weights::Weights;
+use crate::variables::Name;
+use ndarray::{Array1, ArrayView1};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct ReferencePoint {
+    aspirations: Vec<(Name, f64)>,
+    weights: Option<Weights>,
+    augmentation: f64,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ReferencePointError {
+    #[error("The aspiration of '{0}' is missing: each variable must have one")]
+    MissingAspiration(String),
+    #[error("The aspiration '{0}' is not a variable of the problem")]
+    UnknownVariable(String),
+    #[error("Each variable can have only one aspiration: '{0}' has more than one")]
+    RedefinitionAspiration(String),
+    #[error("The aspiration of '{0}' must be finite")]
+    NotFiniteAspiration(String),
+    #[error("The augmentation must be finite and non-negative")]
+    InvalidAugmentation,
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+impl From<EntryError> for ReferencePointError {
+    fn from(error: EntryError) -> Self {
+        match error {
+            EntryError::Unknown(name) => ReferencePointError::UnknownVariable(name),
+            EntryError::Invalid(name) => ReferencePointError::NotFiniteAspiration(name),
+            EntryError::Redefinition(name) => ReferencePointError::RedefinitionAspiration(name),
+            EntryError::Missing(name) => ReferencePointError::MissingAspiration(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReferencePointRanking {
+    reference: Array1<f64>,
+    ranking: Ranking,
+}
+
+impl ReferencePointRanking {
+    /// The rescaled reference point, in the same order of the variables of the problem. Negative values are better
+    /// than the best alternative.
+    pub fn reference(&self) -> ArrayView1<'_, f64> {
+        self.reference.view()
+    }
+
+    /// The achievement of each alternative: the lower, the better. Negative achievements exceed all the aspirations.
+    pub fn achievements(&self) -> ArrayView1<'_, f64> {
+        self.ranking.scores()
+    }
+
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+}
+
+impl ReferencePoint {
+    /// A reference point with equal weights and an augmentation of 0.001.
+    pub fn new(aspirations: Vec<(Name, f64)>) -> Self {
+        Self {
+            aspirations,
+            weights: None,
+            augmentation: 1e-3,
+        }
+    }
+
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// The weight of the sum of the shortfalls: the higher, the more the method rewards the average achievement.
+    pub fn with_augmentation(mut self, augmentation: f64) -> Self {
+        self.augmentation = augmentation;
+        self
+    }
+
+    fn get_aspirations(
+        &self,
+        problem: &Problem,
+    ) -> Result<BTreeMap<Name, f64>, ReferencePointError> {
+        let aspirations = self.aspirations.iter().map(|(name, a)| (name, *a));
+        Ok(entries_by_name(problem.names(), aspirations, true, |a| {
+            a.is_finite()
+        })?)
+    }
+
+    /// The aspirations, rescaled like the values of each variable.
+    fn get_reference(&self, problem: &Problem) -> Result<Array1<f64>, ReferencePointError> {
+        let aspirations = self.get_aspirations(problem)?;
+        Ok(problem
+            .variables()
+            .map(|variable| {
+                // a constant variable is rescaled to zeros: the aspiration only moves by its distance from the value
+                let (_, best) = worst_and_best(variable);
+                let distance = rescaled_distance(variable);
+                let aspiration = aspirations[variable.name()];
+                if variable.higher_is_better() {
+                    distance(aspiration, best)
+                } else {
+                    distance(best, aspiration)
+                }
+            })
+            .collect())
+    }
+
+    pub fn rank(&self, problem: &Problem) -> Result<ReferencePointRanking, ReferencePointError> {
+        if !(self.augmentation.is_finite() && self.augmentation >= 0.) {
+            return Err(ReferencePointError::InvalidAugmentation);
+        }
+        let reference = self.get_reference(problem)?;
+        let weights = problem.get_weights_or_equal(self.weights.as_ref())?;
+        let matrix = problem.get_problem_matrix();
+        let achievements = matrix
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let shortfalls = (&row - &reference) * &weights;
+                let max = shortfalls.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                max + self.augmentation * shortfalls.sum()
+            })
+            .collect();
+        Ok(ReferencePointRanking {
+            reference,
+            ranking: Ranking::ascending(achievements),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{
+        RobustScaling, RobustVariableAutoscale, Values, VariableAutoscale,
+        VariableInvertedAutoscale,
+    };
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![10., 20., 30.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![1., 5., 9.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rank() {
+        let p = create_test_problem();
+        let middle = ReferencePoint::new(vec![
+            (Name::try_from("cost").unwrap(), 20.),
+            (Name::try_from("quality").unwrap(), 5.),
+        ])
+        .rank(&p)
+        .unwrap();
+        assert_ulps_eq!(middle.reference(), array![0.5, 0.5]);
+        assert_ulps_eq!(
+            middle.achievements(),
+            array![0.25, 0., 0.25],
+            epsilon = 1e-12
+        );
+        assert_eq!(middle.ranking().best(), 1);
+
+        // the decision maker wants the best quality, whatever the cost
+        let quality = ReferencePoint::new(vec![
+            (Name::try_from("quality").unwrap(), 9.),
+            (Name::try_from("cost").unwrap(), 30.),
+        ])
+        .rank(&p)
+        .unwrap();
+        assert_ulps_eq!(quality.reference(), array![1., 0.]);
+        assert_eq!(quality.ranking().order(), &[2, 1, 0]);
+    }
+
+    #[test]
+    fn test_robust_reference() {
+        // the aspiration is rescaled on the quantile bounds, like the values
+        let p = Problem::define(vec![Box::new(RobustVariableAutoscale::new(
+            Name::try_from("price").unwrap(),
+            Values::try_from(array![1., 2., 3., 4., 100.]).unwrap(),
+            RobustScaling::default(),
+        ))])
+        .unwrap();
+        let result = ReferencePoint::new(vec![(Name::try_from("price").unwrap(), 3.)])
+            .rank(&p)
+            .unwrap();
+        assert_ulps_eq!(result.reference()[0], 1.8 / 79.6, epsilon = 1e-12);
+        assert_ulps_eq!(
+            result.reference()[0],
+            p.get_problem_matrix()[[2, 0]],
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_augmentation() {
+        // the second alternative has the same cost and a better quality than the first one
+        let p = Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![10., 10., 30.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![1., 5., 9.]).unwrap(),
+            )),
+        ])
+        .unwrap();
+        let aspirations = vec![
+            (Name::try_from("cost").unwrap(), 10.),
+            (Name::try_from("quality").unwrap(), 1.),
+        ];
+        let plain = ReferencePoint::new(aspirations.clone())
+            .with_augmentation(0.)
+            .rank(&p)
+            .unwrap();
+        assert_ulps_eq!(plain.achievements()[0], plain.achievements()[1]);
+        let augmented = ReferencePoint::new(aspirations).rank(&p).unwrap();
+        assert_eq!(augmented.ranking().order(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn test_rank_with_weights() {
+        let weights = Weights::try_from(vec![
+            (Name::try_from("cost").unwrap(), 1.),
+            (Name::try_from("quality").unwrap(), 0.),
+        ])
+        .unwrap();
+        let result = ReferencePoint::new(vec![
+            (Name::try_from("cost").unwrap(), 20.),
+            (Name::try_from("quality").unwrap(), 5.),
+        ])
+        .with_weights(weights)
+        .rank(&create_test_problem())
+        .unwrap();
+        assert_eq!(result.ranking().order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_huge_range() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("cost").unwrap(),
+            Values::try_from(array![-f64::MAX, f64::MAX]).unwrap(),
+        ))])
+        .unwrap();
+        let result = ReferencePoint::new(vec![(Name::try_from("cost").unwrap(), 0.)])
+            .rank(&p)
+            .unwrap();
+        assert_ulps_eq!(result.reference(), array![0.5]);
+        assert_ulps_eq!(result.achievements(), array![-0.5005, 0.5005]);
+    }
+
+    #[test]
+    fn rank_missing_aspiration_failure() {
+        let err = ReferencePoint::new(vec![(Name::try_from("cost").unwrap(), 20.)])
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ReferencePointError::MissingAspiration("quality".to_string())
+        );
+    }
+
+    #[test]
+    fn rank_unknown_variable_failure() {
+        let err = ReferencePoint::new(vec![(Name::try_from("speed").unwrap(), 1.)])
+            .rank(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ReferencePointError::UnknownVariable("speed".to_string())
+        );
+    }
+
+    #[test]
+    fn rank_redefinition_aspiration_failure() {
+        let err = ReferencePoint::new(vec![
+            (Name::try_from("cost").unwrap(), 20.),
+            (Name::try_from("cost").unwrap(), 10.),
+        ])
+        .rank(&create_test_problem())
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ReferencePointError::RedefinitionAspiration("cost".to_string())
+        );
+    }
+
+    #[test]
+    fn rank_invalid_augmentation_failure() {
+        let err = ReferencePoint::new(vec![
+            (Name::try_from("cost").unwrap(), 20.),
+            (Name::try_from("quality").unwrap(), 5.),
+        ])
+        .with_augmentation(-1.)
+        .rank(&create_test_problem())
+        .unwrap_err();
+        assert_eq!(err, ReferencePointError::InvalidAugmentation);
+    }
+
+    #[test]
+    fn rank_not_finite_aspiration_failure() {
+        let err = ReferencePoint::new(vec![
+            (Name::try_from("cost").unwrap(), f64::NAN),
+            (Name::try_from("quality").unwrap(), 5.),
+        ])
+        .rank(&create_test_problem())
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ReferencePointError::NotFiniteAspiration("cost".to_string())
+        );
+    }
+}
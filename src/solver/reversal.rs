@@ -0,0 +1,367 @@
+//! This module diagnoses rank reversals: changes of the relative order of two alternatives caused by removing or
+//! adding another, irrelevant, alternative.
+//!
+//! Min-max scaling depends on the set of alternatives, so the ranking of a problem may not be stable. Each test
+//! re-solves a modified problem and compares every pair of the original alternatives with the baseline ranking.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::weights::Weights;
+use crate::variables::{Values, VariableProperties};
+use ndarray::{Array1, Axis, concatenate};
+use ndarray_stats::QuantileExt;
+
+#[derive(Debug, Clone, Default)]
+pub struct RankReversal {
+    weights: Option<Weights>,
+    dominated_copy_gap: Option<f64>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RankReversalError {
+    #[error("The gap of the dominated copies must be finite and positive")]
+    InvalidGap,
+    #[error("The dominated copy overflows on '{0}': the gap is too big for its values")]
+    CopyOverflow(String),
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+/// How the problem was modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modification {
+    /// The alternative was removed.
+    Removed(usize),
+    /// A copy of the alternative, slightly worse on every variable, was added.
+    DominatedCopyAdded(usize),
+}
+
+/// Two alternatives that swapped their relative order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reversal {
+    pub modification: Modification,
+    /// The alternative ranked better in the baseline, and worse after the modification.
+    pub better: usize,
+    /// The alternative ranked worse in the baseline, and better after the modification.
+    pub worse: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RankReversalReport {
+    baseline: Ranking,
+    reversals: Vec<Reversal>,
+}
+
+impl RankReversalReport {
+    /// The ranking of the unmodified problem.
+    pub fn baseline(&self) -> &Ranking {
+        &self.baseline
+    }
+
+    pub fn reversals(&self) -> &[Reversal] {
+        &self.reversals
+    }
+
+    /// Whether no modification reverses the ranking.
+    pub fn is_stable(&self) -> bool {
+        self.reversals.is_empty()
+    }
+
+    /// The modifications that change the best alternative.
+    pub fn best_changes(&self) -> Vec<Modification> {
+        let best = self.baseline.best();
+        let mut changes: Vec<Modification> = self
+            .reversals
+            .iter()
+            .filter(|r| r.better == best)
+            .map(|r| r.modification)
+            .collect();
+        changes.dedup();
+        changes
+    }
+}
+
+impl RankReversal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ranks with `Problem::rank_weighted` instead of `Problem::rank`.
+    pub fn with_weights(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Also tests the addition of a dominated copy of each alternative. The copy is worse than the alternative by `gap`
+    /// times the range of each variable: it changes the scaling only when it falls beyond the worst value. On a
+    /// constant variable the range is 0 and the copy ties with the alternative, so a problem of constant variables
+    /// gets an equal copy, not a dominated one.
+    pub fn with_dominated_copies(mut self, gap: f64) -> Self {
+        self.dominated_copy_gap = Some(gap);
+        self
+    }
+
+    fn rank(&self, problem: &Problem) -> Result<Ranking, ProblemError> {
+        match &self.weights {
+            Some(weights) => problem.rank_weighted(weights),
+            None => Ok(problem.rank()),
+        }
+    }
+
+    /// Compares the baseline with a ranking where `alternatives[i]` is the original index of the i-th alternative.
+    fn find_reversals(
+        baseline: &Ranking,
+        ranking: &Ranking,
+        alternatives: &[usize],
+        modification: Modification,
+    ) -> Vec<Reversal> {
+        // NOTE: ties are not reversals, only strict changes of order are reported
+        let before = baseline.scores();
+        let after = ranking.scores();
+        let mut reversals = Vec::new();
+        for (i, &a) in alternatives.iter().enumerate() {
+            for (j, &b) in alternatives.iter().enumerate() {
+                if before[a] < before[b] && after[i] > after[j] {
+                    reversals.push(Reversal {
+                        modification,
+                        better: a,
+                        worse: b,
+                    });
+                }
+            }
+        }
+        reversals
+    }
+
+    fn removal(problem: &Problem, removed: usize) -> Result<Problem, ProblemError> {
+        problem.map_values(|v| {
+            let kept: Array1<f64> = v
+                .values()
+                .values()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != removed)
+                .map(|(_, &x)| x)
+                .collect();
+            Values::try_from(kept).unwrap()
+        })
+    }
+
+    /// The value of the dominated copy on a variable: it can overflow with huge values or gaps.
+    fn copy_value(variable: &dyn VariableProperties, copied: usize, gap: f64) -> f64 {
+        // the best and worst raw values are the ones rescaled to the min and max
+        let raw = variable.values().values();
+        let rescaled = variable.rescale();
+        let best = raw[rescaled.values().argmin().unwrap()];
+        let worst = raw[rescaled.values().argmax().unwrap()];
+        raw[copied] + gap * (worst - best)
+    }
+
+    fn dominated_copy(
+        problem: &Problem,
+        copied: usize,
+        gap: f64,
+    ) -> Result<Problem, RankReversalError> {
+        if let Some(variable) = problem
+            .variables()
+            .find(|v| !RankReversal::copy_value(*v, copied, gap).is_finite())
+        {
+            return Err(RankReversalError::CopyOverflow(
+                variable.name().as_str().to_string(),
+            ));
+        }
+        Ok(problem.map_values(|v| {
+            let copy = RankReversal::copy_value(v, copied, gap);
+            let raw = v.values().values();
+            Values::try_from(concatenate![Axis(0), raw, Array1::from_elem(1, copy)]).unwrap()
+        })?)
+    }
+
+    pub fn run(&self, problem: &Problem) -> Result<RankReversalReport, RankReversalError> {
+        if let Some(gap) = self.dominated_copy_gap
+            && !(gap.is_finite() && gap > 0.)
+        {
+            return Err(RankReversalError::InvalidGap);
+        }
+
+        let baseline = self.rank(problem)?;
+        let size = problem.alternatives();
+        let mut reversals = Vec::new();
+
+        // a problem cannot be empty, so there is nothing to remove with a single alternative
+        if size > 1 {
+            for removed in 0..size {
+                let ranking = self.rank(&RankReversal::removal(problem, removed)?)?;
+                let alternatives: Vec<usize> = (0..size).filter(|&i| i != removed).collect();
+                reversals.extend(RankReversal::find_reversals(
+                    &baseline,
+                    &ranking,
+                    &alternatives,
+                    Modification::Removed(removed),
+                ));
+            }
+        }
+
+        if let Some(gap) = self.dominated_copy_gap {
+            let alternatives: Vec<usize> = (0..size).collect();
+            for copied in 0..size {
+                let ranking = self.rank(&RankReversal::dominated_copy(problem, copied, gap)?)?;
+                reversals.extend(RankReversal::find_reversals(
+                    &baseline,
+                    &ranking,
+                    &alternatives,
+                    Modification::DominatedCopyAdded(copied),
+                ));
+            }
+        }
+
+        Ok(RankReversalReport {
+            baseline,
+            reversals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Name, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem(x: Array1<f64>, y: Array1<f64>) -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                Values::try_from(x).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("y").unwrap(),
+                Values::try_from(y).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_removal() {
+        let p = create_test_problem(array![1., 2., 3.], array![3., 4., 5.]);
+        let removed = RankReversal::removal(&p, 1).unwrap();
+        assert_eq!(removed.alternatives(), 2);
+        assert_eq!(removed.get_problem_matrix(), array![[0., 1.], [1., 0.]]);
+    }
+
+    #[test]
+    fn test_dominated_copy() {
+        let p = create_test_problem(array![1., 2., 3.], array![3., 4., 5.]);
+        let copy = RankReversal::dominated_copy(&p, 1, 1.).unwrap();
+        assert_eq!(copy.alternatives(), 4);
+        // x goes up (worse) and y goes down (worse) by their ranges
+        let matrix = copy.get_problem_matrix();
+        assert_ulps_eq!(matrix.row(3), array![1., 1.]);
+        assert_ulps_eq!(matrix.row(1), array![1. / 3., 1. / 3.]);
+    }
+
+    #[test]
+    fn test_stable_problem() {
+        let p = create_test_problem(array![1., 2., 3.], array![3., 4., 5.]);
+        let report = RankReversal::new()
+            .with_dominated_copies(0.1)
+            .run(&p)
+            .unwrap();
+        assert_eq!(report.baseline().order(), &[1, 0, 2]);
+        assert!(report.is_stable());
+        assert!(report.best_changes().is_empty());
+    }
+
+    #[test]
+    fn test_reversal_on_removal() {
+        // removing the last alternative shrinks the ranges, and the second alternative becomes the best
+        let p = create_test_problem(array![0., 0., 1., 2.], array![0., 1., 2., 3.]);
+        let report = RankReversal::new().run(&p).unwrap();
+        assert_eq!(report.baseline().best(), 2);
+        assert!(!report.is_stable());
+        assert!(report.reversals().contains(&Reversal {
+            modification: Modification::Removed(3),
+            better: 2,
+            worse: 1,
+        }));
+        assert!(report.best_changes().contains(&Modification::Removed(3)));
+    }
+
+    #[test]
+    fn test_single_alternative() {
+        let p = create_test_problem(array![1.], array![1.]);
+        let report = RankReversal::new()
+            .with_dominated_copies(1.)
+            .run(&p)
+            .unwrap();
+        assert!(report.is_stable());
+    }
+
+    #[test]
+    fn run_invalid_gap_failure() {
+        let p = create_test_problem(array![1.], array![1.]);
+        let err = RankReversal::new()
+            .with_dominated_copies(0.)
+            .run(&p)
+            .unwrap_err();
+        assert_eq!(err, RankReversalError::InvalidGap);
+    }
+
+    /// A variable defined outside of the crate, that cannot be rebuilt with other values.
+    #[derive(Debug)]
+    struct Score(Name, Values);
+
+    impl VariableProperties for Score {
+        fn name(&self) -> &Name {
+            &self.0
+        }
+
+        fn values(&self) -> &Values {
+            &self.1
+        }
+
+        fn rescale(&self) -> Values {
+            self.1.clone()
+        }
+    }
+
+    #[test]
+    fn run_unsupported_variable_failure() {
+        let p = Problem::define(vec![Box::new(Score(
+            Name::try_from("score").unwrap(),
+            Values::try_from(array![0., 1.]).unwrap(),
+        ))])
+        .unwrap();
+        let err = RankReversal::new().run(&p).unwrap_err();
+        assert_eq!(
+            err,
+            RankReversalError::Problem(ProblemError::UnsupportedVariable("score".to_string()))
+        );
+    }
+
+    #[test]
+    fn run_copy_overflow_failure() {
+        let p = create_test_problem(array![0., 1e308], array![0., 1.]);
+        let err = RankReversal::new()
+            .with_dominated_copies(10.)
+            .run(&p)
+            .unwrap_err();
+        assert_eq!(err, RankReversalError::CopyOverflow("x".to_string()));
+    }
+
+    #[test]
+    fn run_weights_mismatch_failure() {
+        let p = create_test_problem(array![1.], array![1.]);
+        let weights = Weights::try_from(vec![(Name::try_from("x").unwrap(), 1.)]).unwrap();
+        let err = RankReversal::new()
+            .with_weights(weights)
+            .run(&p)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            RankReversalError::Problem(ProblemError::WeightsMismatch)
+        );
+    }
+}
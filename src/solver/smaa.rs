@@ -0,0 +1,368 @@
+//! This module implements the Stochastic Multicriteria Acceptability Analysis (SMAA-2).
+//!
+//! When the weights are unknown, they are sampled uniformly from the simplex, optionally constrained by an ordinal
+//! ranking of the variables' importance. Each sample ranks the alternatives with the weighted distance from the
+//! ideal vector, as `Problem::rank_weighted` does. With uncertain values, the confidence factor of an alternative is
+//! the share of realizations of the values for which its central weights still make it the best one.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::vector::weighted_l2_norm_vectors;
+use super::weights::Weights;
+use crate::variables::{Name, UncertainVariable};
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Debug, Clone)]
+pub struct Smaa {
+    samples: usize,
+    seed: u64,
+    weights_order: Vec<Name>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SmaaError {
+    #[error("At least one sample is required")]
+    NoSamples,
+    #[error("The variable '{0}' is not part of the problem")]
+    UnknownVariable(String),
+    #[error("Each variable can appear only once in the weights order")]
+    RedefinitionVariable,
+    #[error("The uncertain variables must have the same alternatives of the analysed problem")]
+    AlternativesMismatch,
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+#[derive(Debug, Clone)]
+pub struct SmaaResult {
+    rank_acceptability: Array2<f64>,
+    central_weights: Vec<Option<Weights>>,
+}
+
+impl SmaaResult {
+    /// The share of weights for which the alternative gets the given rank, 0 being the best.
+    pub fn rank_acceptability(&self, alternative: usize, rank: usize) -> f64 {
+        self.rank_acceptability[[alternative, rank]]
+    }
+
+    /// The rank acceptability indexes: one row for each alternative, one column for each rank.
+    pub fn rank_acceptabilities(&self) -> ArrayView2<'_, f64> {
+        self.rank_acceptability.view()
+    }
+
+    /// The share of weights that makes the alternative the best one.
+    pub fn first_rank_acceptabilities(&self) -> ArrayView1<'_, f64> {
+        self.rank_acceptability.column(0)
+    }
+
+    /// The typical weights that make the alternative the best one. `None` when the alternative is never the best.
+    pub fn central_weights(&self, alternative: usize) -> Option<&Weights> {
+        self.central_weights[alternative].as_ref()
+    }
+}
+
+/// Samples a weights vector uniformly from the simplex. The weights at the `ordered` indexes are sorted in decreasing
+/// order, which keeps the distribution uniform over the constrained region.
+pub(crate) fn sample_weights<R: Rng>(rng: &mut R, size: usize, ordered: &[usize]) -> Array1<f64> {
+    // normalized exponential samples are uniform on the simplex
+    let mut weights: Array1<f64> = (0..size)
+        .map(|_| -(1. - rng.random::<f64>()).ln())
+        .collect();
+    let total = weights.sum();
+    weights /= total;
+
+    let mut constrained: Vec<f64> = ordered.iter().map(|&i| weights[i]).collect();
+    constrained.sort_by(|a, b| b.total_cmp(a));
+    for (&i, w) in ordered.iter().zip(constrained) {
+        weights[i] = w;
+    }
+    weights
+}
+
+impl Smaa {
+    pub fn new(samples: usize, seed: u64) -> Self {
+        Self {
+            samples,
+            seed,
+            weights_order: Vec::new(),
+        }
+    }
+
+    /// Constrains the weights by importance: each variable is at least as important as the following ones. Variables
+    /// not listed are unconstrained.
+    pub fn with_weights_order(mut self, weights_order: Vec<Name>) -> Self {
+        self.weights_order = weights_order;
+        self
+    }
+
+    fn get_ordered_indexes(&self, problem: &Problem) -> Result<Vec<usize>, SmaaError> {
+        let names: Vec<&Name> = problem.names().collect();
+        let mut indexes = Vec::with_capacity(self.weights_order.len());
+        for name in &self.weights_order {
+            let index = names
+                .iter()
+                .position(|n| *n == name)
+                .ok_or_else(|| SmaaError::UnknownVariable(name.as_str().to_string()))?;
+            if indexes.contains(&index) {
+                return Err(SmaaError::RedefinitionVariable);
+            }
+            indexes.push(index);
+        }
+        Ok(indexes)
+    }
+
+    pub fn analyse(&self, problem: &Problem) -> Result<SmaaResult, SmaaError> {
+        if self.samples == 0 {
+            return Err(SmaaError::NoSamples);
+        }
+        let ordered = self.get_ordered_indexes(problem)?;
+        let matrix = problem.get_problem_matrix();
+        let (alternatives, variables) = matrix.dim();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut rank_counts = Array2::<f64>::zeros((alternatives, alternatives));
+        let mut weights_totals = Array2::<f64>::zeros((alternatives, variables));
+
+        for _ in 0..self.samples {
+            let weights = sample_weights(&mut rng, variables, &ordered);
+            let ranking = rank(matrix.view(), weights.view());
+            for (position, &alternative) in ranking.order().iter().enumerate() {
+                rank_counts[[alternative, position]] += 1.;
+            }
+            let mut best_totals = weights_totals.row_mut(ranking.best());
+            best_totals += &weights;
+        }
+
+        let wins = rank_counts.column(0).to_owned();
+        let rank_acceptability = rank_counts / self.samples as f64;
+
+        let central_vectors: Vec<Option<Array1<f64>>> = weights_totals
+            .axis_iter(Axis(0))
+            .zip(wins.iter())
+            .map(|(totals, &wins)| (wins > 0.).then(|| &totals / wins))
+            .collect();
+
+        let names: Vec<Name> = problem.names().cloned().collect();
+        let central_weights = central_vectors
+            .into_iter()
+            .map(|central| {
+                central.map(|w| {
+                    Weights::try_from(names.iter().cloned().zip(w).collect::<Vec<_>>()).unwrap()
+                })
+            })
+            .collect();
+
+        Ok(SmaaResult {
+            rank_acceptability,
+            central_weights,
+        })
+    }
+
+    /// The confidence factor of each alternative: the share of realizations of the uncertain values for which the
+    /// alternative is the best one with its central weights. `None` when the alternative is never the best, as it has
+    /// no central weights. The result usually comes from the analysis of the expected values of the variables.
+    pub fn confidence_factors(
+        &self,
+        result: &SmaaResult,
+        variables: &[UncertainVariable],
+    ) -> Result<Vec<Option<f64>>, SmaaError> {
+        if self.samples == 0 {
+            return Err(SmaaError::NoSamples);
+        }
+        let alternatives = result.central_weights.len();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut wins = vec![0.; alternatives];
+        for _ in 0..self.samples {
+            let problem = Problem::define(variables.iter().map(|v| v.sample(&mut rng)).collect())?;
+            if problem.alternatives() != alternatives {
+                return Err(SmaaError::AlternativesMismatch);
+            }
+            // the same realization for all the alternatives, so that the factors are comparable
+            for (alternative, central) in result.central_weights.iter().enumerate() {
+                if let Some(weights) = central
+                    && problem.rank_weighted(weights)?.best() == alternative
+                {
+                    wins[alternative] += 1.;
+                }
+            }
+        }
+        Ok(result
+            .central_weights
+            .iter()
+            .zip(wins)
+            .map(|(central, wins)| central.as_ref().map(|_| wins / self.samples as f64))
+            .collect())
+    }
+}
+
+fn rank(matrix: ArrayView2<f64>, weights: ArrayView1<f64>) -> Ranking {
+    Ranking::ascending(weighted_l2_norm_vectors(matrix, weights))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{
+        Distribution, UncertainValues, Values, VariableAutoscale, VariableInvertedAutoscale,
+    };
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("x").unwrap(),
+                Values::try_from(array![1., 2., 3., 3.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("y").unwrap(),
+                Values::try_from(array![3., 4., 5., 3.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sample_weights() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let w = sample_weights(&mut rng, 4, &[3, 0]);
+            assert_ulps_eq!(w.sum(), 1.);
+            assert!(w.iter().all(|&x| x >= 0.));
+            assert!(w[3] >= w[0]);
+        }
+    }
+
+    #[test]
+    fn test_analyse() {
+        let p = create_test_problem();
+        let result = Smaa::new(1000, 42).analyse(&p).unwrap();
+
+        // each row and each column of the rank acceptability indexes sum to 1
+        for sums in [
+            result.rank_acceptabilities().sum_axis(Axis(0)),
+            result.rank_acceptabilities().sum_axis(Axis(1)),
+        ] {
+            assert_ulps_eq!(sums, Array1::ones(4), epsilon = 1e-12);
+        }
+        // the last alternative is dominated: it is the worst for any weights
+        assert_ulps_eq!(result.rank_acceptability(3, 3), 1.);
+        assert!(result.central_weights(3).is_none());
+
+        // the first alternative wins only when x matters more than y
+        let central = result.central_weights(0).unwrap();
+        assert!(
+            central.get(&Name::try_from("x").unwrap()).unwrap()
+                > central.get(&Name::try_from("y").unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_analyse_is_reproducible() {
+        let p = create_test_problem();
+        let first = Smaa::new(100, 7).analyse(&p).unwrap();
+        let second = Smaa::new(100, 7).analyse(&p).unwrap();
+        assert_eq!(first.rank_acceptabilities(), second.rank_acceptabilities());
+    }
+
+    #[test]
+    fn test_analyse_with_weights_order() {
+        let p = create_test_problem();
+        let result = Smaa::new(500, 1)
+            .with_weights_order(vec![
+                Name::try_from("y").unwrap(),
+                Name::try_from("x").unwrap(),
+            ])
+            .analyse(&p)
+            .unwrap();
+        // when y matters more, the first alternative can never be the best
+        assert_ulps_eq!(result.rank_acceptability(0, 0), 0.);
+        let central = result.central_weights(2).unwrap();
+        assert!(
+            central.get(&Name::try_from("y").unwrap()).unwrap()
+                >= central.get(&Name::try_from("x").unwrap()).unwrap()
+        );
+    }
+
+    fn create_test_variables(first: Distribution) -> Vec<UncertainVariable> {
+        let exact = |values: &[f64]| {
+            values
+                .iter()
+                .map(|&x| Distribution::exact(x).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let mut x = exact(&[1., 2., 3., 3.]);
+        x[0] = first;
+        vec![
+            UncertainVariable::autoscale(
+                Name::try_from("x").unwrap(),
+                UncertainValues::try_from(x).unwrap(),
+            ),
+            UncertainVariable::inverted_autoscale(
+                Name::try_from("y").unwrap(),
+                UncertainValues::try_from(exact(&[3., 4., 5., 3.])).unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_confidence_factors() {
+        let smaa = Smaa::new(500, 3);
+        let result = smaa.analyse(&create_test_problem()).unwrap();
+
+        // with exact values, the central weights always make their alternative the best
+        let exact = create_test_variables(Distribution::exact(1.).unwrap());
+        let factors = smaa.confidence_factors(&result, &exact).unwrap();
+        assert_eq!(factors[0], Some(1.));
+        assert_eq!(factors[2], Some(1.));
+        assert_eq!(factors[3], None);
+
+        // the cost of the first alternative can be the highest one: it is not always the best
+        let uncertain = create_test_variables(Distribution::uniform(0., 4.).unwrap());
+        let factors = smaa.confidence_factors(&result, &uncertain).unwrap();
+        let first = factors[0].unwrap();
+        assert!(first > 0. && first < 1.);
+        assert_eq!(factors[3], None);
+    }
+
+    #[test]
+    fn confidence_factors_alternatives_mismatch_failure() {
+        let smaa = Smaa::new(10, 3);
+        let result = smaa.analyse(&create_test_problem()).unwrap();
+        let variables = vec![UncertainVariable::autoscale(
+            Name::try_from("x").unwrap(),
+            UncertainValues::try_from(vec![Distribution::exact(1.).unwrap()]).unwrap(),
+        )];
+        let err = smaa.confidence_factors(&result, &variables).unwrap_err();
+        assert_eq!(err, SmaaError::AlternativesMismatch);
+    }
+
+    #[test]
+    fn analyse_no_samples_failure() {
+        let err = Smaa::new(0, 0).analyse(&create_test_problem()).unwrap_err();
+        assert_eq!(err, SmaaError::NoSamples);
+    }
+
+    #[test]
+    fn analyse_unknown_variable_failure() {
+        let err = Smaa::new(1, 0)
+            .with_weights_order(vec![Name::try_from("z").unwrap()])
+            .analyse(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, SmaaError::UnknownVariable("z".to_string()));
+    }
+
+    #[test]
+    fn analyse_redefinition_variable_failure() {
+        let err = Smaa::new(1, 0)
+            .with_weights_order(vec![
+                Name::try_from("x").unwrap(),
+                Name::try_from("x").unwrap(),
+            ])
+            .analyse(&create_test_problem())
+            .unwrap_err();
+        assert_eq!(err, SmaaError::RedefinitionVariable);
+    }
+}
@@ -0,0 +1,261 @@
+//! This module elicits the weights of the variables with swing weighting, on the ranges of the problem.
+//!
+//! The swing of a variable goes from its worst to its best bound: the range that the rescaling maps from 1 to 0, that
+//! is the observed values for most variables and the quantile bounds for the robust ones. The decision maker rates
+//! how much each swing matters, usually 100 for the most important one and less for the others. The weights are
+//! proportional to the ratings, so they weigh the swings actually rescaled, and not the variables in the abstract.
+use super::problem::{EntryError, Problem, entries_by_name, worst_and_best};
+use super::weights::{Weights, WeightsError};
+use crate::variables::Name;
+use std::fmt;
+
+/// The worst and the best bounds of a variable in the problem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Swing {
+    name: Name,
+    worst: f64,
+    best: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwingWeighting {
+    swings: Vec<Swing>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SwingError {
+    #[error("The rating of '{0}' is missing: each variable must have one")]
+    MissingRating(String),
+    #[error("The rating '{0}' is not a variable of the problem")]
+    UnknownVariable(String),
+    #[error("Each variable can have only one rating: '{0}' has more than one")]
+    RedefinitionRating(String),
+    #[error("The rating of '{0}' must be finite and non-negative")]
+    InvalidRating(String),
+    #[error("The values of '{0}' are all the same: its swing cannot be rated above 0")]
+    EmptySwing(String),
+    #[error(transparent)]
+    Weights(#[from] WeightsError),
+}
+
+impl From<EntryError> for SwingError {
+    fn from(error: EntryError) -> Self {
+        match error {
+            EntryError::Unknown(name) => SwingError::UnknownVariable(name),
+            EntryError::Invalid(name) => SwingError::InvalidRating(name),
+            EntryError::Redefinition(name) => SwingError::RedefinitionRating(name),
+            EntryError::Missing(name) => SwingError::MissingRating(name),
+        }
+    }
+}
+
+impl Swing {
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn worst(&self) -> f64 {
+        self.worst
+    }
+
+    pub fn best(&self) -> f64 {
+        self.best
+    }
+
+    /// The size of the swing, in the units of the values.
+    pub fn range(&self) -> f64 {
+        (self.best - self.worst).abs()
+    }
+}
+
+impl fmt::Display for Swing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: from {} to {}",
+            self.name.as_str(),
+            self.worst,
+            self.best
+        )
+    }
+}
+
+impl SwingWeighting {
+    /// The swings of the variables of the problem, from the bounds of their rescaling.
+    pub fn new(problem: &Problem) -> Self {
+        let swings = problem
+            .variables()
+            .map(|variable| {
+                let (worst, best) = worst_and_best(variable);
+                Swing {
+                    name: variable.name().clone(),
+                    worst,
+                    best,
+                }
+            })
+            .collect();
+        Self { swings }
+    }
+
+    /// The swings, in the same order of the variables of the problem.
+    pub fn swings(&self) -> &[Swing] {
+        &self.swings
+    }
+
+    /// The weights from the ratings of the swings: the higher the rating, the more the swing matters.
+    pub fn weights(&self, ratings: Vec<(Name, f64)>) -> Result<Weights, SwingError> {
+        let names = self.swings.iter().map(|s| &s.name);
+        let ratings = ratings.iter().map(|(name, rating)| (name, *rating));
+        let rated = entries_by_name(names, ratings, true, |r| r.is_finite() && *r >= 0.)?;
+        if let Some(swing) = self
+            .swings
+            .iter()
+            .find(|s| rated[&s.name] > 0. && s.range() == 0.)
+        {
+            return Err(SwingError::EmptySwing(swing.name.as_str().to_string()));
+        }
+        Ok(Weights::try_from(rated.into_iter().collect::<Vec<_>>())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{
+        RobustScaling, RobustVariableAutoscale, Values, VariableAutoscale,
+        VariableInvertedAutoscale,
+    };
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![30., 10., 20.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![7., 9., 8.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("vendor").unwrap(),
+                Values::try_from(array![1., 1., 1.]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_swings() {
+        let swing = SwingWeighting::new(&create_test_problem());
+        let swings = swing.swings();
+        assert_eq!(swings.len(), 3);
+        assert_eq!(swings[0].name(), &Name::try_from("cost").unwrap());
+        assert_ulps_eq!(swings[0].worst(), 30.);
+        assert_ulps_eq!(swings[0].best(), 10.);
+        assert_ulps_eq!(swings[0].range(), 20.);
+        assert_eq!(swings[1].to_string(), "quality: from 7 to 9");
+        assert_ulps_eq!(swings[2].range(), 0.);
+    }
+
+    #[test]
+    fn test_robust_swing() {
+        // the swing is the range actually rescaled, without the outlier
+        let p = Problem::define(vec![Box::new(RobustVariableAutoscale::new(
+            Name::try_from("price").unwrap(),
+            Values::try_from(array![1., 2., 3., 4., 100.]).unwrap(),
+            RobustScaling::default(),
+        ))])
+        .unwrap();
+        let swing = SwingWeighting::new(&p);
+        assert_ulps_eq!(swing.swings()[0].worst(), 80.8);
+        assert_ulps_eq!(swing.swings()[0].best(), 1.2);
+    }
+
+    #[test]
+    fn test_weights() {
+        let weights = SwingWeighting::new(&create_test_problem())
+            .weights(vec![
+                (Name::try_from("quality").unwrap(), 100.),
+                (Name::try_from("cost").unwrap(), 60.),
+                (Name::try_from("vendor").unwrap(), 0.),
+            ])
+            .unwrap();
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("quality").unwrap()).unwrap(),
+            0.625
+        );
+        assert_ulps_eq!(
+            weights.get(&Name::try_from("cost").unwrap()).unwrap(),
+            0.375
+        );
+        assert_ulps_eq!(weights.get(&Name::try_from("vendor").unwrap()).unwrap(), 0.);
+        // the weights plug into the rescaled problem
+        let ranking = create_test_problem().rank_weighted(&weights).unwrap();
+        assert_eq!(ranking.best(), 1);
+    }
+
+    #[test]
+    fn weights_empty_swing_failure() {
+        let err = SwingWeighting::new(&create_test_problem())
+            .weights(vec![
+                (Name::try_from("quality").unwrap(), 100.),
+                (Name::try_from("cost").unwrap(), 60.),
+                (Name::try_from("vendor").unwrap(), 10.),
+            ])
+            .unwrap_err();
+        assert_eq!(err, SwingError::EmptySwing("vendor".to_string()));
+    }
+
+    #[test]
+    fn weights_missing_rating_failure() {
+        let err = SwingWeighting::new(&create_test_problem())
+            .weights(vec![
+                (Name::try_from("quality").unwrap(), 100.),
+                (Name::try_from("cost").unwrap(), 60.),
+            ])
+            .unwrap_err();
+        assert_eq!(err, SwingError::MissingRating("vendor".to_string()));
+    }
+
+    #[test]
+    fn weights_unknown_variable_failure() {
+        let err = SwingWeighting::new(&create_test_problem())
+            .weights(vec![(Name::try_from("speed").unwrap(), 100.)])
+            .unwrap_err();
+        assert_eq!(err, SwingError::UnknownVariable("speed".to_string()));
+    }
+
+    #[test]
+    fn weights_invalid_rating_failure() {
+        let err = SwingWeighting::new(&create_test_problem())
+            .weights(vec![(Name::try_from("cost").unwrap(), -1.)])
+            .unwrap_err();
+        assert_eq!(err, SwingError::InvalidRating("cost".to_string()));
+    }
+
+    #[test]
+    fn weights_redefinition_rating_failure() {
+        let err = SwingWeighting::new(&create_test_problem())
+            .weights(vec![
+                (Name::try_from("cost").unwrap(), 1.),
+                (Name::try_from("cost").unwrap(), 2.),
+            ])
+            .unwrap_err();
+        assert_eq!(err, SwingError::RedefinitionRating("cost".to_string()));
+    }
+
+    #[test]
+    fn weights_all_zero_failure() {
+        let err = SwingWeighting::new(&create_test_problem())
+            .weights(vec![
+                (Name::try_from("quality").unwrap(), 0.),
+                (Name::try_from("cost").unwrap(), 0.),
+                (Name::try_from("vendor").unwrap(), 0.),
+            ])
+            .unwrap_err();
+        assert_eq!(err, SwingError::Weights(WeightsError::AllZero));
+    }
+}
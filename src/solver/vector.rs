@@ -7,7 +7,7 @@ fn l2_norm(v: ArrayView1<f64>) -> f64 {
     v.dot(&v).sqrt()
 }
 
-fn l2_norm_vectors(m: ArrayView2<f64>) -> Array1<f64> {
+pub fn l2_norm_vectors(m: ArrayView2<f64>) -> Array1<f64> {
     // compute l2 norm for each vector (row)
     // NOTE: we don't check for empty vectors or infinite values because the caller guarantees for it
     let mut norms = Array1::zeros(m.nrows());
@@ -18,6 +18,24 @@ fn l2_norm_vectors(m: ArrayView2<f64>) -> Array1<f64> {
     norms
 }
 
+pub fn weighted_l2_norm_vectors(m: ArrayView2<f64>, weights: ArrayView1<f64>) -> Array1<f64> {
+    // compute the weighted l2 norm for each vector (row): sqrt(sum(w_j * x_j^2))
+    // NOTE: we don't check for sizes or negative weights because the caller guarantees for it
+    m.mapv(|x| x * x).dot(&weights).mapv(f64::sqrt)
+}
+
+pub fn weighted_closeness_vectors(m: ArrayView2<f64>, weights: ArrayView1<f64>) -> Array1<f64> {
+    // TOPSIS closeness of each vector (row) to the ideal (zeros) rather than the anti-ideal (ones)
+    // NOTE: we don't check for sizes or negative weights because the caller guarantees for it
+    let to_ideal = weighted_l2_norm_vectors(m, weights);
+    let to_anti_ideal = weighted_l2_norm_vectors(m.mapv(|x| 1. - x).view(), weights);
+    let total = &to_ideal + &to_anti_ideal;
+    // a vector with no weighted distance from both is halfway
+    Zip::from(&to_anti_ideal)
+        .and(&total)
+        .map_collect(|&anti, &total| if total > 0. { anti / total } else { 0.5 })
+}
+
 pub fn index_of_best_vector(m: ArrayView2<f64>) -> usize {
     // compute l2 norm for each vector (row) and find the best (min) vector
     // NOTE: we don't check for empty vectors or infinite values because the caller guarantees for it
@@ -83,4 +101,30 @@ mod tests {
         assert_ulps_eq!(l2_norm_vectors(m.view()), array![sqrt3, 2., SQRT2, SQRT2,]);
         assert_eq!(index_of_best_vector(m.view()), 2);
     }
+
+    #[test]
+    fn weighted_closeness_vectors_success() {
+        let m = array![[0., 0.], [1., 1.], [0.5, 0.5], [0., 1.]];
+        assert_ulps_eq!(
+            weighted_closeness_vectors(m.view(), array![0.5, 0.5].view()),
+            array![1., 0., 0.5, 0.5]
+        );
+        assert_ulps_eq!(
+            weighted_closeness_vectors(m.view(), array![1., 0.].view()),
+            array![1., 0., 0.5, 1.]
+        );
+    }
+
+    #[test]
+    fn weighted_l2_norm_vectors_success() {
+        let m = array![[1., 1.], [2., 0.], [0., 2.],];
+        assert_ulps_eq!(
+            weighted_l2_norm_vectors(m.view(), array![0.5, 0.5].view()),
+            l2_norm_vectors(m.view()) / SQRT2
+        );
+        assert_ulps_eq!(
+            weighted_l2_norm_vectors(m.view(), array![1., 0.].view()),
+            array![1., 2., 0.]
+        );
+    }
 }
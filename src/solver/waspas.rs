@@ -0,0 +1,199 @@
+//! This module ranks problems with the simple scoring methods: SAW (weighted sum), WPM (weighted product) and WASPAS,
+//! which blends the two.
+//!
+//! Unlike the distance-based methods, the raw values are normalized linearly by the best value of each variable:
+//! `x / max` when the higher, the better, and `min / x` when the lower, the better. The values must be positive, and
+//! the best value of each variable is normalized to 1. The higher the score, the better.
+use super::problem::{Problem, ProblemError};
+use super::ranking::Ranking;
+use super::weights::Weights;
+use ndarray::{Array1, Array2, Axis};
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum ScoringError {
+    #[error("The values of '{0}' must be positive for the linear normalization")]
+    NonPositiveValues(String),
+    #[error("Lambda must be in [0, 1]")]
+    InvalidLambda,
+    #[error(transparent)]
+    Problem(#[from] ProblemError),
+}
+
+pub(super) fn get_linear_matrix(problem: &Problem) -> Result<Array2<f64>, ScoringError> {
+    let mut matrix = Array2::zeros((problem.alternatives(), problem.names().count()));
+    for (mut column, variable) in matrix.axis_iter_mut(Axis(1)).zip(problem.variables()) {
+        let values = variable.values().values();
+        if values.iter().any(|&x| x <= 0.) {
+            return Err(ScoringError::NonPositiveValues(
+                variable.name().as_str().to_string(),
+            ));
+        }
+        if variable.higher_is_better() {
+            let max = values.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+            column.assign(&values.mapv(|x| x / max));
+        } else {
+            let min = values.fold(f64::INFINITY, |a, &b| a.min(b));
+            column.assign(&values.mapv(|x| min / x));
+        }
+    }
+    Ok(matrix)
+}
+
+/// The WASPAS scores: `lambda` times the weighted sum plus `1 - lambda` times the weighted product.
+pub(super) fn waspas_scores(
+    problem: &Problem,
+    weights: &Array1<f64>,
+    lambda: f64,
+) -> Result<Array1<f64>, ScoringError> {
+    if !(0. ..=1.).contains(&lambda) {
+        return Err(ScoringError::InvalidLambda);
+    }
+    weighted_scores(&get_linear_matrix(problem)?, weights, lambda)
+}
+
+/// The WASPAS scores of values that are already normalized, the higher the better: one row for each alternative, one
+/// column for each variable. The values must not be negative; a variable with a zero weight does not count in the
+/// product, even where its value is 0.
+pub(super) fn weighted_scores(
+    matrix: &Array2<f64>,
+    weights: &Array1<f64>,
+    lambda: f64,
+) -> Result<Array1<f64>, ScoringError> {
+    if !(0. ..=1.).contains(&lambda) {
+        return Err(ScoringError::InvalidLambda);
+    }
+    let sum = matrix.dot(weights);
+    let mut logarithms = matrix.mapv(f64::ln);
+    for (mut column, &weight) in logarithms.axis_iter_mut(Axis(1)).zip(weights) {
+        if weight == 0. {
+            column.fill(0.);
+        }
+    }
+    let product = logarithms.dot(weights).mapv(f64::exp);
+    Ok(sum * lambda + product * (1. - lambda))
+}
+
+impl Problem {
+    /// Ranks the alternatives by Simple Additive Weighting: the weighted sum of the normalized values.
+    pub fn rank_saw(&self, weights: &Weights) -> Result<Ranking, ScoringError> {
+        self.rank_waspas(weights, 1.)
+    }
+
+    /// Ranks the alternatives by the Weighted Product Model: the product of the normalized values, each one raised to
+    /// its weight.
+    pub fn rank_wpm(&self, weights: &Weights) -> Result<Ranking, ScoringError> {
+        self.rank_waspas(weights, 0.)
+    }
+
+    /// Ranks the alternatives by WASPAS: `lambda` blends the weighted sum (1) and the weighted product (0).
+    pub fn rank_waspas(&self, weights: &Weights, lambda: f64) -> Result<Ranking, ScoringError> {
+        let weights = self.get_weights_vector(weights)?;
+        Ok(Ranking::descending(waspas_scores(self, &weights, lambda)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::{Name, Values, VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_problem() -> Problem {
+        Problem::define(vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("cost").unwrap(),
+                Values::try_from(array![2., 4., 1.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("quality").unwrap(),
+                Values::try_from(array![8., 10., 0.5]).unwrap(),
+            )),
+        ])
+        .unwrap()
+    }
+
+    fn create_test_weights() -> Weights {
+        Weights::try_from(vec![
+            (Name::try_from("cost").unwrap(), 1.),
+            (Name::try_from("quality").unwrap(), 1.),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_linear_matrix() {
+        let matrix = get_linear_matrix(&create_test_problem()).unwrap();
+        assert_ulps_eq!(matrix, array![[0.5, 0.8], [0.25, 1.], [1., 0.05]]);
+    }
+
+    #[test]
+    fn test_saw() {
+        let p = create_test_problem();
+        let ranking = p.rank_saw(&create_test_weights()).unwrap();
+        assert_ulps_eq!(ranking.scores(), array![0.65, 0.625, 0.525]);
+        assert_eq!(ranking.order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_wpm() {
+        let p = create_test_problem();
+        let ranking = p.rank_wpm(&create_test_weights()).unwrap();
+        assert_ulps_eq!(
+            ranking.scores(),
+            array![0.4_f64.sqrt(), 0.5, 0.05_f64.sqrt()]
+        );
+        assert_eq!(ranking.order(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_waspas() {
+        let p = create_test_problem();
+        let w = create_test_weights();
+        let saw = p.rank_saw(&w).unwrap();
+        let wpm = p.rank_wpm(&w).unwrap();
+        let waspas = p.rank_waspas(&w, 0.5).unwrap();
+        assert_ulps_eq!(waspas.scores(), (&saw.scores() + &wpm.scores()) * 0.5);
+    }
+
+    #[test]
+    fn test_product_penalizes_bad_values() {
+        // the weighted sum compensates the poor quality of the last alternative, the weighted product does not
+        let w = Weights::try_from(vec![
+            (Name::try_from("cost").unwrap(), 3.),
+            (Name::try_from("quality").unwrap(), 1.),
+        ])
+        .unwrap();
+        let p = create_test_problem();
+        assert_eq!(p.rank_saw(&w).unwrap().best(), 2);
+        assert_eq!(p.rank_wpm(&w).unwrap().best(), 0);
+    }
+
+    #[test]
+    fn rank_non_positive_values_failure() {
+        let p = Problem::define(vec![Box::new(VariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            Values::try_from(array![0., 1.]).unwrap(),
+        ))])
+        .unwrap();
+        let w = Weights::try_from(vec![(Name::try_from("x").unwrap(), 1.)]).unwrap();
+        let err = p.rank_saw(&w).unwrap_err();
+        assert_eq!(err, ScoringError::NonPositiveValues("x".to_string()));
+    }
+
+    #[test]
+    fn rank_invalid_lambda_failure() {
+        let err = create_test_problem()
+            .rank_waspas(&create_test_weights(), 1.5)
+            .unwrap_err();
+        assert_eq!(err, ScoringError::InvalidLambda);
+    }
+
+    #[test]
+    fn rank_weights_mismatch_failure() {
+        let w = Weights::try_from(vec![(Name::try_from("cost").unwrap(), 1.)]).unwrap();
+        let err = create_test_problem().rank_wpm(&w).unwrap_err();
+        assert_eq!(err, ScoringError::Problem(ProblemError::WeightsMismatch));
+    }
+}
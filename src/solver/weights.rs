@@ -0,0 +1,262 @@
+//! This module defines the relative importance of the variables of a problem.
+use crate::variables::Name;
+use std::collections::BTreeMap;
+
+/// Non-negative weights, one for each variable, normalized to sum to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weights(BTreeMap<Name, f64>);
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum WeightsError {
+    #[error("The weights cannot be empty")]
+    Empty,
+    #[error("The weights must be finite and non-negative")]
+    InvalidWeight,
+    #[error("At least one weight must be positive")]
+    AllZero,
+    #[error("Each variable can have only one weight")]
+    RedefinitionVariable,
+    #[error("SWARA needs one comparative importance step for each variable after the first one")]
+    StepsMismatch,
+    #[error("The comparative importance steps must be finite and non-negative")]
+    InvalidStep,
+}
+
+impl Weights {
+    pub fn get(&self, name: &Name) -> Option<f64> {
+        self.0.get(name).copied()
+    }
+
+    /// The weights, in the same order of the variables of a problem.
+    pub fn iter(&self) -> impl Iterator<Item = (&Name, f64)> {
+        self.0.iter().map(|(name, &weight)| (name, weight))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // NOTE: always false, weights are never empty by construction
+        self.0.is_empty()
+    }
+
+    /// Weights from the variables ranked from the most important, with the weight of each position.
+    fn from_positions(
+        order: Vec<Name>,
+        weight: impl Fn(usize, usize) -> f64,
+    ) -> Result<Self, WeightsError> {
+        let size = order.len();
+        let weights = order
+            .into_iter()
+            .enumerate()
+            .map(|(position, name)| (name, weight(position + 1, size)))
+            .collect::<Vec<_>>();
+        Weights::try_from(weights)
+    }
+
+    /// Rank-order centroid weights, from the most important variable: the i-th weight is the mean of 1/k for k from i
+    /// to n.
+    pub fn rank_order_centroid(order: Vec<Name>) -> Result<Self, WeightsError> {
+        Weights::from_positions(order, |i, n| {
+            (i..=n).map(|k| 1. / k as f64).sum::<f64>() / n as f64
+        })
+    }
+
+    /// Rank-sum weights, from the most important variable: the i-th weight is proportional to n - i + 1.
+    pub fn rank_sum(order: Vec<Name>) -> Result<Self, WeightsError> {
+        Weights::from_positions(order, |i, n| (n - i + 1) as f64)
+    }
+
+    /// Rank-reciprocal weights, from the most important variable: the i-th weight is proportional to 1/i.
+    pub fn rank_reciprocal(order: Vec<Name>) -> Result<Self, WeightsError> {
+        Weights::from_positions(order, |i, _| 1. / i as f64)
+    }
+
+    /// SWARA weights, from the most important variable. Each step is how much more important a variable is than the
+    /// next one: 0 is equally important, 0.5 is 50% more important.
+    pub fn swara(order: Vec<Name>, steps: &[f64]) -> Result<Self, WeightsError> {
+        if order.len() != steps.len() + 1 {
+            return Err(WeightsError::StepsMismatch);
+        }
+        if steps.iter().any(|s| !s.is_finite() || *s < 0.) {
+            return Err(WeightsError::InvalidStep);
+        }
+        // the recalculated weight of each variable is the previous one divided by 1 + step
+        let mut weight = 1.;
+        let mut weights = Vec::with_capacity(order.len());
+        for (k, name) in order.into_iter().enumerate() {
+            if k > 0 {
+                weight /= 1. + steps[k - 1];
+            }
+            weights.push((name, weight));
+        }
+        Weights::try_from(weights)
+    }
+}
+
+impl TryFrom<Vec<(Name, f64)>> for Weights {
+    type Error = WeightsError;
+
+    fn try_from(weights: Vec<(Name, f64)>) -> Result<Self, Self::Error> {
+        if weights.is_empty() {
+            return Err(WeightsError::Empty);
+        }
+        if weights.iter().any(|(_, w)| !w.is_finite() || *w < 0.) {
+            return Err(WeightsError::InvalidWeight);
+        }
+        let highest = weights.iter().fold(0., |a: f64, (_, w)| a.max(*w));
+        if highest <= 0. {
+            return Err(WeightsError::AllZero);
+        }
+        // divided by the highest weight first, so that the total of big weights does not overflow
+        let total: f64 = weights.iter().map(|(_, w)| w / highest).sum();
+
+        let mut normalized = BTreeMap::new();
+        for (name, weight) in weights {
+            if normalized.insert(name, weight / highest / total).is_some() {
+                return Err(WeightsError::RedefinitionVariable);
+            }
+        }
+        Ok(Weights(normalized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_try_from_normalizes() {
+        let w = Weights::try_from(vec![
+            (Name::try_from("y").unwrap(), 3.),
+            (Name::try_from("x").unwrap(), 1.),
+        ])
+        .unwrap();
+        assert_eq!(w.len(), 2);
+        assert_ulps_eq!(w.get(&Name::try_from("x").unwrap()).unwrap(), 0.25);
+        assert_ulps_eq!(w.get(&Name::try_from("y").unwrap()).unwrap(), 0.75);
+        assert_eq!(w.get(&Name::try_from("z").unwrap()), None);
+        // sorted by name, as the variables of a problem
+        let names: Vec<&str> = w.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["x", "y"]);
+    }
+
+    fn order() -> Vec<Name> {
+        vec![
+            Name::try_from("quality").unwrap(),
+            Name::try_from("cost").unwrap(),
+            Name::try_from("time").unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_rank_order_centroid() {
+        let w = Weights::rank_order_centroid(order()).unwrap();
+        assert_ulps_eq!(
+            w.get(&Name::try_from("quality").unwrap()).unwrap(),
+            11. / 18.
+        );
+        assert_ulps_eq!(w.get(&Name::try_from("cost").unwrap()).unwrap(), 5. / 18.);
+        assert_ulps_eq!(w.get(&Name::try_from("time").unwrap()).unwrap(), 2. / 18.);
+    }
+
+    #[test]
+    fn test_rank_sum() {
+        let w = Weights::rank_sum(order()).unwrap();
+        assert_ulps_eq!(w.get(&Name::try_from("quality").unwrap()).unwrap(), 0.5);
+        assert_ulps_eq!(w.get(&Name::try_from("cost").unwrap()).unwrap(), 1. / 3.);
+        assert_ulps_eq!(w.get(&Name::try_from("time").unwrap()).unwrap(), 1. / 6.);
+    }
+
+    #[test]
+    fn test_rank_reciprocal() {
+        let w = Weights::rank_reciprocal(order()).unwrap();
+        assert_ulps_eq!(
+            w.get(&Name::try_from("quality").unwrap()).unwrap(),
+            6. / 11.
+        );
+        assert_ulps_eq!(w.get(&Name::try_from("cost").unwrap()).unwrap(), 3. / 11.);
+        assert_ulps_eq!(w.get(&Name::try_from("time").unwrap()).unwrap(), 2. / 11.);
+    }
+
+    #[test]
+    fn test_swara() {
+        // quality is as important as cost, which is twice as important as time
+        let w = Weights::swara(order(), &[0., 1.]).unwrap();
+        assert_ulps_eq!(w.get(&Name::try_from("quality").unwrap()).unwrap(), 0.4);
+        assert_ulps_eq!(w.get(&Name::try_from("cost").unwrap()).unwrap(), 0.4);
+        assert_ulps_eq!(w.get(&Name::try_from("time").unwrap()).unwrap(), 0.2);
+    }
+
+    #[test]
+    fn test_rank_weights_redefinition_failure() {
+        let err = Weights::rank_sum(vec![
+            Name::try_from("x").unwrap(),
+            Name::try_from("x").unwrap(),
+        ])
+        .unwrap_err();
+        assert_eq!(err, WeightsError::RedefinitionVariable);
+        let err = Weights::rank_order_centroid(vec![]).unwrap_err();
+        assert_eq!(err, WeightsError::Empty);
+    }
+
+    #[test]
+    fn test_swara_failure() {
+        let err = Weights::swara(order(), &[0.5]).unwrap_err();
+        assert_eq!(err, WeightsError::StepsMismatch);
+        let err = Weights::swara(order(), &[0.5, -1.]).unwrap_err();
+        assert_eq!(err, WeightsError::InvalidStep);
+    }
+
+    #[test]
+    fn test_try_from_empty_failure() {
+        let err = Weights::try_from(vec![]).unwrap_err();
+        assert_eq!(err, WeightsError::Empty);
+    }
+
+    #[test]
+    fn test_try_from_negative_failure() {
+        let err = Weights::try_from(vec![(Name::try_from("x").unwrap(), -1.)]).unwrap_err();
+        assert_eq!(err, WeightsError::InvalidWeight);
+    }
+
+    #[test]
+    fn test_try_from_not_finite_failure() {
+        let err = Weights::try_from(vec![(Name::try_from("x").unwrap(), f64::NAN)]).unwrap_err();
+        assert_eq!(err, WeightsError::InvalidWeight);
+    }
+
+    #[test]
+    fn test_try_from_all_zero_failure() {
+        let err = Weights::try_from(vec![
+            (Name::try_from("x").unwrap(), 0.),
+            (Name::try_from("y").unwrap(), 0.),
+        ])
+        .unwrap_err();
+        assert_eq!(err, WeightsError::AllZero);
+    }
+
+    #[test]
+    fn test_try_from_huge_weights() {
+        let w = Weights::try_from(vec![
+            (Name::try_from("x").unwrap(), f64::MAX),
+            (Name::try_from("y").unwrap(), f64::MAX),
+        ])
+        .unwrap();
+        assert_ulps_eq!(w.get(&Name::try_from("x").unwrap()).unwrap(), 0.5);
+        assert_ulps_eq!(w.get(&Name::try_from("y").unwrap()).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_try_from_redefinition_failure() {
+        let err = Weights::try_from(vec![
+            (Name::try_from("x").unwrap(), 1.),
+            (Name::try_from("x").unwrap(), 2.),
+        ])
+        .unwrap_err();
+        assert_eq!(err, WeightsError::RedefinitionVariable);
+    }
+}
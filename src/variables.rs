@@ -1,13 +1,44 @@
+mod distribution;
+mod expression;
+mod fuzzynumber;
+mod fuzzyvalues;
+mod fuzzyvariableautoscale;
+mod fuzzyvariableinvertedautoscale;
+mod intervalvalues;
+mod intervalvariableautoscale;
+mod intervalvariableinvertedautoscale;
+mod linguisticscale;
 mod name;
+mod robustscaling;
+mod robustvariableautoscale;
+mod robustvariableinvertedautoscale;
 mod scaling;
 mod traits;
+mod uncertainvalues;
+mod uncertainvariable;
 mod values;
 mod variableautoscale;
 mod variableinvertedautoscale;
 
-pub use self::name::Name;
-pub use self::traits::VariableProperties;
-pub use self::values::Values;
+pub use self::distribution::{Distribution, DistributionError};
+pub use self::expression::{EXPRESSION_MAX_DEPTH, Expression, ExpressionError};
+pub use self::fuzzynumber::{Defuzzification, FuzzyNumber, FuzzyNumberError};
+pub use self::fuzzyvalues::{FuzzyValues, FuzzyValuesError};
+pub use self::fuzzyvariableautoscale::FuzzyVariableAutoscale;
+pub use self::fuzzyvariableinvertedautoscale::FuzzyVariableInvertedAutoscale;
+pub use self::intervalvalues::{IntervalValues, IntervalValuesError};
+pub use self::intervalvariableautoscale::IntervalVariableAutoscale;
+pub use self::intervalvariableinvertedautoscale::IntervalVariableInvertedAutoscale;
+pub use self::linguisticscale::{LinguisticScale, LinguisticScaleError};
+pub use self::name::{Name, NameError};
+pub use self::robustscaling::{Clipping, RobustScaling, RobustScalingError};
+pub use self::robustvariableautoscale::RobustVariableAutoscale;
+pub use self::robustvariableinvertedautoscale::RobustVariableInvertedAutoscale;
+pub(crate) use self::scaling::{halve_huge_range, normalize_vector};
+pub use self::traits::{FuzzyVariableProperties, IntervalVariableProperties, VariableProperties};
+pub use self::uncertainvalues::{UncertainValues, UncertainValuesError};
+pub use self::uncertainvariable::{UncertainVariable, VariableBuilder};
+pub use self::values::{Values, ValuesError};
 pub use self::variableautoscale::VariableAutoscale;
 pub use self::variableinvertedautoscale::VariableInvertedAutoscale;
 
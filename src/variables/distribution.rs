@@ -0,0 +1,229 @@
+//! This module defines the probability distributions of uncertain values.
+use rand::Rng;
+use rand::distr::Uniform;
+use rand_distr::{Distribution as _, Normal};
+
+/// The distribution of an uncertain value.
+#[derive(Debug, Clone)]
+pub struct Distribution(Kind);
+
+#[derive(Debug, Clone)]
+enum Kind {
+    /// A value known without uncertainty.
+    Exact(f64),
+    Normal(Normal<f64>),
+    Uniform(Uniform<f64>, f64, f64),
+    /// The low bound, the mode and the high bound.
+    Triangular(f64, f64, f64),
+    /// Observed samples, drawn with replacement.
+    Empirical(Vec<f64>),
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum DistributionError {
+    #[error("The parameters of the distribution cannot be NaN or Infinity")]
+    NoFiniteValues,
+    #[error("The standard deviation cannot be negative")]
+    NegativeStandardDeviation,
+    #[error("The bounds must be ordered: low <= mode <= high")]
+    UnorderedBounds,
+    #[error("The range between the bounds cannot be Infinity")]
+    InfiniteRange,
+    #[error("The empirical distribution needs at least one sample")]
+    EmptySample,
+}
+
+fn check_finite(parameters: &[f64]) -> Result<(), DistributionError> {
+    if parameters.iter().all(|x| x.is_finite()) {
+        Ok(())
+    } else {
+        Err(DistributionError::NoFiniteValues)
+    }
+}
+
+/// The samples are drawn by scaling the range: it must not overflow.
+fn check_range(low: f64, high: f64) -> Result<(), DistributionError> {
+    if (high - low).is_finite() {
+        Ok(())
+    } else {
+        Err(DistributionError::InfiniteRange)
+    }
+}
+
+impl Distribution {
+    pub fn exact(value: f64) -> Result<Self, DistributionError> {
+        check_finite(&[value])?;
+        Ok(Distribution(Kind::Exact(value)))
+    }
+
+    pub fn normal(mean: f64, std_dev: f64) -> Result<Self, DistributionError> {
+        check_finite(&[mean, std_dev])?;
+        if std_dev < 0. {
+            return Err(DistributionError::NegativeStandardDeviation);
+        }
+        Ok(Distribution(Kind::Normal(
+            Normal::new(mean, std_dev).unwrap(),
+        )))
+    }
+
+    pub fn uniform(low: f64, high: f64) -> Result<Self, DistributionError> {
+        check_finite(&[low, high])?;
+        if low > high {
+            return Err(DistributionError::UnorderedBounds);
+        }
+        check_range(low, high)?;
+        // rand scales the range a little further: it can still overflow next to the limits of the floats
+        let uniform =
+            Uniform::new_inclusive(low, high).map_err(|_| DistributionError::InfiniteRange)?;
+        Ok(Distribution(Kind::Uniform(uniform, low, high)))
+    }
+
+    pub fn triangular(low: f64, mode: f64, high: f64) -> Result<Self, DistributionError> {
+        check_finite(&[low, mode, high])?;
+        if !(low <= mode && mode <= high) {
+            return Err(DistributionError::UnorderedBounds);
+        }
+        check_range(low, high)?;
+        Ok(Distribution(Kind::Triangular(low, mode, high)))
+    }
+
+    pub fn empirical(samples: Vec<f64>) -> Result<Self, DistributionError> {
+        if samples.is_empty() {
+            return Err(DistributionError::EmptySample);
+        }
+        check_finite(&samples)?;
+        Ok(Distribution(Kind::Empirical(samples)))
+    }
+
+    pub fn mean(&self) -> f64 {
+        match &self.0 {
+            Kind::Exact(value) => *value,
+            Kind::Normal(normal) => normal.mean(),
+            Kind::Uniform(_, low, high) => low * 0.5 + high * 0.5,
+            Kind::Triangular(low, mode, high) => low / 3. + mode / 3. + high / 3.,
+            Kind::Empirical(samples) => {
+                // each sample is divided first, so that the sum of big samples does not overflow
+                let size = samples.len() as f64;
+                samples.iter().map(|x| x / size).sum()
+            }
+        }
+    }
+
+    pub(crate) fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match &self.0 {
+            Kind::Exact(value) => *value,
+            // the tails of the normal are unbounded: a huge mean or standard deviation can overflow
+            Kind::Normal(normal) => normal.sample(rng).clamp(f64::MIN, f64::MAX),
+            Kind::Uniform(uniform, _, _) => uniform.sample(rng),
+            Kind::Triangular(low, mode, high) => {
+                // the inverse of the cumulative distribution, on the fractions of the range rather than on its
+                // square, which can overflow
+                let range = high - low;
+                if range == 0. {
+                    return *low;
+                }
+                let peak = (mode - low) / range;
+                let u: f64 = rng.random();
+                let x = if u < peak {
+                    low + range * (u * peak).sqrt()
+                } else {
+                    high - range * ((1. - u) * (1. - peak)).sqrt()
+                };
+                x.clamp(*low, *high)
+            }
+            Kind::Empirical(samples) => samples[rng.random_range(0..samples.len())],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_mean() {
+        assert_ulps_eq!(Distribution::exact(2.).unwrap().mean(), 2.);
+        assert_ulps_eq!(Distribution::normal(2., 1.).unwrap().mean(), 2.);
+        assert_ulps_eq!(Distribution::uniform(1., 3.).unwrap().mean(), 2.);
+        assert_ulps_eq!(Distribution::triangular(1., 1., 4.).unwrap().mean(), 2.);
+        assert_ulps_eq!(Distribution::empirical(vec![1., 3.]).unwrap().mean(), 2.);
+    }
+
+    #[test]
+    fn test_sample_within_bounds() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let uniform = Distribution::uniform(1., 3.).unwrap();
+        let triangular = Distribution::triangular(1., 2., 3.).unwrap();
+        let empirical = Distribution::empirical(vec![1., 3.]).unwrap();
+        for _ in 0..100 {
+            assert!((1. ..=3.).contains(&uniform.sample(&mut rng)));
+            assert!((1. ..=3.).contains(&triangular.sample(&mut rng)));
+            let x = empirical.sample(&mut rng);
+            assert!(x == 1. || x == 3.);
+        }
+        assert_ulps_eq!(Distribution::exact(2.).unwrap().sample(&mut rng), 2.);
+        assert_ulps_eq!(Distribution::normal(2., 0.).unwrap().sample(&mut rng), 2.);
+    }
+
+    #[test]
+    fn test_huge_parameters() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let normal = Distribution::normal(f64::MAX, f64::MAX).unwrap();
+        for _ in 0..100 {
+            assert!(normal.sample(&mut rng).is_finite());
+        }
+        let empirical = Distribution::empirical(vec![f64::MAX, f64::MAX]).unwrap();
+        assert_ulps_eq!(empirical.mean(), f64::MAX);
+        // the square of the range overflows, the range itself does not
+        let triangular = Distribution::triangular(0., 1e200, 1e200).unwrap();
+        for _ in 0..100 {
+            assert!((0. ..=1e200).contains(&triangular.sample(&mut rng)));
+        }
+    }
+
+    #[test]
+    fn test_not_finite_failure() {
+        let err = Distribution::normal(f64::NAN, 1.).unwrap_err();
+        assert_eq!(err, DistributionError::NoFiniteValues);
+        let err = Distribution::empirical(vec![1., f64::INFINITY]).unwrap_err();
+        assert_eq!(err, DistributionError::NoFiniteValues);
+    }
+
+    #[test]
+    fn test_negative_std_dev_failure() {
+        let err = Distribution::normal(0., -1.).unwrap_err();
+        assert_eq!(err, DistributionError::NegativeStandardDeviation);
+    }
+
+    #[test]
+    fn test_unordered_bounds_failure() {
+        let err = Distribution::uniform(2., 1.).unwrap_err();
+        assert_eq!(err, DistributionError::UnorderedBounds);
+        let err = Distribution::triangular(1., 4., 3.).unwrap_err();
+        assert_eq!(err, DistributionError::UnorderedBounds);
+    }
+
+    #[test]
+    fn test_infinite_range_failure() {
+        let err = Distribution::uniform(-f64::MAX, f64::MAX).unwrap_err();
+        assert_eq!(err, DistributionError::InfiniteRange);
+        let err = Distribution::triangular(-f64::MAX, 0., f64::MAX).unwrap_err();
+        assert_eq!(err, DistributionError::InfiniteRange);
+        let err = Distribution::uniform(0., f64::MAX).unwrap_err();
+        assert_eq!(err, DistributionError::InfiniteRange);
+        // the bounds alone can be huge
+        assert_ulps_eq!(
+            Distribution::uniform(f64::MAX, f64::MAX).unwrap().mean(),
+            f64::MAX
+        );
+    }
+
+    #[test]
+    fn test_empty_sample_failure() {
+        let err = Distribution::empirical(vec![]).unwrap_err();
+        assert_eq!(err, DistributionError::EmptySample);
+    }
+}
@@ -0,0 +1,798 @@
+//! This module defines derived variables: expressions evaluated element-wise over the values of other variables.
+//!
+//! An expression references the variables by name, such as `price / capacity`, `log(latency)` or `max(a, b)`. It
+//! supports numbers, the operators `+`, `-`, `*`, `/` and `^`, parentheses and the functions `abs`, `exp`, `log` (the
+//! natural logarithm), `log10`, `sqrt`, `min` and `max`. Only a word of digits is a number: `2024_cost` is a name. The
+//! result is a vector of raw values, to give to a variable before the rescaling, so that the derived criterion stays
+//! traceable to the data it comes from. An expression can be nested up to `EXPRESSION_MAX_DEPTH` levels, parentheses,
+//! calls and operators included.
+use super::Name;
+use super::Values;
+use super::VariableProperties;
+use ndarray::{Array1, Zip};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// The deepest nesting of an expression: the tree of its operations is parsed and evaluated recursively.
+pub const EXPRESSION_MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ExpressionError {
+    #[error("The expression cannot be empty")]
+    Empty,
+    #[error("Unexpected character '{0}' at position {1}")]
+    UnexpectedCharacter(char, usize),
+    #[error("Unexpected '{0}' at position {1}")]
+    UnexpectedToken(String, usize),
+    #[error("Unexpected end of the expression: {0} is missing")]
+    UnexpectedEnd(String),
+    #[error("The number '{0}' at position {1} is not valid")]
+    InvalidNumber(String, usize),
+    #[error("The function '{0}' at position {1} is not known")]
+    UnknownFunction(String, usize),
+    #[error("The function '{0}' at position {1} cannot take {2} arguments")]
+    InvalidArguments(String, usize, usize),
+    #[error("The expression is nested deeper than {0} levels")]
+    TooDeep(usize),
+    #[error("The expression must reference at least one variable")]
+    NoVariables,
+    #[error("The variable '{0}' is not defined")]
+    UnknownVariable(String),
+    #[error("The variable '{0}' does not have the same number of values of the others")]
+    SizeMismatch(String),
+    #[error("'{0}' is not finite for the alternative {1}")]
+    NotFinite(String, usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Abs,
+    Exp,
+    Log,
+    Log10,
+    Sqrt,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Variable(Name),
+    Negate(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(Operator),
+    Open,
+    Close,
+    Comma,
+}
+
+impl Operator {
+    fn symbol(&self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+            Operator::Power => '^',
+        }
+    }
+
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Operator::Add => a + b,
+            Operator::Subtract => a - b,
+            Operator::Multiply => a * b,
+            Operator::Divide => a / b,
+            Operator::Power => a.powf(b),
+        }
+    }
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "abs" => Some(Function::Abs),
+            "exp" => Some(Function::Exp),
+            "log" => Some(Function::Log),
+            "log10" => Some(Function::Log10),
+            "sqrt" => Some(Function::Sqrt),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Function::Abs => "abs",
+            Function::Exp => "exp",
+            Function::Log => "log",
+            Function::Log10 => "log10",
+            Function::Sqrt => "sqrt",
+            Function::Min => "min",
+            Function::Max => "max",
+        }
+    }
+
+    fn accepts(&self, arguments: usize) -> bool {
+        match self {
+            Function::Min | Function::Max => arguments >= 2,
+            _ => arguments == 1,
+        }
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(x) => write!(f, "{x}"),
+            Token::Identifier(name) => write!(f, "{name}"),
+            Token::Operator(operator) => write!(f, "{}", operator.symbol()),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the operands that are operations themselves are in parentheses, so that the order is explicit
+        let operand = |node: &Node| match node {
+            Node::Binary(..) | Node::Negate(_) => format!("({node})"),
+            _ => node.to_string(),
+        };
+        match self {
+            Node::Number(x) => write!(f, "{x}"),
+            Node::Variable(name) => write!(f, "{}", name.as_str()),
+            Node::Negate(node) => write!(f, "-{}", operand(node)),
+            Node::Binary(operator, a, b) => {
+                write!(f, "{} {} {}", operand(a), operator.symbol(), operand(b))
+            }
+            Node::Call(function, arguments) => {
+                let arguments: Vec<String> = arguments.iter().map(Node::to_string).collect();
+                write!(f, "{}({})", function.name(), arguments.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// The tokens of the expression, each with the position of its first character.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ExpressionError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let token = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '+' => Token::Operator(Operator::Add),
+            '-' => Token::Operator(Operator::Subtract),
+            '*' => Token::Operator(Operator::Multiply),
+            '/' => Token::Operator(Operator::Divide),
+            '^' => Token::Operator(Operator::Power),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            _ if c.is_alphanumeric() || c == '_' || c == '.' => {
+                // a name can start with a digit, such as `2024_cost`: only a word of digits starts a number
+                let mut end = i;
+                while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+                    end += 1;
+                }
+                if chars[start..end].iter().all(char::is_ascii_digit) {
+                    while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                        i += 1;
+                    }
+                    let text: String = chars[start..i].iter().collect();
+                    let number = text
+                        .parse()
+                        .map_err(|_| ExpressionError::InvalidNumber(text, start))?;
+                    tokens.push((Token::Number(number), start));
+                } else {
+                    i = end;
+                    tokens.push((Token::Identifier(chars[start..i].iter().collect()), start));
+                }
+                continue;
+            }
+            _ => return Err(ExpressionError::UnexpectedCharacter(c, start)),
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser, from the lowest precedence to the highest:
+/// sums, products, negations, powers (right associative) and then numbers, variables, calls and parentheses. Both the
+/// nesting of the calls of the parser and the depth of the nodes it builds are bounded by `EXPRESSION_MAX_DEPTH`.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    /// The nested calls of the parser, from the outermost operand.
+    nesting: usize,
+    /// The depth of the last node built.
+    depth: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(Token, usize)>) -> Self {
+        Self {
+            tokens,
+            next: 0,
+            nesting: 0,
+            depth: 0,
+        }
+    }
+
+    /// Parses a nested operand with `parse`.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Node, ExpressionError>,
+    ) -> Result<Node, ExpressionError> {
+        if self.nesting == EXPRESSION_MAX_DEPTH {
+            return Err(ExpressionError::TooDeep(EXPRESSION_MAX_DEPTH));
+        }
+        self.nesting += 1;
+        let node = parse(self);
+        self.nesting -= 1;
+        node
+    }
+
+    /// A node one level deeper than the deepest of its operands.
+    fn node(&mut self, node: Node, operands: usize) -> Result<Node, ExpressionError> {
+        if operands == EXPRESSION_MAX_DEPTH {
+            return Err(ExpressionError::TooDeep(EXPRESSION_MAX_DEPTH));
+        }
+        self.depth = operands + 1;
+        Ok(node)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    fn unexpected(&self) -> ExpressionError {
+        match self.tokens.get(self.next) {
+            Some((token, position)) => {
+                ExpressionError::UnexpectedToken(token.to_string(), *position)
+            }
+            None => ExpressionError::UnexpectedEnd("an operand".to_string()),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        match self.advance() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((token, position)) => Err(ExpressionError::UnexpectedToken(
+                token.to_string(),
+                position,
+            )),
+            None => Err(ExpressionError::UnexpectedEnd(format!("'{expected}'"))),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.product()?;
+        while let Some(Token::Operator(operator @ (Operator::Add | Operator::Subtract))) =
+            self.peek().cloned()
+        {
+            self.next += 1;
+            let depth = self.depth;
+            let right = self.product()?;
+            let operands = depth.max(self.depth);
+            node = self.node(
+                Node::Binary(operator, Box::new(node), Box::new(right)),
+                operands,
+            )?;
+        }
+        Ok(node)
+    }
+
+    fn product(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.negation()?;
+        while let Some(Token::Operator(operator @ (Operator::Multiply | Operator::Divide))) =
+            self.peek().cloned()
+        {
+            self.next += 1;
+            let depth = self.depth;
+            let right = self.negation()?;
+            let operands = depth.max(self.depth);
+            node = self.node(
+                Node::Binary(operator, Box::new(node), Box::new(right)),
+                operands,
+            )?;
+        }
+        Ok(node)
+    }
+
+    fn negation(&mut self) -> Result<Node, ExpressionError> {
+        if self.peek() == Some(&Token::Operator(Operator::Subtract)) {
+            self.next += 1;
+            let node = self.nested(Parser::negation)?;
+            let depth = self.depth;
+            return self.node(Node::Negate(Box::new(node)), depth);
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Node, ExpressionError> {
+        let base = self.primary()?;
+        if self.peek() == Some(&Token::Operator(Operator::Power)) {
+            self.next += 1;
+            let depth = self.depth;
+            // the exponent can be negative, as in 2 ^ -1
+            let exponent = self.nested(Parser::negation)?;
+            let operands = depth.max(self.depth);
+            return self.node(
+                Node::Binary(Operator::Power, Box::new(base), Box::new(exponent)),
+                operands,
+            );
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        let Some((token, position)) = self.tokens.get(self.next).cloned() else {
+            return Err(self.unexpected());
+        };
+        match token {
+            Token::Number(x) => {
+                self.next += 1;
+                self.node(Node::Number(x), 0)
+            }
+            Token::Open => {
+                self.next += 1;
+                let node = self.nested(Parser::sum)?;
+                self.expect(Token::Close)?;
+                Ok(node)
+            }
+            Token::Identifier(identifier) => {
+                self.next += 1;
+                if self.peek() == Some(&Token::Open) {
+                    self.next += 1;
+                    return self.call(identifier, position);
+                }
+                // NOTE: the identifier has only alphanumeric characters and underscores
+                self.node(Node::Variable(Name::try_from(identifier).unwrap()), 0)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// The arguments of a call, after the opening parenthesis.
+    fn call(&mut self, identifier: String, position: usize) -> Result<Node, ExpressionError> {
+        let Some(function) = Function::from_name(&identifier) else {
+            return Err(ExpressionError::UnknownFunction(identifier, position));
+        };
+        let mut arguments = vec![self.nested(Parser::sum)?];
+        let mut depth = self.depth;
+        while self.peek() == Some(&Token::Comma) {
+            self.next += 1;
+            arguments.push(self.nested(Parser::sum)?);
+            depth = depth.max(self.depth);
+        }
+        self.expect(Token::Close)?;
+        if !function.accepts(arguments.len()) {
+            return Err(ExpressionError::InvalidArguments(
+                identifier,
+                position,
+                arguments.len(),
+            ));
+        }
+        self.node(Node::Call(function, arguments), depth)
+    }
+}
+
+impl Node {
+    fn names<'a>(&'a self, names: &mut BTreeSet<&'a Name>) {
+        match self {
+            Node::Number(_) => {}
+            Node::Variable(name) => {
+                names.insert(name);
+            }
+            Node::Negate(node) => node.names(names),
+            Node::Binary(_, a, b) => {
+                a.names(names);
+                b.names(names);
+            }
+            Node::Call(_, arguments) => arguments.iter().for_each(|node| node.names(names)),
+        }
+    }
+
+    /// The values of the node, for each alternative. The first operation that is not finite is reported, with the
+    /// alternative.
+    fn evaluate(
+        &self,
+        variables: &[Box<dyn VariableProperties>],
+        size: usize,
+    ) -> Result<Array1<f64>, ExpressionError> {
+        let values = match self {
+            Node::Number(x) => Array1::from_elem(size, *x),
+            // NOTE: the caller guarantees that the variables of the expression are defined
+            Node::Variable(name) => variables
+                .iter()
+                .find(|variable| variable.name() == name)
+                .unwrap()
+                .values()
+                .values()
+                .to_owned(),
+            Node::Negate(node) => -node.evaluate(variables, size)?,
+            Node::Binary(operator, a, b) => {
+                let a = a.evaluate(variables, size)?;
+                let b = b.evaluate(variables, size)?;
+                Zip::from(&a)
+                    .and(&b)
+                    .map_collect(|&a, &b| operator.apply(a, b))
+            }
+            Node::Call(function, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|node| node.evaluate(variables, size))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match function {
+                    Function::Abs => arguments[0].mapv(f64::abs),
+                    Function::Exp => arguments[0].mapv(f64::exp),
+                    Function::Log => arguments[0].mapv(f64::ln),
+                    Function::Log10 => arguments[0].mapv(f64::log10),
+                    Function::Sqrt => arguments[0].mapv(f64::sqrt),
+                    Function::Min => arguments[1..].iter().fold(arguments[0].clone(), |a, b| {
+                        Zip::from(&a).and(b).map_collect(|&a, &b| a.min(b))
+                    }),
+                    Function::Max => arguments[1..].iter().fold(arguments[0].clone(), |a, b| {
+                        Zip::from(&a).and(b).map_collect(|&a, &b| a.max(b))
+                    }),
+                }
+            }
+        };
+        match values.iter().position(|x| !x.is_finite()) {
+            Some(alternative) => Err(ExpressionError::NotFinite(self.to_string(), alternative)),
+            None => Ok(values),
+        }
+    }
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err(ExpressionError::Empty);
+        }
+        let mut parser = Parser::new(tokens);
+        let root = parser.sum()?;
+        if parser.peek().is_some() {
+            return Err(parser.unexpected());
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            root,
+        })
+    }
+
+    /// The names of the variables referenced by the expression, without repetitions.
+    pub fn names(&self) -> Vec<&Name> {
+        let mut names = BTreeSet::new();
+        self.root.names(&mut names);
+        names.into_iter().collect()
+    }
+
+    /// The values of the expression for each alternative, from the values of the variables it references.
+    pub fn evaluate(
+        &self,
+        variables: &[Box<dyn VariableProperties>],
+    ) -> Result<Values, ExpressionError> {
+        let mut size = None;
+        for name in self.names() {
+            let Some(variable) = variables.iter().find(|variable| variable.name() == name) else {
+                return Err(ExpressionError::UnknownVariable(name.as_str().to_string()));
+            };
+            let len = variable.values().values().len();
+            if *size.get_or_insert(len) != len {
+                return Err(ExpressionError::SizeMismatch(name.as_str().to_string()));
+            }
+        }
+        let Some(size) = size else {
+            return Err(ExpressionError::NoVariables);
+        };
+        let values = self.root.evaluate(variables, size)?;
+        // NOTE: the values are not empty, and all of them are finite
+        Ok(Values::try_from(values).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Problem;
+    use crate::variables::{VariableAutoscale, VariableInvertedAutoscale};
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_variables() -> Vec<Box<dyn VariableProperties>> {
+        vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("price").unwrap(),
+                Values::try_from(array![100., 300., 200.]).unwrap(),
+            )),
+            Box::new(VariableInvertedAutoscale::new(
+                Name::try_from("capacity").unwrap(),
+                Values::try_from(array![10., 20., 40.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("latency").unwrap(),
+                Values::try_from(array![1., 10., 100.]).unwrap(),
+            )),
+        ]
+    }
+
+    fn evaluate(source: &str) -> Result<Values, ExpressionError> {
+        Expression::parse(source)?.evaluate(&create_test_variables())
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_ulps_eq!(
+            evaluate("price / capacity").unwrap().values(),
+            array![10., 15., 5.]
+        );
+        assert_ulps_eq!(
+            evaluate("log10(latency)").unwrap().values(),
+            array![0., 1., 2.]
+        );
+        assert_ulps_eq!(
+            evaluate("max(price, capacity * 10, 250)").unwrap().values(),
+            array![250., 300., 400.]
+        );
+        assert_ulps_eq!(
+            evaluate("min(price, 150) - 2 * -capacity")
+                .unwrap()
+                .values(),
+            array![120., 190., 230.]
+        );
+    }
+
+    #[test]
+    fn test_precedence() {
+        // powers are right associative and bind tighter than negations
+        assert_ulps_eq!(
+            evaluate("-2 ^ 3 ^ 2 + latency * 0").unwrap().values(),
+            array![-512., -512., -512.]
+        );
+        assert_ulps_eq!(
+            evaluate("(1 + 2) * latency / 2 ^ -1").unwrap().values(),
+            array![6., 60., 600.]
+        );
+        assert_ulps_eq!(
+            evaluate("price - capacity - 10").unwrap().values(),
+            array![80., 270., 150.]
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        let expression = Expression::parse("price / capacity + log(price)").unwrap();
+        assert_eq!(
+            expression.names(),
+            vec![
+                &Name::try_from("capacity").unwrap(),
+                &Name::try_from("price").unwrap()
+            ]
+        );
+        assert_eq!(expression.to_string(), "price / capacity + log(price)");
+    }
+
+    #[test]
+    fn test_names_starting_with_digit() {
+        // a name can start with a digit, a number cannot have letters
+        let expression = Expression::parse("2024_cost * 2 + 3d / .5").unwrap();
+        assert_eq!(
+            expression.names(),
+            vec![
+                &Name::try_from("2024_cost").unwrap(),
+                &Name::try_from("3d").unwrap()
+            ]
+        );
+        let variables: Vec<Box<dyn VariableProperties>> = vec![
+            Box::new(VariableAutoscale::new(
+                Name::try_from("2024_cost").unwrap(),
+                Values::try_from(array![1., 2.]).unwrap(),
+            )),
+            Box::new(VariableAutoscale::new(
+                Name::try_from("3d").unwrap(),
+                Values::try_from(array![1., 0.]).unwrap(),
+            )),
+        ];
+        assert_ulps_eq!(
+            expression.evaluate(&variables).unwrap().values(),
+            array![4., 4.]
+        );
+    }
+
+    #[test]
+    fn test_derived_variable() {
+        // the price per unit of capacity is a criterion of its own
+        let mut variables = create_test_variables();
+        let values = Expression::parse("price / capacity")
+            .unwrap()
+            .evaluate(&variables)
+            .unwrap();
+        variables.push(Box::new(VariableAutoscale::new(
+            Name::try_from("unit_price").unwrap(),
+            values,
+        )));
+        let p = Problem::define(variables).unwrap();
+        assert_eq!(p.names().count(), 4);
+        assert_eq!(p.alternatives(), 3);
+    }
+
+    #[test]
+    fn parse_unexpected_character_failure() {
+        let err = Expression::parse("price % capacity").unwrap_err();
+        assert_eq!(err, ExpressionError::UnexpectedCharacter('%', 6));
+    }
+
+    #[test]
+    fn parse_unexpected_token_failure() {
+        let err = Expression::parse("price capacity").unwrap_err();
+        assert_eq!(
+            err,
+            ExpressionError::UnexpectedToken("capacity".to_string(), 6)
+        );
+        let err = Expression::parse("price * / capacity").unwrap_err();
+        assert_eq!(err, ExpressionError::UnexpectedToken("/".to_string(), 8));
+    }
+
+    #[test]
+    fn parse_unexpected_end_failure() {
+        let err = Expression::parse("price *").unwrap_err();
+        assert_eq!(
+            err,
+            ExpressionError::UnexpectedEnd("an operand".to_string())
+        );
+        let err = Expression::parse("log(price").unwrap_err();
+        assert_eq!(err, ExpressionError::UnexpectedEnd("')'".to_string()));
+    }
+
+    #[test]
+    fn parse_empty_failure() {
+        let err = Expression::parse("  ").unwrap_err();
+        assert_eq!(err, ExpressionError::Empty);
+    }
+
+    #[test]
+    fn parse_invalid_number_failure() {
+        let err = Expression::parse("price * 1.2.3").unwrap_err();
+        assert_eq!(err, ExpressionError::InvalidNumber("1.2.3".to_string(), 8));
+    }
+
+    #[test]
+    fn parse_unknown_function_failure() {
+        let err = Expression::parse("1 + ln(price)").unwrap_err();
+        assert_eq!(err, ExpressionError::UnknownFunction("ln".to_string(), 4));
+    }
+
+    #[test]
+    fn parse_invalid_arguments_failure() {
+        let err = Expression::parse("max(price)").unwrap_err();
+        assert_eq!(
+            err,
+            ExpressionError::InvalidArguments("max".to_string(), 0, 1)
+        );
+        let err = Expression::parse("log(price, 10)").unwrap_err();
+        assert_eq!(
+            err,
+            ExpressionError::InvalidArguments("log".to_string(), 0, 2)
+        );
+    }
+
+    #[test]
+    fn parse_too_deep_failure() {
+        let too_deep = ExpressionError::TooDeep(EXPRESSION_MAX_DEPTH);
+        let negations = "-".repeat(100_000) + "price";
+        assert_eq!(Expression::parse(&negations).unwrap_err(), too_deep);
+        let parentheses = "(".repeat(100_000) + "price" + &")".repeat(100_000);
+        assert_eq!(Expression::parse(&parentheses).unwrap_err(), too_deep);
+        let calls = "abs(".repeat(100_000) + "price" + &")".repeat(100_000);
+        assert_eq!(Expression::parse(&calls).unwrap_err(), too_deep);
+        let powers = "price ^ ".repeat(100_000) + "price";
+        assert_eq!(Expression::parse(&powers).unwrap_err(), too_deep);
+        // the operators in a row build a deep tree too
+        let sums = "price + ".repeat(100_000) + "price";
+        assert_eq!(Expression::parse(&sums).unwrap_err(), too_deep);
+        // the variable and its negations fill all the levels: one more negation is too deep
+        let negations = "-".repeat(EXPRESSION_MAX_DEPTH - 2) + "price";
+        let expression = Expression::parse(&negations).unwrap();
+        assert_ulps_eq!(
+            expression
+                .evaluate(&create_test_variables())
+                .unwrap()
+                .values(),
+            array![100., 300., 200.]
+        );
+        let negations = "-".repeat(EXPRESSION_MAX_DEPTH - 1) + "price";
+        assert!(Expression::parse(&negations).is_ok());
+        let negations = "-".repeat(EXPRESSION_MAX_DEPTH) + "price";
+        assert_eq!(Expression::parse(&negations).unwrap_err(), too_deep);
+    }
+
+    #[test]
+    fn evaluate_unknown_variable_failure() {
+        let err = evaluate("price / seats").unwrap_err();
+        assert_eq!(err, ExpressionError::UnknownVariable("seats".to_string()));
+    }
+
+    #[test]
+    fn evaluate_no_variables_failure() {
+        let err = evaluate("1 + 2").unwrap_err();
+        assert_eq!(err, ExpressionError::NoVariables);
+    }
+
+    #[test]
+    fn evaluate_size_mismatch_failure() {
+        let mut variables = create_test_variables();
+        variables.push(Box::new(VariableAutoscale::new(
+            Name::try_from("seats").unwrap(),
+            Values::try_from(array![1., 2.]).unwrap(),
+        )));
+        let err = Expression::parse("price / seats")
+            .unwrap()
+            .evaluate(&variables)
+            .unwrap_err();
+        assert_eq!(err, ExpressionError::SizeMismatch("seats".to_string()));
+    }
+
+    #[test]
+    fn evaluate_not_finite_failure() {
+        // the innermost operation that fails is reported
+        let err = evaluate("2 * log(capacity - 20)").unwrap_err();
+        assert_eq!(
+            err,
+            ExpressionError::NotFinite("log(capacity - 20)".to_string(), 0)
+        );
+        let err = evaluate("price / (capacity - 20)").unwrap_err();
+        assert_eq!(
+            err,
+            ExpressionError::NotFinite("price / (capacity - 20)".to_string(), 1)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! This module defines trapezoidal fuzzy numbers, the triangular ones being a special case, and their arithmetic.
+use std::ops::{Add, Mul, Sub};
+
+/// A trapezoidal fuzzy number `(a, b, c, d)`: the membership grows from `a` to `b`, is full between `b` and `c` and
+/// decreases from `c` to `d`. A triangular fuzzy number has `b == c`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyNumber {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum FuzzyNumberError {
+    #[error("The fuzzy number cannot have NaN or Infinity")]
+    NoFiniteValues,
+    #[error("The fuzzy number must be ordered: a <= b <= c <= d")]
+    Unordered,
+}
+
+/// How to turn a fuzzy number into a crisp one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defuzzification {
+    /// The center of gravity of the membership function.
+    Centroid,
+    /// The average of the values with full membership.
+    MeanOfMaxima,
+}
+
+impl FuzzyNumber {
+    pub fn trapezoidal(a: f64, b: f64, c: f64, d: f64) -> Result<Self, FuzzyNumberError> {
+        if ![a, b, c, d].iter().all(|x| x.is_finite()) {
+            return Err(FuzzyNumberError::NoFiniteValues);
+        }
+        if !(a <= b && b <= c && c <= d) {
+            return Err(FuzzyNumberError::Unordered);
+        }
+        Ok(Self { a, b, c, d })
+    }
+
+    pub fn triangular(a: f64, b: f64, c: f64) -> Result<Self, FuzzyNumberError> {
+        FuzzyNumber::trapezoidal(a, b, b, c)
+    }
+
+    pub fn crisp(value: f64) -> Result<Self, FuzzyNumberError> {
+        FuzzyNumber::trapezoidal(value, value, value, value)
+    }
+
+    pub fn vertices(&self) -> [f64; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+
+    pub fn is_triangular(&self) -> bool {
+        self.b == self.c
+    }
+
+    pub fn centroid(&self) -> f64 {
+        let denominator = 3. * (self.d + self.c - self.a - self.b);
+        if denominator == 0. {
+            // a crisp number has no area
+            return self.a;
+        }
+        let numerator = self.d.powi(2) + self.c.powi(2) + self.c * self.d
+            - self.a.powi(2)
+            - self.b.powi(2)
+            - self.a * self.b;
+        numerator / denominator
+    }
+
+    pub fn mean_of_maxima(&self) -> f64 {
+        (self.b + self.c) * 0.5
+    }
+
+    pub fn defuzzify(&self, method: Defuzzification) -> f64 {
+        match method {
+            Defuzzification::Centroid => self.centroid(),
+            Defuzzification::MeanOfMaxima => self.mean_of_maxima(),
+        }
+    }
+
+    /// The vertex distance between two fuzzy numbers.
+    pub fn distance(&self, other: &FuzzyNumber) -> f64 {
+        let squares: f64 = self
+            .vertices()
+            .iter()
+            .zip(other.vertices())
+            .map(|(x, y)| (x - y).powi(2))
+            .sum();
+        (squares / 4.).sqrt()
+    }
+
+    /// Applies an increasing linear transformation to all vertices.
+    pub(crate) fn map_increasing(&self, f: impl Fn(f64) -> f64) -> Self {
+        Self {
+            a: f(self.a),
+            b: f(self.b),
+            c: f(self.c),
+            d: f(self.d),
+        }
+    }
+
+    /// Applies a decreasing linear transformation to all vertices, which reverses their order.
+    pub(crate) fn map_decreasing(&self, f: impl Fn(f64) -> f64) -> Self {
+        Self {
+            a: f(self.d),
+            b: f(self.c),
+            c: f(self.b),
+            d: f(self.a),
+        }
+    }
+}
+
+fn min_max(products: [f64; 4]) -> (f64, f64) {
+    products
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &x| {
+            (min.min(x), max.max(x))
+        })
+}
+
+impl Add for FuzzyNumber {
+    type Output = FuzzyNumber;
+
+    fn add(self, other: FuzzyNumber) -> FuzzyNumber {
+        Self {
+            a: self.a + other.a,
+            b: self.b + other.b,
+            c: self.c + other.c,
+            d: self.d + other.d,
+        }
+    }
+}
+
+impl Sub for FuzzyNumber {
+    type Output = FuzzyNumber;
+
+    fn sub(self, other: FuzzyNumber) -> FuzzyNumber {
+        Self {
+            a: self.a - other.d,
+            b: self.b - other.c,
+            c: self.c - other.b,
+            d: self.d - other.a,
+        }
+    }
+}
+
+impl Mul for FuzzyNumber {
+    type Output = FuzzyNumber;
+
+    fn mul(self, other: FuzzyNumber) -> FuzzyNumber {
+        // interval arithmetic on the support (alpha = 0) and on the core (alpha = 1). The result is the usual
+        // trapezoidal approximation of the product.
+        let (a, d) = min_max([
+            self.a * other.a,
+            self.a * other.d,
+            self.d * other.a,
+            self.d * other.d,
+        ]);
+        let (b, c) = min_max([
+            self.b * other.b,
+            self.b * other.c,
+            self.c * other.b,
+            self.c * other.c,
+        ]);
+        Self { a, b, c, d }
+    }
+}
+
+impl Mul<f64> for FuzzyNumber {
+    type Output = FuzzyNumber;
+
+    fn mul(self, scalar: f64) -> FuzzyNumber {
+        if scalar >= 0. {
+            self.map_increasing(|x| x * scalar)
+        } else {
+            self.map_decreasing(|x| x * scalar)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+
+    #[test]
+    fn test_triangular() {
+        let n = FuzzyNumber::triangular(1., 2., 4.).unwrap();
+        assert_eq!(n.vertices(), [1., 2., 2., 4.]);
+        assert!(n.is_triangular());
+    }
+
+    #[test]
+    fn test_unordered_failure() {
+        let err = FuzzyNumber::trapezoidal(1., 3., 2., 4.).unwrap_err();
+        assert_eq!(err, FuzzyNumberError::Unordered);
+    }
+
+    #[test]
+    fn test_not_finite_failure() {
+        let err = FuzzyNumber::triangular(1., f64::NAN, 2.).unwrap_err();
+        assert_eq!(err, FuzzyNumberError::NoFiniteValues);
+    }
+
+    #[test]
+    fn test_defuzzify() {
+        let triangle = FuzzyNumber::triangular(0., 3., 3.).unwrap();
+        assert_ulps_eq!(triangle.defuzzify(Defuzzification::Centroid), 2.);
+        assert_ulps_eq!(triangle.defuzzify(Defuzzification::MeanOfMaxima), 3.);
+
+        let trapezoid = FuzzyNumber::trapezoidal(0., 1., 3., 4.).unwrap();
+        assert_ulps_eq!(trapezoid.centroid(), 2.);
+        assert_ulps_eq!(trapezoid.mean_of_maxima(), 2.);
+
+        let crisp = FuzzyNumber::crisp(5.).unwrap();
+        assert_ulps_eq!(crisp.centroid(), 5.);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let x = FuzzyNumber::triangular(1., 2., 3.).unwrap();
+        let y = FuzzyNumber::trapezoidal(2., 3., 4., 5.).unwrap();
+        assert_eq!((x + y).vertices(), [3., 5., 6., 8.]);
+        assert_eq!((y - x).vertices(), [-1., 1., 2., 4.]);
+        assert_eq!((x * y).vertices(), [2., 6., 8., 15.]);
+        assert_eq!((x * 2.).vertices(), [2., 4., 4., 6.]);
+        assert_eq!((x * -1.).vertices(), [-3., -2., -2., -1.]);
+    }
+
+    #[test]
+    fn test_distance() {
+        let x = FuzzyNumber::triangular(0., 0., 0.).unwrap();
+        let y = FuzzyNumber::triangular(1., 1., 1.).unwrap();
+        assert_ulps_eq!(x.distance(&y), 1.);
+        assert_ulps_eq!(x.distance(&x), 0.);
+    }
+}
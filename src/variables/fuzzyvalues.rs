@@ -0,0 +1,111 @@
+//! This module defines the fuzzy values of a variable.
+use super::Values;
+use super::fuzzynumber::{Defuzzification, FuzzyNumber};
+use ndarray::Array1;
+
+#[derive(Debug, Clone)]
+pub struct FuzzyValues(Vec<FuzzyNumber>);
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum FuzzyValuesError {
+    #[error("The values cannot be empty")]
+    Empty,
+    #[error("The values cannot be NaN or Infinity")]
+    NoFiniteValues,
+}
+
+impl FuzzyValues {
+    pub fn values(&self) -> &[FuzzyNumber] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // NOTE: always false, values are never empty by construction
+        self.0.is_empty()
+    }
+
+    pub fn defuzzify(&self, method: Defuzzification) -> Values {
+        let crisp: Array1<f64> = self.0.iter().map(|n| n.defuzzify(method)).collect();
+        Values::try_from(crisp).unwrap()
+    }
+}
+
+impl TryFrom<Vec<FuzzyNumber>> for FuzzyValues {
+    type Error = FuzzyValuesError;
+
+    fn try_from(values: Vec<FuzzyNumber>) -> Result<Self, Self::Error> {
+        if values.is_empty() {
+            return Err(FuzzyValuesError::Empty);
+        }
+        // the arithmetic of the fuzzy numbers can overflow their vertices
+        let any_not_finite = values
+            .iter()
+            .any(|n| n.vertices().iter().any(|x| !x.is_finite()));
+        if any_not_finite {
+            return Err(FuzzyValuesError::NoFiniteValues);
+        }
+        Ok(FuzzyValues(values))
+    }
+}
+
+impl From<Values> for FuzzyValues {
+    fn from(values: Values) -> Self {
+        // a crisp value is a degenerate fuzzy number. Values are always finite, so the conversion cannot fail.
+        FuzzyValues(
+            values
+                .values()
+                .iter()
+                .map(|&x| FuzzyNumber::crisp(x).unwrap())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_try_from_numbers() {
+        let v = FuzzyValues::try_from(vec![
+            FuzzyNumber::triangular(0., 3., 3.).unwrap(),
+            FuzzyNumber::trapezoidal(0., 1., 3., 4.).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(v.len(), 2);
+        assert_ulps_eq!(
+            v.defuzzify(Defuzzification::Centroid).values(),
+            array![2., 2.]
+        );
+        assert_ulps_eq!(
+            v.defuzzify(Defuzzification::MeanOfMaxima).values(),
+            array![3., 2.]
+        );
+    }
+
+    #[test]
+    fn test_from_crisp_values() {
+        let v = FuzzyValues::from(Values::try_from(array![1., 2.]).unwrap());
+        assert_eq!(v.values()[1].vertices(), [2., 2., 2., 2.]);
+    }
+
+    #[test]
+    fn test_try_from_empty_failure() {
+        let err = FuzzyValues::try_from(vec![]).unwrap_err();
+        assert_eq!(err, FuzzyValuesError::Empty);
+    }
+
+    #[test]
+    fn test_try_from_overflowing_numbers_failure() {
+        let huge = FuzzyNumber::crisp(f64::MAX).unwrap();
+        let err = FuzzyValues::try_from(vec![huge + huge]).unwrap_err();
+        assert_eq!(err, FuzzyValuesError::NoFiniteValues);
+    }
+}
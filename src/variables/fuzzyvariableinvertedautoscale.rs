@@ -0,0 +1,58 @@
+//! This module implements the inverted autoscaled fuzzy vectorized variables.
+use super::FuzzyValues;
+use super::FuzzyVariableProperties;
+use super::Name;
+use super::scaling::autorescale_fuzzy;
+
+#[derive(Debug)]
+pub struct FuzzyVariableInvertedAutoscale {
+    name: Name,
+    values: FuzzyValues,
+}
+
+impl FuzzyVariableInvertedAutoscale {
+    pub fn new(name: Name, values: FuzzyValues) -> Self {
+        Self { name, values }
+    }
+}
+
+impl FuzzyVariableProperties for FuzzyVariableInvertedAutoscale {
+    fn name(&self) -> &Name {
+        &self.name
+    }
+
+    fn values(&self) -> &FuzzyValues {
+        &self.values
+    }
+
+    fn rescale(&self) -> FuzzyValues {
+        autorescale_fuzzy(self.values(), true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::FuzzyNumber;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn create_variable_with_values_and_rescale() {
+        let var = FuzzyVariableInvertedAutoscale::new(
+            Name::try_from("x").unwrap(),
+            FuzzyValues::try_from(vec![
+                FuzzyNumber::triangular(0., 1., 2.).unwrap(),
+                FuzzyNumber::trapezoidal(2., 3., 3., 4.).unwrap(),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(var.name(), "x");
+        let scaled: Vec<[f64; 4]> = var
+            .rescale()
+            .values()
+            .iter()
+            .map(|n| n.vertices())
+            .collect();
+        assert_eq!(scaled, vec![[0.5, 0.75, 0.75, 1.], [0., 0.25, 0.25, 0.5]]);
+    }
+}
@@ -0,0 +1,119 @@
+//! This module defines the interval values of a variable: each value is an estimate between a lower and an upper bound.
+use super::Values;
+use super::values::ValuesError;
+use ndarray::{Array1, ArrayView1, Zip};
+
+#[derive(Debug, Clone)]
+pub struct IntervalValues {
+    lower: Values,
+    upper: Values,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum IntervalValuesError {
+    #[error("Invalid bounds: {0}")]
+    InvalidBounds(#[from] ValuesError),
+    #[error("Lower and upper bounds must have the same number of values")]
+    BoundsSizeMismatch,
+    #[error("Each lower bound must be less than or equal to its upper bound")]
+    InvertedBounds,
+}
+
+impl IntervalValues {
+    pub fn new(lower: Values, upper: Values) -> Result<Self, IntervalValuesError> {
+        if lower.values().len() != upper.values().len() {
+            return Err(IntervalValuesError::BoundsSizeMismatch);
+        }
+        let any_inverted = Zip::from(lower.values())
+            .and(upper.values())
+            .fold(false, |acc, &l, &u| acc || l > u);
+        if any_inverted {
+            return Err(IntervalValuesError::InvertedBounds);
+        }
+        Ok(Self { lower, upper })
+    }
+
+    pub fn lower(&self) -> ArrayView1<'_, f64> {
+        self.lower.values()
+    }
+
+    pub fn upper(&self) -> ArrayView1<'_, f64> {
+        self.upper.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lower.values().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // NOTE: always false, values are never empty by construction
+        self.len() == 0
+    }
+
+    pub fn midpoints(&self) -> Values {
+        let midpoints = (&self.lower() + &self.upper()) * 0.5;
+        Values::try_from(midpoints).unwrap()
+    }
+}
+
+impl From<Values> for IntervalValues {
+    fn from(values: Values) -> Self {
+        // a crisp value is a degenerate interval
+        Self {
+            lower: values.clone(),
+            upper: values,
+        }
+    }
+}
+
+impl TryFrom<(Array1<f64>, Array1<f64>)> for IntervalValues {
+    type Error = IntervalValuesError;
+
+    fn try_from(bounds: (Array1<f64>, Array1<f64>)) -> Result<Self, Self::Error> {
+        let (lower, upper) = bounds;
+        IntervalValues::new(Values::try_from(lower)?, Values::try_from(upper)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_try_from_bounds() {
+        let v = IntervalValues::try_from((array![8., 1.], array![12., 1.])).unwrap();
+        assert_eq!(v.lower(), array![8., 1.]);
+        assert_eq!(v.upper(), array![12., 1.]);
+        assert_eq!(v.len(), 2);
+        assert_eq!(v.midpoints().values(), array![10., 1.]);
+    }
+
+    #[test]
+    fn test_from_crisp_values() {
+        let v = IntervalValues::from(Values::try_from(array![1., 2.]).unwrap());
+        assert_eq!(v.lower(), v.upper());
+    }
+
+    #[test]
+    fn test_try_from_size_mismatch_failure() {
+        let err = IntervalValues::try_from((array![1., 2.], array![3.])).unwrap_err();
+        assert_eq!(err, IntervalValuesError::BoundsSizeMismatch);
+    }
+
+    #[test]
+    fn test_try_from_inverted_bounds_failure() {
+        let err = IntervalValues::try_from((array![1., 5.], array![3., 4.])).unwrap_err();
+        assert_eq!(err, IntervalValuesError::InvertedBounds);
+    }
+
+    #[test]
+    fn test_try_from_invalid_bounds_failure() {
+        let err = IntervalValues::try_from((array![f64::NAN], array![3.])).unwrap_err();
+        assert_eq!(
+            err,
+            IntervalValuesError::InvalidBounds(ValuesError::NoFiniteValues)
+        );
+    }
+}
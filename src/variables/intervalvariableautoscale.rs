@@ -0,0 +1,51 @@
+//! This module implements the autoscaled interval vectorized variables.
+use super::IntervalValues;
+use super::IntervalVariableProperties;
+use super::Name;
+use super::scaling::autorescale_interval;
+
+#[derive(Debug)]
+pub struct IntervalVariableAutoscale {
+    name: Name,
+    values: IntervalValues,
+}
+
+impl IntervalVariableAutoscale {
+    pub fn new(name: Name, values: IntervalValues) -> Self {
+        Self { name, values }
+    }
+}
+
+impl IntervalVariableProperties for IntervalVariableAutoscale {
+    fn name(&self) -> &Name {
+        &self.name
+    }
+
+    fn values(&self) -> &IntervalValues {
+        &self.values
+    }
+
+    fn rescale(&self) -> IntervalValues {
+        autorescale_interval(self.values(), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn create_variable_with_values_and_rescale() {
+        let var = IntervalVariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            IntervalValues::try_from((array![8., 10., 4.], array![12., 10., 6.])).unwrap(),
+        );
+        assert_eq!(var.name(), "x");
+        let scaled = var.rescale();
+        assert_ulps_eq!(scaled.lower(), array![0.5, 0.75, 0.]);
+        assert_ulps_eq!(scaled.upper(), array![1., 0.75, 0.25]);
+    }
+}
@@ -0,0 +1,128 @@
+//! This module defines linguistic scales: verbal judgements mapped to fuzzy numbers.
+use super::FuzzyValues;
+use super::fuzzynumber::FuzzyNumber;
+
+#[derive(Debug, Clone)]
+pub struct LinguisticScale {
+    terms: Vec<(String, FuzzyNumber)>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum LinguisticScaleError {
+    #[error("The scale cannot be empty")]
+    Empty,
+    #[error("The term '{0}' is defined more than once")]
+    DuplicateTerm(String),
+    #[error("The term '{0}' is not part of the scale")]
+    UnknownTerm(String),
+    #[error("At least one judgement is required")]
+    NoJudgements,
+}
+
+impl LinguisticScale {
+    /// Defines a custom scale. Terms are kept in the given order, usually from the worst to the best.
+    pub fn new(terms: Vec<(&str, FuzzyNumber)>) -> Result<Self, LinguisticScaleError> {
+        if terms.is_empty() {
+            return Err(LinguisticScaleError::Empty);
+        }
+        let mut scale = Self { terms: Vec::new() };
+        for (term, number) in terms {
+            if scale.get(term).is_ok() {
+                return Err(LinguisticScaleError::DuplicateTerm(term.to_string()));
+            }
+            scale.terms.push((term.to_string(), number));
+        }
+        Ok(scale)
+    }
+
+    /// The common five-point scale, from "very poor" to "very good", with triangular fuzzy numbers in [0, 1].
+    pub fn five_point() -> Self {
+        let triangular = |a, b, c| FuzzyNumber::triangular(a, b, c).unwrap();
+        Self::new(vec![
+            ("very poor", triangular(0., 0., 0.25)),
+            ("poor", triangular(0., 0.25, 0.5)),
+            ("fair", triangular(0.25, 0.5, 0.75)),
+            ("good", triangular(0.5, 0.75, 1.)),
+            ("very good", triangular(0.75, 1., 1.)),
+        ])
+        .unwrap()
+    }
+
+    pub fn terms(&self) -> impl Iterator<Item = &str> {
+        self.terms.iter().map(|(term, _)| term.as_str())
+    }
+
+    pub fn get(&self, term: &str) -> Result<FuzzyNumber, LinguisticScaleError> {
+        self.terms
+            .iter()
+            .find(|(t, _)| t == term)
+            .map(|(_, number)| *number)
+            .ok_or_else(|| LinguisticScaleError::UnknownTerm(term.to_string()))
+    }
+
+    /// Translates the judgements of each alternative to fuzzy values.
+    pub fn values(&self, judgements: &[&str]) -> Result<FuzzyValues, LinguisticScaleError> {
+        let numbers = judgements
+            .iter()
+            .map(|term| self.get(term))
+            .collect::<Result<Vec<_>, _>>()?;
+        FuzzyValues::try_from(numbers).map_err(|_| LinguisticScaleError::NoJudgements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_five_point_scale() {
+        let scale = LinguisticScale::five_point();
+        assert_eq!(scale.terms().count(), 5);
+        assert_eq!(
+            scale.get("good").unwrap(),
+            FuzzyNumber::triangular(0.5, 0.75, 1.).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_scale_to_values() {
+        let scale = LinguisticScale::new(vec![
+            ("low", FuzzyNumber::triangular(0., 0., 5.).unwrap()),
+            ("high", FuzzyNumber::triangular(5., 10., 10.).unwrap()),
+        ])
+        .unwrap();
+        let values = scale.values(&["high", "low", "high"]).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.values()[1], scale.get("low").unwrap());
+    }
+
+    #[test]
+    fn test_unknown_term_failure() {
+        let err = LinguisticScale::five_point()
+            .values(&["good", "excellent"])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            LinguisticScaleError::UnknownTerm("excellent".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_judgements_failure() {
+        let err = LinguisticScale::five_point().values(&[]).unwrap_err();
+        assert_eq!(err, LinguisticScaleError::NoJudgements);
+    }
+
+    #[test]
+    fn test_empty_scale_failure() {
+        let err = LinguisticScale::new(vec![]).unwrap_err();
+        assert_eq!(err, LinguisticScaleError::Empty);
+    }
+
+    #[test]
+    fn test_duplicate_term_failure() {
+        let n = FuzzyNumber::crisp(1.).unwrap();
+        let err = LinguisticScale::new(vec![("a", n), ("a", n)]).unwrap_err();
+        assert_eq!(err, LinguisticScaleError::DuplicateTerm("a".to_string()));
+    }
+}
@@ -0,0 +1,180 @@
+//! This module defines the robust scaling of the values of a variable, on quantile bounds.
+//!
+//! The bounds replace the minimum and the maximum of the min-max scaling: the values beyond them are clipped to the
+//! bounds, so that a single outlier does not squash all the other values to one end of the scale.
+use super::Values;
+use ndarray::Axis;
+use ndarray_stats::QuantileExt;
+use ndarray_stats::interpolate::Linear;
+use noisy_float::types::n64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clipping {
+    /// The bounds are the quantiles, interpolated between the values.
+    Quantile,
+    /// The bounds are the values at the nearest ranks of the quantiles: the values beyond are winsorized. A sample too
+    /// small to have a rank below the lower quantile (or above the upper one) keeps its extreme values.
+    Winsorize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RobustScaling {
+    lower: f64,
+    upper: f64,
+    clipping: Clipping,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RobustScalingError {
+    #[error("The quantiles must be in [0, 1], with the lower one below the upper one")]
+    InvalidQuantiles,
+}
+
+impl Default for RobustScaling {
+    /// Clipping to the 5th and the 95th percentiles.
+    fn default() -> Self {
+        Self {
+            lower: 0.05,
+            upper: 0.95,
+            clipping: Clipping::Quantile,
+        }
+    }
+}
+
+impl RobustScaling {
+    /// Clipping to the lower and the upper quantiles, such as 0.05 and 0.95.
+    pub fn new(lower: f64, upper: f64) -> Result<Self, RobustScalingError> {
+        if !(0. <= lower && lower < upper && upper <= 1.) {
+            return Err(RobustScalingError::InvalidQuantiles);
+        }
+        Ok(Self {
+            lower,
+            upper,
+            clipping: Clipping::Quantile,
+        })
+    }
+
+    pub fn with_clipping(mut self, clipping: Clipping) -> Self {
+        self.clipping = clipping;
+        self
+    }
+
+    pub fn clipping(&self) -> Clipping {
+        self.clipping
+    }
+
+    /// The lower and the upper bounds of the values.
+    pub fn bounds(&self, v: &Values) -> (f64, f64) {
+        let mut values = v.values().to_owned();
+        // NOTE: the values are never empty and always finite, and the quantiles are in [0, 1]
+        match self.clipping {
+            Clipping::Quantile => (
+                values
+                    .quantile_axis_skipnan_mut(Axis(0), n64(self.lower), &Linear)
+                    .unwrap()
+                    .into_scalar(),
+                values
+                    .quantile_axis_skipnan_mut(Axis(0), n64(self.upper), &Linear)
+                    .unwrap()
+                    .into_scalar(),
+            ),
+            Clipping::Winsorize => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                // the number of values below the lower quantile and above the upper one, with some slack for the
+                // rounding of the products
+                let size = sorted.len() as f64;
+                let below = (self.lower * size + 1e-9).floor() as usize;
+                let above = (size - self.upper * size + 1e-9).floor() as usize;
+                let lower = below.min(sorted.len() - 1);
+                let upper = (sorted.len() - 1).saturating_sub(above).max(lower);
+                (sorted[lower], sorted[upper])
+            }
+        }
+    }
+
+    /// The indexes of the values beyond the bounds.
+    pub fn clipped(&self, v: &Values) -> Vec<usize> {
+        let (lower, upper) = self.bounds(v);
+        v.values()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &x)| x < lower || x > upper)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_values() -> Values {
+        // a single vendor quoting 25 times the others
+        Values::try_from(array![1., 2., 3., 4., 100.]).unwrap()
+    }
+
+    #[test]
+    fn test_quantile_bounds() {
+        let scaling = RobustScaling::new(0.05, 0.95).unwrap();
+        let (lower, upper) = scaling.bounds(&create_test_values());
+        assert_ulps_eq!(lower, 1.2);
+        assert_ulps_eq!(upper, 80.8);
+        assert_eq!(scaling.clipped(&create_test_values()), vec![0, 4]);
+    }
+
+    #[test]
+    fn test_winsorize_bounds() {
+        let scaling = RobustScaling::new(0.2, 0.8)
+            .unwrap()
+            .with_clipping(Clipping::Winsorize);
+        let (lower, upper) = scaling.bounds(&create_test_values());
+        assert_ulps_eq!(lower, 2.);
+        assert_ulps_eq!(upper, 4.);
+        assert_eq!(scaling.clipped(&create_test_values()), vec![0, 4]);
+        // one value in twenty is beyond each of the default quantiles
+        let mut values: Vec<f64> = (1..20).map(f64::from).collect();
+        values.push(100.);
+        let values = Values::try_from(ndarray::Array1::from(values)).unwrap();
+        let scaling = RobustScaling::default().with_clipping(Clipping::Winsorize);
+        assert_eq!(scaling.bounds(&values), (2., 19.));
+        assert_eq!(scaling.clipped(&values), vec![0, 19]);
+    }
+
+    #[test]
+    fn test_winsorize_small_sample() {
+        // five values have no rank beyond the 5th and the 95th percentiles: even the outlier is kept
+        let scaling = RobustScaling::default().with_clipping(Clipping::Winsorize);
+        assert_eq!(scaling.bounds(&create_test_values()), (1., 100.));
+        assert!(scaling.clipped(&create_test_values()).is_empty());
+        let values = Values::try_from(array![1., 2., 3., 4.]).unwrap();
+        assert_eq!(scaling.bounds(&values), (1., 4.));
+        assert!(scaling.clipped(&values).is_empty());
+    }
+
+    #[test]
+    fn test_full_range() {
+        let scaling = RobustScaling::new(0., 1.).unwrap();
+        assert_eq!(scaling.bounds(&create_test_values()), (1., 100.));
+        assert!(scaling.clipped(&create_test_values()).is_empty());
+    }
+
+    #[test]
+    fn new_invalid_quantiles_failure() {
+        assert_eq!(
+            RobustScaling::new(0.5, 0.5).unwrap_err(),
+            RobustScalingError::InvalidQuantiles
+        );
+        assert_eq!(
+            RobustScaling::new(-0.1, 0.9).unwrap_err(),
+            RobustScalingError::InvalidQuantiles
+        );
+        assert_eq!(
+            RobustScaling::new(0.1, f64::NAN).unwrap_err(),
+            RobustScalingError::InvalidQuantiles
+        );
+    }
+}
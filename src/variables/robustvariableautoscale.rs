@@ -0,0 +1,107 @@
+//! This module implements the robust autoscaled vectorized variables, on quantile bounds.
+use super::Name;
+use super::RobustScaling;
+use super::Values;
+use super::VariableProperties;
+use super::scaling::robust_rescale_vector;
+
+#[derive(Debug)]
+pub struct RobustVariableAutoscale {
+    name: Name,
+    values: Values,
+    scaling: RobustScaling,
+}
+
+impl RobustVariableAutoscale {
+    pub fn new(name: Name, values: Values, scaling: RobustScaling) -> Self {
+        Self {
+            name,
+            values,
+            scaling,
+        }
+    }
+
+    /// The indexes of the values clipped to the bounds.
+    pub fn clipped(&self) -> Vec<usize> {
+        self.scaling.clipped(&self.values)
+    }
+}
+
+impl VariableProperties for RobustVariableAutoscale {
+    fn name(&self) -> &Name {
+        &self.name
+    }
+
+    fn values(&self) -> &Values {
+        &self.values
+    }
+
+    /// The quantile bounds of the values.
+    fn bounds(&self) -> (f64, f64) {
+        self.scaling.bounds(&self.values)
+    }
+
+    fn rescale(&self) -> Values {
+        let (lower, upper) = self.bounds();
+        robust_rescale_vector(self.values(), lower, upper, false)
+    }
+
+    fn higher_is_better(&self) -> bool {
+        false
+    }
+
+    fn with_values(&self, values: Values) -> Option<Box<dyn VariableProperties>> {
+        Some(Box::new(RobustVariableAutoscale::new(
+            self.name.clone(),
+            values,
+            self.scaling,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::Clipping;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    fn create_test_variable() -> RobustVariableAutoscale {
+        RobustVariableAutoscale::new(
+            Name::try_from("price").unwrap(),
+            Values::try_from(array![1., 2., 3., 4., 100.]).unwrap(),
+            RobustScaling::new(0.2, 0.8)
+                .unwrap()
+                .with_clipping(Clipping::Winsorize),
+        )
+    }
+
+    #[test]
+    fn test_rescale() {
+        let var = create_test_variable();
+        assert_eq!(var.bounds(), (2., 4.));
+        assert_eq!(var.clipped(), vec![0, 4]);
+        assert_ulps_eq!(var.rescale().values(), array![0., 0., 0.5, 1., 1.]);
+        assert!(!var.higher_is_better());
+    }
+
+    #[test]
+    fn test_small_sample_not_clipped() {
+        // four clean values have no rank beyond the 5th and the 95th percentiles
+        let var = RobustVariableAutoscale::new(
+            Name::try_from("price").unwrap(),
+            Values::try_from(array![1., 2., 3., 4.]).unwrap(),
+            RobustScaling::default().with_clipping(Clipping::Winsorize),
+        );
+        assert_eq!(var.bounds(), (1., 4.));
+        assert!(var.clipped().is_empty());
+        assert_ulps_eq!(var.rescale().values(), array![0., 1. / 3., 2. / 3., 1.]);
+        // the new values keep the scaling, not the bounds
+        let var = create_test_variable()
+            .with_values(Values::try_from(array![1., 2., 3.]).unwrap())
+            .unwrap();
+        assert_eq!(var.name().as_str(), "price");
+        assert_ulps_eq!(var.rescale().values(), array![0., 0.5, 1.]);
+    }
+}
@@ -1,4 +1,7 @@
 //! This module implements the low level computations for vectorized variables.
+use super::fuzzynumber::FuzzyNumber;
+use super::fuzzyvalues::FuzzyValues;
+use super::intervalvalues::IntervalValues;
 use super::values::Values;
 use ndarray::{Array1, ArrayView1};
 use ndarray_stats::QuantileExt;
@@ -21,17 +24,33 @@ pub fn rescale_and_invert_vector(
     (&rescale_vector(v, shift, scaling_factor) - ones) * -1.0
 }
 
+/// The factor that keeps the range between `lower` and `upper` finite, with the bounds multiplied by it: the values are
+/// halved when the range overflows, and kept as they are otherwise.
+pub(crate) fn halve_huge_range(lower: f64, upper: f64) -> (f64, f64, f64) {
+    if (upper - lower).is_finite() {
+        (1., lower, upper)
+    } else {
+        (0.5, lower / 2., upper / 2.)
+    }
+}
+
 pub fn autorescale_vector(v: &Values, inverted: bool) -> Values {
     let values = v.values();
-    let shift = values.min().unwrap();
-    let max_value = values.max().unwrap();
+    let (factor, shift, max_value) =
+        halve_huge_range(*values.min().unwrap(), *values.max().unwrap());
+    let values = &values * factor;
     let scaling_factor = 1.0 / (max_value - shift);
 
     if scaling_factor.is_finite() {
         if inverted {
-            Values::try_from(rescale_and_invert_vector(values, *shift, scaling_factor)).unwrap()
+            Values::try_from(rescale_and_invert_vector(
+                values.view(),
+                shift,
+                scaling_factor,
+            ))
+            .unwrap()
         } else {
-            Values::try_from(rescale_vector(values, *shift, scaling_factor)).unwrap()
+            Values::try_from(rescale_vector(values.view(), shift, scaling_factor)).unwrap()
         }
     } else {
         // Handle special cases where all values are the same (min == max). The result is always a zero-vetor.
@@ -39,6 +58,104 @@ pub fn autorescale_vector(v: &Values, inverted: bool) -> Values {
     }
 }
 
+pub fn robust_rescale_vector(v: &Values, lower: f64, upper: f64, inverted: bool) -> Values {
+    // the values beyond the bounds are clipped: they are rescaled to 0 or 1, like the bounds
+    let values = v.values().mapv(|x| x.clamp(lower, upper));
+    let (factor, lower, upper) = halve_huge_range(lower, upper);
+    let values = values * factor;
+    let scaling_factor = 1.0 / (upper - lower);
+
+    if scaling_factor.is_finite() {
+        if inverted {
+            Values::try_from(rescale_and_invert_vector(
+                values.view(),
+                lower,
+                scaling_factor,
+            ))
+            .unwrap()
+        } else {
+            Values::try_from(rescale_vector(values.view(), lower, scaling_factor)).unwrap()
+        }
+    } else {
+        // both bounds are the same, as the values within them
+        Values::try_from(Array1::zeros(values.len())).unwrap()
+    }
+}
+
+pub fn normalize_vector(v: &Values) -> Values {
+    // vector normalization: each value is divided by the euclidean norm of all the values
+    let values = v.values();
+    let highest = values.fold(0., |a: f64, &b| a.max(b.abs()));
+    if highest > 0. {
+        // like `hypot`, the values are divided by the highest one first, so that the squares do not overflow
+        let values = &values / highest;
+        let norm = values.dot(&values).sqrt();
+        Values::try_from(&values / norm).unwrap()
+    } else {
+        // all the values are zero, and so they stay
+        Values::try_from(Array1::zeros(values.len())).unwrap()
+    }
+}
+
+pub fn autorescale_interval(v: &IntervalValues, inverted: bool) -> IntervalValues {
+    // Both bounds share the same scale: the range goes from the smallest lower bound to the biggest upper bound.
+    let (factor, shift, max_value) =
+        halve_huge_range(*v.lower().min().unwrap(), *v.upper().max().unwrap());
+    let (lower, upper) = (&v.lower() * factor, &v.upper() * factor);
+    let scaling_factor = 1.0 / (max_value - shift);
+
+    if scaling_factor.is_finite() {
+        let (lower, upper) = if inverted {
+            // inverting swaps the bounds: the upper bound becomes the lowest rescaled value
+            (
+                rescale_and_invert_vector(upper.view(), shift, scaling_factor),
+                rescale_and_invert_vector(lower.view(), shift, scaling_factor),
+            )
+        } else {
+            (
+                rescale_vector(lower.view(), shift, scaling_factor),
+                rescale_vector(upper.view(), shift, scaling_factor),
+            )
+        };
+        IntervalValues::try_from((lower, upper)).unwrap()
+    } else {
+        // Same special case of autorescale_vector: all bounds are the same (min == max).
+        IntervalValues::from(Values::try_from(Array1::zeros(v.len())).unwrap())
+    }
+}
+
+pub fn autorescale_fuzzy(v: &FuzzyValues, inverted: bool) -> FuzzyValues {
+    // The range goes from the smallest to the biggest vertex of all the fuzzy numbers.
+    let (min, max) = v
+        .values()
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), n| {
+            let [a, _, _, d] = n.vertices();
+            (min.min(a), max.max(d))
+        });
+    let (factor, shift, max_value) = halve_huge_range(min, max);
+    let scaling_factor = 1.0 / (max_value - shift);
+
+    let rescaled: Vec<FuzzyNumber> = if !scaling_factor.is_finite() {
+        // Same special case of autorescale_vector: all vertices are the same (min == max).
+        v.values()
+            .iter()
+            .map(|n| n.map_increasing(|_| 0.))
+            .collect()
+    } else if inverted {
+        v.values()
+            .iter()
+            .map(|n| n.map_decreasing(|x| 1. - (x * factor - shift) * scaling_factor))
+            .collect()
+    } else {
+        v.values()
+            .iter()
+            .map(|n| n.map_increasing(|x| (x * factor - shift) * scaling_factor))
+            .collect()
+    };
+    FuzzyValues::try_from(rescaled).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +163,35 @@ mod tests {
     use ndarray::array;
     use std::convert::TryFrom;
 
+    #[test]
+    fn normalize_vector_success() {
+        let v = Values::try_from(array![3., 4.]).unwrap();
+        assert_ulps_eq!(normalize_vector(&v).values(), array![0.6, 0.8]);
+        let huge = Values::try_from(array![3e300, 4e300]).unwrap();
+        assert_ulps_eq!(normalize_vector(&huge).values(), array![0.6, 0.8]);
+        let tiny = Values::try_from(array![3e-300, -4e-300]).unwrap();
+        assert_ulps_eq!(normalize_vector(&tiny).values(), array![0.6, -0.8]);
+        let zeros = Values::try_from(array![0., 0.]).unwrap();
+        assert_ulps_eq!(normalize_vector(&zeros).values(), array![0., 0.]);
+    }
+
+    #[test]
+    fn robust_rescale_vector_clips() {
+        let v = Values::try_from(array![1., 2., 3., 4., 100.]).unwrap();
+        assert_ulps_eq!(
+            robust_rescale_vector(&v, 2., 4., false).values(),
+            array![0., 0., 0.5, 1., 1.]
+        );
+        assert_ulps_eq!(
+            robust_rescale_vector(&v, 2., 4., true).values(),
+            array![1., 1., 0.5, 0., 0.]
+        );
+        assert_ulps_eq!(
+            robust_rescale_vector(&v, 3., 3., false).values(),
+            array![0., 0., 0., 0., 0.]
+        );
+    }
+
     #[test]
     fn rescale_vector_size_one() {
         let v = Values::try_from(array![1.]).unwrap();
@@ -62,6 +208,17 @@ mod tests {
         assert_ulps_eq!(autorescale_vector(&v, true).values(), expected_scaled);
     }
 
+    #[test]
+    fn rescale_vector_huge_range() {
+        let v = Values::try_from(array![-f64::MAX, 0., f64::MAX]).unwrap();
+        assert_ulps_eq!(autorescale_vector(&v, false).values(), array![0., 0.5, 1.]);
+        assert_ulps_eq!(autorescale_vector(&v, true).values(), array![1., 0.5, 0.]);
+        assert_ulps_eq!(
+            robust_rescale_vector(&v, -f64::MAX, f64::MAX, false).values(),
+            array![0., 0.5, 1.]
+        );
+    }
+
     #[test]
     fn rescale_vector_pos_already_scaled() {
         let v = Values::try_from(array![0., 0.5, 1.]).unwrap();
@@ -110,4 +267,73 @@ mod tests {
             array![1., 0.9166666666666666, 0.5, 0.08333333333333337, 0.]
         );
     }
+
+    #[test]
+    fn autorescale_interval_full() {
+        let v = IntervalValues::try_from((array![8., 10., 4.], array![12., 10., 6.])).unwrap();
+        let scaled = autorescale_interval(&v, false);
+        assert_ulps_eq!(scaled.lower(), array![0.5, 0.75, 0.]);
+        assert_ulps_eq!(scaled.upper(), array![1., 0.75, 0.25]);
+
+        let scaled = autorescale_interval(&v, true);
+        assert_ulps_eq!(scaled.lower(), array![0., 0.25, 0.75]);
+        assert_ulps_eq!(scaled.upper(), array![0.5, 0.25, 1.]);
+    }
+
+    #[test]
+    fn autorescale_interval_all_values_the_same() {
+        let v = IntervalValues::try_from((array![3., 3.], array![3., 3.])).unwrap();
+        let scaled = autorescale_interval(&v, true);
+        assert_ulps_eq!(scaled.lower(), array![0., 0.]);
+        assert_ulps_eq!(scaled.upper(), array![0., 0.]);
+    }
+
+    #[test]
+    fn autorescale_interval_huge_range() {
+        let v = IntervalValues::try_from((array![-f64::MAX, 0.], array![0., f64::MAX])).unwrap();
+        let scaled = autorescale_interval(&v, false);
+        assert_ulps_eq!(scaled.lower(), array![0., 0.5]);
+        assert_ulps_eq!(scaled.upper(), array![0.5, 1.]);
+        let scaled = autorescale_interval(&v, true);
+        assert_ulps_eq!(scaled.lower(), array![0.5, 0.]);
+        assert_ulps_eq!(scaled.upper(), array![1., 0.5]);
+    }
+
+    #[test]
+    fn autorescale_fuzzy_full() {
+        let v = FuzzyValues::try_from(vec![
+            FuzzyNumber::triangular(0., 1., 2.).unwrap(),
+            FuzzyNumber::trapezoidal(2., 3., 3., 4.).unwrap(),
+        ])
+        .unwrap();
+        let scaled = autorescale_fuzzy(&v, false);
+        assert_eq!(scaled.values()[0].vertices(), [0., 0.25, 0.25, 0.5]);
+        assert_eq!(scaled.values()[1].vertices(), [0.5, 0.75, 0.75, 1.]);
+
+        let scaled = autorescale_fuzzy(&v, true);
+        assert_eq!(scaled.values()[0].vertices(), [0.5, 0.75, 0.75, 1.]);
+        assert_eq!(scaled.values()[1].vertices(), [0., 0.25, 0.25, 0.5]);
+    }
+
+    #[test]
+    fn autorescale_fuzzy_huge_range() {
+        let v = FuzzyValues::try_from(vec![
+            FuzzyNumber::triangular(-f64::MAX, -f64::MAX, 0.).unwrap(),
+            FuzzyNumber::triangular(0., f64::MAX, f64::MAX).unwrap(),
+        ])
+        .unwrap();
+        let scaled = autorescale_fuzzy(&v, false);
+        assert_ulps_eq!(&scaled.values()[0].vertices()[..], &[0., 0., 0., 0.5][..]);
+        assert_ulps_eq!(&scaled.values()[1].vertices()[..], &[0.5, 1., 1., 1.][..]);
+        let scaled = autorescale_fuzzy(&v, true);
+        assert_ulps_eq!(&scaled.values()[0].vertices()[..], &[0.5, 1., 1., 1.][..]);
+        assert_ulps_eq!(&scaled.values()[1].vertices()[..], &[0., 0., 0., 0.5][..]);
+    }
+
+    #[test]
+    fn autorescale_fuzzy_all_values_the_same() {
+        let v = FuzzyValues::try_from(vec![FuzzyNumber::crisp(3.).unwrap()]).unwrap();
+        let scaled = autorescale_fuzzy(&v, true);
+        assert_eq!(scaled.values()[0].vertices(), [0., 0., 0., 0.]);
+    }
 }
@@ -1,6 +1,8 @@
 //! This module defines the traites for the vectorized variables.
 use super::Name;
 use super::Values;
+use super::fuzzyvalues::FuzzyValues;
+use super::intervalvalues::IntervalValues;
 
 pub trait VariableProperties: std::fmt::Debug {
     fn name(&self) -> &Name;
@@ -8,4 +10,123 @@ pub trait VariableProperties: std::fmt::Debug {
     fn values(&self) -> &Values;
 
     fn rescale(&self) -> Values;
+
+    /// The lower and the upper bounds of the rescaling: the values rescaled to the ends of the scale. By default the
+    /// lowest and the highest values: the kinds of variables with other bounds override it.
+    fn bounds(&self) -> (f64, f64) {
+        let values = self.values().values();
+        let lowest = values.fold(f64::INFINITY, |a, &b| a.min(b));
+        let highest = values.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        (lowest, highest)
+    }
+
+    /// Whether the higher the raw value, the better: the direction of the rescaling. By default it comes from the
+    /// rescaling itself, which maps the highest raw value closer to 0 (the best) than the lowest one.
+    fn higher_is_better(&self) -> bool {
+        let raw = self.values().values();
+        let rescaled = self.rescale();
+        let rescaled = rescaled.values();
+        let (mut lowest, mut highest) = (0, 0);
+        for (i, &x) in raw.iter().enumerate() {
+            if x < raw[lowest] {
+                lowest = i;
+            }
+            if x > raw[highest] {
+                highest = i;
+            }
+        }
+        rescaled[highest] < rescaled[lowest]
+    }
+
+    /// A new variable of the same kind and name, with other values. By default `None`: the variable cannot be rebuilt,
+    /// and the methods that change the values of a problem reject it.
+    fn with_values(&self, _values: Values) -> Option<Box<dyn VariableProperties>> {
+        None
+    }
+}
+
+pub trait IntervalVariableProperties: std::fmt::Debug {
+    fn name(&self) -> &Name;
+
+    fn values(&self) -> &IntervalValues;
+
+    fn rescale(&self) -> IntervalValues;
+}
+
+pub trait FuzzyVariableProperties: std::fmt::Debug {
+    fn name(&self) -> &Name;
+
+    fn values(&self) -> &FuzzyValues;
+
+    fn rescale(&self) -> FuzzyValues;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    /// A variable defined outside of the crate, with only the required methods.
+    #[derive(Debug)]
+    struct Score {
+        name: Name,
+        values: Values,
+    }
+
+    impl VariableProperties for Score {
+        fn name(&self) -> &Name {
+            &self.name
+        }
+
+        fn values(&self) -> &Values {
+            &self.values
+        }
+
+        fn rescale(&self) -> Values {
+            let max = self
+                .values
+                .values()
+                .fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+            Values::try_from(self.values.values().mapv(|x| (max - x) / max)).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_default_higher_is_better() {
+        let score = Score {
+            name: Name::try_from("score").unwrap(),
+            values: Values::try_from(array![2., 4., 1.]).unwrap(),
+        };
+        assert!(score.higher_is_better());
+        // a constant variable has no direction
+        let constant = Score {
+            name: Name::try_from("score").unwrap(),
+            values: Values::try_from(array![2., 2.]).unwrap(),
+        };
+        assert!(!constant.higher_is_better());
+    }
+
+    #[test]
+    fn test_default_with_values() {
+        let score = Score {
+            name: Name::try_from("score").unwrap(),
+            values: Values::try_from(array![1., 2., 4.]).unwrap(),
+        };
+        // the scaling of the variable is unknown, so it is not replaced with another one
+        assert!(
+            score
+                .with_values(Values::try_from(array![2., 4.]).unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_default_bounds() {
+        let score = Score {
+            name: Name::try_from("score").unwrap(),
+            values: Values::try_from(array![2., 4., 1.]).unwrap(),
+        };
+        assert_eq!(score.bounds(), (1., 4.));
+    }
 }
@@ -0,0 +1,103 @@
+//! This module defines the uncertain values of a variable: each value follows a probability distribution.
+use super::Values;
+use super::distribution::Distribution;
+use ndarray::Array1;
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+pub struct UncertainValues(Vec<Distribution>);
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum UncertainValuesError {
+    #[error("The values cannot be empty")]
+    Empty,
+}
+
+impl UncertainValues {
+    pub fn values(&self) -> &[Distribution] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // NOTE: always false, values are never empty by construction
+        self.0.is_empty()
+    }
+
+    /// The expected value of each distribution.
+    pub fn expected(&self) -> Values {
+        let means: Array1<f64> = self.0.iter().map(|d| d.mean()).collect();
+        Values::try_from(means).unwrap()
+    }
+
+    pub(crate) fn sample<R: Rng>(&self, rng: &mut R) -> Values {
+        let sample: Array1<f64> = self.0.iter().map(|d| d.sample(rng)).collect();
+        Values::try_from(sample).unwrap()
+    }
+}
+
+impl TryFrom<Vec<Distribution>> for UncertainValues {
+    type Error = UncertainValuesError;
+
+    fn try_from(values: Vec<Distribution>) -> Result<Self, Self::Error> {
+        if values.is_empty() {
+            return Err(UncertainValuesError::Empty);
+        }
+        Ok(UncertainValues(values))
+    }
+}
+
+impl From<Values> for UncertainValues {
+    fn from(values: Values) -> Self {
+        // Values are always finite, so the conversion cannot fail.
+        UncertainValues(
+            values
+                .values()
+                .iter()
+                .map(|&x| Distribution::exact(x).unwrap())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_try_from_distributions() {
+        let v = UncertainValues::try_from(vec![
+            Distribution::uniform(1., 3.).unwrap(),
+            Distribution::exact(5.).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(v.len(), 2);
+        assert_ulps_eq!(v.expected().values(), array![2., 5.]);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let sample = v.sample(&mut rng);
+        assert!((1. ..=3.).contains(&sample.values()[0]));
+        assert_ulps_eq!(sample.values()[1], 5.);
+    }
+
+    #[test]
+    fn test_from_exact_values() {
+        let v = UncertainValues::from(Values::try_from(array![1., 2.]).unwrap());
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(v.sample(&mut rng).values(), array![1., 2.]);
+    }
+
+    #[test]
+    fn test_try_from_empty_failure() {
+        let err = UncertainValues::try_from(vec![]).unwrap_err();
+        assert_eq!(err, UncertainValuesError::Empty);
+    }
+}
@@ -0,0 +1,90 @@
+//! This module implements the uncertain variables: each realization of their values is a regular variable.
+use super::Name;
+use super::UncertainValues;
+use super::Values;
+use super::VariableAutoscale;
+use super::VariableInvertedAutoscale;
+use super::VariableProperties;
+use rand::Rng;
+
+/// Builds the variable of a single realization of the uncertain values.
+pub type VariableBuilder = fn(Name, Values) -> Box<dyn VariableProperties>;
+
+#[derive(Debug)]
+pub struct UncertainVariable {
+    name: Name,
+    values: UncertainValues,
+    builder: VariableBuilder,
+}
+
+impl UncertainVariable {
+    pub fn new(name: Name, values: UncertainValues, builder: VariableBuilder) -> Self {
+        Self {
+            name,
+            values,
+            builder,
+        }
+    }
+
+    /// Each realization is a `VariableAutoscale`.
+    pub fn autoscale(name: Name, values: UncertainValues) -> Self {
+        UncertainVariable::new(name, values, |name, values| {
+            Box::new(VariableAutoscale::new(name, values))
+        })
+    }
+
+    /// Each realization is a `VariableInvertedAutoscale`.
+    pub fn inverted_autoscale(name: Name, values: UncertainValues) -> Self {
+        UncertainVariable::new(name, values, |name, values| {
+            Box::new(VariableInvertedAutoscale::new(name, values))
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn values(&self) -> &UncertainValues {
+        &self.values
+    }
+
+    /// The variable with the expected values.
+    pub fn expected(&self) -> Box<dyn VariableProperties> {
+        (self.builder)(self.name.clone(), self.values.expected())
+    }
+
+    pub(crate) fn sample<R: Rng>(&self, rng: &mut R) -> Box<dyn VariableProperties> {
+        (self.builder)(self.name.clone(), self.values.sample(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::Distribution;
+    use approx::assert_ulps_eq;
+    use ndarray::array;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn create_variable_and_sample() {
+        let var = UncertainVariable::inverted_autoscale(
+            Name::try_from("x").unwrap(),
+            UncertainValues::try_from(vec![
+                Distribution::uniform(0., 1.).unwrap(),
+                Distribution::uniform(2., 3.).unwrap(),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(var.name(), "x");
+        assert_ulps_eq!(var.expected().values().values(), array![0.5, 2.5]);
+        assert_ulps_eq!(var.expected().rescale().values(), array![1., 0.]);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let sample = var.sample(&mut rng);
+        assert_eq!(sample.name(), "x");
+        assert_ulps_eq!(sample.rescale().values(), array![1., 0.]);
+    }
+}
@@ -13,7 +13,7 @@ pub enum ValuesError {
 }
 
 impl Values {
-    pub fn values(&self) -> ArrayView1<f64> {
+    pub fn values(&self) -> ArrayView1<'_, f64> {
         self.0.view()
     }
 }
@@ -28,13 +28,21 @@ impl VariableProperties for VariableAutoscale {
     fn rescale(&self) -> Values {
         autorescale_vector(self.values(), false)
     }
+
+    fn higher_is_better(&self) -> bool {
+        false
+    }
+
+    fn with_values(&self, values: Values) -> Option<Box<dyn VariableProperties>> {
+        Some(Box::new(VariableAutoscale::new(self.name.clone(), values)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use approx::assert_ulps_eq;
-    use ndarray::array;
+    use ndarray::{array, s};
     use std::convert::TryFrom;
 
     #[test]
@@ -45,6 +53,7 @@ mod tests {
         );
         assert_eq!(var.name(), "x");
         assert_eq!(var.rescale().values(), array![0.]);
+        assert!(!var.higher_is_better());
     }
 
     #[test]
@@ -59,4 +68,20 @@ mod tests {
             array![0., 0.3333333333333333, 0.6666666666666666, 1.]
         );
     }
+
+    #[test]
+    fn create_variable_with_other_values() {
+        let var = VariableAutoscale::new(
+            Name::try_from("x").unwrap(),
+            Values::try_from(array![0., 1.]).unwrap(),
+        );
+        let other = var
+            .with_values(Values::try_from(array![1., 0.]).unwrap())
+            .unwrap();
+        assert_eq!(other.name(), "x");
+        assert_eq!(
+            other.rescale().values(),
+            var.rescale().values().slice(s![..;-1])
+        );
+    }
 }